@@ -0,0 +1,250 @@
+//! compare: fuzzy equality of floating-point values, shared by the rand, complex and vector crates
+//!
+//! Two values are close if they are equal or within any one of the tolerances: an absolute difference (for values
+//! near zero), a difference relative to the larger magnitude (so the comparison is symmetric and works across zero),
+//! or a number of units in the last place. An infinity is only close to the same infinity, and a NaN is close to
+//! nothing unless the tolerance allows NaNs to compare equal.
+//!
+//! ```
+//! use compare::{Tolerance, assert_close, assert_all_close};
+//!
+//! assert!(Tolerance::new().close(0.1 + 0.2, 0.3));
+//! assert_close!(1.0 - 1e-12, 1.0, rel = 1e-10);
+//! assert_close!(1e-20, 0.0, abs = 1e-15);
+//! assert_all_close!([0.1 + 0.2, 1.0], [0.3, 1.0], ulps = 1);
+//! ```
+
+use std::fmt;
+
+/// Absolute, relative and ULP tolerances, and the NaN policy. The default is a relative tolerance of machine epsilon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+  abs: f64,
+  rel: f64,
+  ulps: u64,
+  nan_equal: bool
+}
+
+impl Default for Tolerance {
+  fn default() -> Self {
+    Tolerance{abs: 0.0, rel: f64::EPSILON, ulps: 0, nan_equal: false}
+  }
+}
+
+impl Tolerance {
+  pub fn new() -> Tolerance {
+    Tolerance::default()
+  }
+
+  /// Exact equality, to which tolerances can be added
+  pub fn exact() -> Tolerance {
+    Tolerance{rel: 0.0, ..Tolerance::default()}
+  }
+
+  pub fn abs(self, abs: f64) -> Tolerance {
+    assert!(abs >= 0.0, "tolerance must be non-negative");
+    Tolerance{abs, ..self}
+  }
+
+  pub fn rel(self, rel: f64) -> Tolerance {
+    assert!(rel >= 0.0, "tolerance must be non-negative");
+    Tolerance{rel, ..self}
+  }
+
+  pub fn ulps(self, ulps: u64) -> Tolerance {
+    Tolerance{ulps, ..self}
+  }
+
+  /// Whether a NaN is close to another NaN (it is never close to a number)
+  pub fn nan_equal(self, nan_equal: bool) -> Tolerance {
+    Tolerance{nan_equal, ..self}
+  }
+
+  pub fn close(&self, x: f64, y: f64) -> bool {
+    if x.is_nan() || y.is_nan() {
+      return self.nan_equal && x.is_nan() && y.is_nan();
+    }
+    if x == y {
+      return true;
+    }
+    if x.is_infinite() || y.is_infinite() {
+      return false;
+    }
+    let diff = (x - y).abs();
+    diff <= self.abs || diff <= self.rel * x.abs().max(y.abs()) || ulps(x, y) <= self.ulps
+  }
+
+  /// Compare slices element-wise, reporting every mismatch
+  pub fn compare(&self, expected: &[f64], actual: &[f64]) -> Result<(), Report> {
+    let mismatches: Vec<Mismatch> = expected.iter().zip(actual).enumerate()
+      .filter(|(_, (&e, &a))| !self.close(e, a))
+      .map(|(index, (&expected, &actual))| Mismatch{index, expected, actual})
+      .collect();
+    if mismatches.is_empty() && expected.len() == actual.len() {
+      Ok(())
+    } else {
+      Err(Report{lengths: (expected.len(), actual.len()), mismatches})
+    }
+  }
+}
+
+/// The number of representable doubles from x to y, which is 0 for +0 and -0 and u64::MAX if either is NaN
+pub fn ulps(x: f64, y: f64) -> u64 {
+  if x.is_nan() || y.is_nan() {
+    return u64::MAX;
+  }
+  // map the bits to integers ordered like the floats, with both zeros at 0
+  fn ordered(x: f64) -> i128 {
+    let bits = x.to_bits() as i64;
+    (if bits < 0 { i64::MIN - bits } else { bits }) as i128
+  }
+  (ordered(x) - ordered(y)).unsigned_abs().min(u64::MAX as u128) as u64
+}
+
+/// A pair of values that aren't close
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mismatch {
+  pub index: usize,
+  pub expected: f64,
+  pub actual: f64
+}
+
+/// The mismatches found comparing two slices, and their lengths
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+  pub lengths: (usize, usize),
+  pub mismatches: Vec<Mismatch>
+}
+
+impl Report {
+  // the most mismatches displayed
+  const MAX_LINES: usize = 10;
+}
+
+impl fmt::Display for Report {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (n, m) = self.lengths;
+    if n != m {
+      writeln!(f, "expected {} values, got {}", n, m)?;
+    }
+    if !self.mismatches.is_empty() {
+      writeln!(f, "{} of {} values differ:", self.mismatches.len(), n.min(m))?;
+    }
+    for d in self.mismatches.iter().take(Report::MAX_LINES) {
+      writeln!(f, "  [{}] expected {:?}, got {:?} (diff {:e}, {} ulps)", d.index, d.expected, d.actual,
+               (d.expected - d.actual).abs(), ulps(d.expected, d.actual))?;
+    }
+    if self.mismatches.len() > Report::MAX_LINES {
+      writeln!(f, "  ... and {} more", self.mismatches.len() - Report::MAX_LINES)?;
+    }
+    Ok(())
+  }
+}
+
+/// Assert that two f64s are close, by default to within machine epsilon relative. Tolerances are given by name, as
+/// `assert_close!(x, y, abs = 1e-12, ulps = 4)`.
+#[macro_export]
+macro_rules! assert_close {
+  ($x:expr, $y:expr $(, $k:ident = $v:expr)* $(,)?) => {{
+    let (x, y): (f64, f64) = ($x, $y);
+    let tolerance = $crate::Tolerance::new()$(.$k($v))*;
+    if !tolerance.close(x, y) {
+      panic!("assertion failed: `{}` is not close to `{}`\n  left: {:?}\n right: {:?}\n  diff: {:e} ({} ulps)\n  \
+              with: {:?}", stringify!($x), stringify!($y), x, y, (x - y).abs(), $crate::ulps(x, y), tolerance);
+    }
+  }};
+}
+
+/// Assert that two sequences of f64 are the same length and close element-wise, taking tolerances like `assert_close`.
+/// The message lists the mismatches.
+#[macro_export]
+macro_rules! assert_all_close {
+  ($x:expr, $y:expr $(, $k:ident = $v:expr)* $(,)?) => {{
+    let tolerance = $crate::Tolerance::new()$(.$k($v))*;
+    if let Err(report) = tolerance.compare(&$x, &$y) {
+      panic!("assertion failed: `{}` is not close to `{}`\n{}", stringify!($x), stringify!($y), report);
+    }
+  }};
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn tolerances() {
+    let t = Tolerance::new();
+    assert!(t.close(1.0, 1.0 + f64::EPSILON));
+    assert!(!t.close(1.0, 1.0 + 2.0 * f64::EPSILON));
+    // relative to the larger value, so symmetric
+    assert!(t.close(2.0 - 2.0 * f64::EPSILON, 2.0) && t.close(2.0, 2.0 - 2.0 * f64::EPSILON));
+    // relative tolerance alone can't compare with zero
+    assert!(!t.close(1e-300, 0.0));
+    assert!(t.abs(1e-300).close(1e-300, 0.0));
+    assert!(Tolerance::exact().close(0.0, -0.0));
+    assert!(!Tolerance::exact().close(1.0, 1.0 + f64::EPSILON));
+    assert!(Tolerance::exact().ulps(1).close(1.0, 1.0 + f64::EPSILON));
+    assert!(Tolerance::exact().ulps(2).close(5e-324, -5e-324));
+    assert!(!Tolerance::exact().ulps(1).close(5e-324, -5e-324));
+    assert!(t.rel(0.1).close(-1.0, -1.05));
+    assert!(!t.rel(0.1).close(-1.0, 1.0));
+  }
+
+  #[test]
+  fn nan_inf() {
+    let t = Tolerance::new().abs(f64::MAX);
+    assert!(t.close(f64::INFINITY, f64::INFINITY));
+    assert!(!t.close(f64::INFINITY, f64::MAX));
+    assert!(!t.close(f64::INFINITY, f64::NEG_INFINITY));
+    assert!(!t.close(f64::NAN, f64::NAN));
+    assert!(t.nan_equal(true).close(f64::NAN, f64::NAN));
+    assert!(!t.nan_equal(true).close(f64::NAN, 0.0));
+  }
+
+  #[test]
+  fn ulp_distance() {
+    assert_eq!(ulps(1.0, 1.0), 0);
+    assert_eq!(ulps(0.0, -0.0), 0);
+    assert_eq!(ulps(1.0, 1.0 + f64::EPSILON), 1);
+    assert_eq!(ulps(1.0 - f64::EPSILON / 2.0, 1.0 + f64::EPSILON), 2);
+    assert_eq!(ulps(-5e-324, 5e-324), 2);
+    assert_eq!(ulps(f64::MAX, f64::INFINITY), 1);
+    assert_eq!(ulps(f64::NEG_INFINITY, f64::INFINITY), 2 * f64::INFINITY.to_bits());
+    assert_eq!(ulps(f64::NAN, 1.0), u64::MAX);
+  }
+
+  #[test]
+  fn report() {
+    let t = Tolerance::new();
+    assert_eq!(t.compare(&[1.0, 2.0], &[1.0, 2.0]), Ok(()));
+    let r = t.compare(&[1.0, 2.0, 3.0], &[1.0, 2.5]).unwrap_err();
+    assert_eq!(r.lengths, (3, 2));
+    assert_eq!(r.mismatches, [Mismatch{index: 1, expected: 2.0, actual: 2.5}]);
+    assert_eq!(r.to_string(), "expected 3 values, got 2\n\
+                               1 of 2 values differ:\n\
+                               \x20 [1] expected 2.0, got 2.5 (diff 5e-1, 1125899906842624 ulps)\n");
+    let r = t.compare(&[0.0; 12], &[1.0; 12]).unwrap_err();
+    assert!(r.to_string().ends_with("  ... and 2 more\n"));
+  }
+
+  #[test]
+  fn macros() {
+    assert_close!(0.1 + 0.2, 0.3);
+    assert_close!(1.0, 1.001, rel = 1e-3);
+    assert_close!(1.0, 1.001, abs = 1e-3, ulps = 1,);
+    assert_all_close!((1..4).map(|i| 0.1 * i as f64).collect::<Vec<_>>(), [0.1, 0.2, 0.3], ulps = 1);
+    assert_all_close!([f64::NAN], [f64::NAN], nan_equal = true);
+  }
+
+  #[test]
+  #[should_panic(expected = "is not close to")]
+  fn assert_close_fails() {
+    assert_close!(1.0, 1.001, rel = 1e-4);
+  }
+
+  #[test]
+  #[should_panic(expected = "[0] expected NaN, got NaN")]
+  fn assert_all_close_fails() {
+    assert_all_close!([f64::NAN], [f64::NAN]);
+  }
+}
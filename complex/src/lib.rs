@@ -1,6 +1,7 @@
 use std::ops::{Add, Div, Mul, Neg, Sub};
 use std::fmt;
 use num_traits::Float;
+use compare::Tolerance;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Cplx<T>
@@ -217,27 +218,10 @@ where
   }
 }
 
-// relative comparision switching to absolute below the tolerance threshhold
-// symmetric so compare(x,y) always same as compare(y,x)
-// handles cases where x and y are not same sign
-pub fn compare(lhs: f64, rhs: f64, reltol: Option<f64>) -> bool {
-  let eps = match reltol {
-    Some(x) if x > 0.0 => x,
-    Some(_) => panic!("compare tolerance must be strictly greater than zero"),
-    None => std::f64::EPSILON
-  };
-
-  let diff = (lhs - rhs).abs();
-  let mid = 0.5 * (lhs + rhs).abs();
-
-  match mid {
-    z if z < eps => diff < eps,
-    _ => diff < eps * mid
-  }
-}
-
+// parts equal to within machine epsilon, relative or (for parts near zero) absolute
 pub fn compare_z(z0: Cplx<f64>, z1: Cplx<f64>) -> bool {
-  compare(z0.r, z1.r, None) && compare(z0.i, z1.i, None)
+  let tolerance = Tolerance::new().abs(f64::EPSILON);
+  tolerance.close(z0.r, z1.r) && tolerance.close(z0.i, z1.i)
 }
 
 
@@ -2,9 +2,9 @@ extern crate num;
 use num::traits::{One, Zero};
 use num::{Bounded, Float, Integer, Signed};
 
-use core::ops::Add;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-#[derive(Debug, PartialEq)] // PartialEq required to test error values
+#[derive(Copy, Clone, Debug, PartialEq)] // PartialEq required to test error values
 pub enum NumericalError {
   // hardware FP exceptions
   DivZero,
@@ -33,21 +33,31 @@ pub enum Number<T> where T: Into<f64> + Float + Copy,
   R(T),
   C { r: T, i: T },
   Inf(bool), // use T's inf...otherwise cant resolve type of T. how do we resolve x-Inf?
+  // the result of an invalid operation, e.g. Inf - Inf, which (like a floating-point NaN) propagates through arithmetic
+  NaN(NumericalError),
 }
 
 
 impl<T> Number<T> where T: Into<f64> + Float + Copy,
 {
+  // infinities and NaNs map to Inf and NaN
   pub fn from_real(x: T) -> Number<T> {
-    // TODO check for inf...
-    // match x {
-    //   _ => Number::R(x)
-    // }
-    Number::R(x)
+    match x {
+      x if x.is_nan() => Number::NaN(NumericalError::InvalidOp),
+      x if x.is_infinite() => Number::Inf(x < T::zero()),
+      _ => Number::R(x)
+    }
   }
 
+  // a zero imaginary part demotes to R, an infinite real part (with a finite imaginary part) is Inf, and an infinite
+  // imaginary part isn't representable
   pub fn from_complex(r: T, i: T) -> Number<T> {
-    Number::C{r, i}
+    match (r, i) {
+      (r, i) if r.is_nan() || i.is_nan() => Number::NaN(NumericalError::InvalidOp),
+      (_, i) if i.is_infinite() => Number::NaN(NumericalError::Infinite),
+      (r, i) if i == T::zero() || r.is_infinite() => Number::from_real(r),
+      _ => Number::C{r, i}
+    }
   }
 
   pub fn from_inf(is_neg: bool) -> Number<T> {
@@ -68,26 +78,157 @@ where
       (Number::C { r: ref ra, i: ref ia }, Number::R(ref b)) => ra == b && &T::zero() == ia,
       (Number::C { r: ref ra, i: ref ia }, Number::C { r: ref rb, i: ref ib }) => ra == rb && ia == ib,
       (Number::Inf(ref a), Number::Inf(ref b)) => a == b,
+      (Number::NaN(ref a), Number::NaN(ref b)) => a == b,
       _ => false,
     }
   }
 }
 
 
+// Inf is a real infinity: adding anything finite leaves it unchanged, and multiplying (or dividing) it by a nonzero
+// real sets its sign. The indeterminate forms Inf - Inf, 0 * Inf, Inf / Inf and 0 / 0 are NaN(InvalidOp), division
+// of anything else by zero is NaN(DivZero), and results that would be a non-real infinity are NaN(Infinite).
 impl<T> Add<Number<T>> for Number<T> where T: Into<f64> + Float + Copy {
-	type Output = Number<T>;
-	fn add(self, rhs: Number<T>) -> Number<T> {
-		match (self, rhs) {
-      (Number::R(a), Number::R(b)) => Number::R(a+b),
-      (Number::R(a), Number::C{r:rb, i:ib}) => Number::C{r:a+rb, i:ib},
-      (Number::C{r:ra, i:ia}, Number::R(b)) => Number::C{r:ra+b, i:ia},
-      (Number::C{r:ra, i:ia}, Number::C{r:rb, i:ib}) => Number::C{r:ra+rb, i:ia+ib},
-      _ => Number::Inf(false) // problem: potentially indeterminate
+  type Output = Number<T>;
+  fn add(self, rhs: Number<T>) -> Number<T> {
+    match (self, rhs) {
+      (Number::NaN(e), _) | (_, Number::NaN(e)) => Number::NaN(e),
+      (Number::Inf(a), Number::Inf(b)) if a == b => Number::Inf(a),
+      (Number::Inf(_), Number::Inf(_)) => Number::NaN(NumericalError::InvalidOp),
+      (Number::Inf(a), _) | (_, Number::Inf(a)) => Number::Inf(a),
+      (Number::R(a), Number::R(b)) => Number::from_real(a + b),
+      (a, b) => Number::from_complex(a.re() + b.re(), a.im() + b.im()),
+    }
+  }
+}
+
+impl<T> Neg for Number<T> where T: Into<f64> + Float + Copy {
+  type Output = Number<T>;
+  fn neg(self) -> Number<T> {
+    match self {
+      Number::R(x) => Number::R(-x),
+      Number::C { r, i } => Number::C { r: -r, i: -i },
+      Number::Inf(is_neg) => Number::Inf(!is_neg),
+      Number::NaN(e) => Number::NaN(e),
+    }
+  }
+}
+
+impl<T> Sub<Number<T>> for Number<T> where T: Into<f64> + Float + Copy {
+  type Output = Number<T>;
+  fn sub(self, rhs: Number<T>) -> Number<T> {
+    self + -rhs
+  }
+}
+
+impl<T> Mul<Number<T>> for Number<T> where T: Into<f64> + Float + Copy {
+  type Output = Number<T>;
+  fn mul(self, rhs: Number<T>) -> Number<T> {
+    match (self, rhs) {
+      (Number::NaN(e), _) | (_, Number::NaN(e)) => Number::NaN(e),
+      (Number::Inf(a), Number::Inf(b)) => Number::Inf(a != b),
+      (Number::Inf(is_neg), x) | (x, Number::Inf(is_neg)) => x.scale_inf(is_neg),
+      (Number::R(a), Number::R(b)) => Number::from_real(a * b),
+      (a, b) => {
+        let (ra, ia, rb, ib) = (a.re(), a.im(), b.re(), b.im());
+        Number::from_complex(ra * rb - ia * ib, ra * ib + ia * rb)
+      }
     }
-	}
+  }
 }
 
+impl<T> Div<Number<T>> for Number<T> where T: Into<f64> + Float + Copy {
+  type Output = Number<T>;
+  fn div(self, rhs: Number<T>) -> Number<T> {
+    match (self, rhs) {
+      (Number::NaN(e), _) | (_, Number::NaN(e)) => Number::NaN(e),
+      (Number::Inf(_), Number::Inf(_)) => Number::NaN(NumericalError::InvalidOp),
+      (x, y) if y.is_zero() && x.is_zero() => Number::NaN(NumericalError::InvalidOp),
+      (_, y) if y.is_zero() => Number::NaN(NumericalError::DivZero),
+      (Number::Inf(is_neg), y) => y.scale_inf(is_neg),
+      (_, Number::Inf(_)) => Number::R(T::zero()),
+      (Number::R(a), Number::R(b)) => Number::from_real(a / b),
+      (a, b) => {
+        let (ra, ia, rb, ib) = (a.re(), a.im(), b.re(), b.im());
+        // Smith's algorithm, scaling by the larger part of the divisor so that rb^2 + ib^2 can't overflow or underflow
+        if rb.abs() >= ib.abs() {
+          let t = ib / rb;
+          let d = rb + ib * t;
+          Number::from_complex((ra + ia * t) / d, (ia - ra * t) / d)
+        } else {
+          let t = rb / ib;
+          let d = rb * t + ib;
+          Number::from_complex((ra * t + ia) / d, (ia * t - ra) / d)
+        }
+      }
+    }
+  }
+}
+
+// the operators with a bare T on either side (T on the left only for f32 and f64, as the orphan rule doesn't allow a
+// generic T), and the assignment operators
+macro_rules! impl_ops {
+  ($($op:ident, $f:ident, $op_assign:ident, $f_assign:ident;)*) => {
+    $(
+      impl<T> $op<T> for Number<T> where T: Into<f64> + Float + Copy {
+        type Output = Number<T>;
+        fn $f(self, rhs: T) -> Number<T> {
+          self.$f(Number::from_real(rhs))
+        }
+      }
+
+      impl<T> $op_assign<Number<T>> for Number<T> where T: Into<f64> + Float + Copy {
+        fn $f_assign(&mut self, rhs: Number<T>) {
+          *self = self.$f(rhs);
+        }
+      }
+
+      impl<T> $op_assign<T> for Number<T> where T: Into<f64> + Float + Copy {
+        fn $f_assign(&mut self, rhs: T) {
+          *self = self.$f(rhs);
+        }
+      }
+
+      impl $op<Number<f32>> for f32 {
+        type Output = Number<f32>;
+        fn $f(self, rhs: Number<f32>) -> Number<f32> {
+          Number::from_real(self).$f(rhs)
+        }
+      }
+
+      impl $op<Number<f64>> for f64 {
+        type Output = Number<f64>;
+        fn $f(self, rhs: Number<f64>) -> Number<f64> {
+          Number::from_real(self).$f(rhs)
+        }
+      }
+    )*
+  };
+}
 
+impl_ops!(Add, add, AddAssign, add_assign;
+          Sub, sub, SubAssign, sub_assign;
+          Mul, mul, MulAssign, mul_assign;
+          Div, div, DivAssign, div_assign;);
+
+impl<T> Number<T> where T: Into<f64> + Float + Copy {
+  fn is_zero(self) -> bool {
+    match self {
+      Number::R(x) => x == T::zero(),
+      Number::C { r, i } => r == T::zero() && i == T::zero(),
+      _ => false,
+    }
+  }
+
+  // Inf times (or divided by) this finite nonzero value
+  fn scale_inf(self, is_neg: bool) -> Number<T> {
+    match self {
+      x if x.is_zero() => Number::NaN(NumericalError::InvalidOp),
+      x if x.im() != T::zero() => Number::NaN(NumericalError::Infinite),
+      x => Number::Inf(is_neg != (x.re() < T::zero())),
+    }
+  }
+}
 
 impl<T> Number<T>
 where
@@ -98,6 +239,7 @@ where
   pub fn R(self) -> Result<T, NumericalError> {
     match self {
       Number::R(x) => Ok(x),
+      Number::NaN(e) => Err(e),
       _ => Err(NumericalError::NotRealNumber),
     }
   }
@@ -108,7 +250,8 @@ where
     match self {
       Number::R(x) => Ok((x, T::zero())),
       Number::C { r, i } => Ok((r, i)),
-      _ => Err(NumericalError::Infinite),
+      Number::Inf(_) => Err(NumericalError::Infinite),
+      Number::NaN(e) => Err(e),
     }
   }
 
@@ -117,6 +260,7 @@ where
       Number::R(val) => val,
       Number::C { r, i: _ } => r,
       Number::Inf(_) => panic!("infinite!"),
+      Number::NaN(e) => panic!("{}!", e),
     }
   }
 
@@ -125,6 +269,7 @@ where
       Number::R(_) => T::zero(),
       Number::C { r: _, i } => i,
       Number::Inf(_) => panic!("infinite!"),
+      Number::NaN(e) => panic!("{}!", e),
     }
   }
 }
@@ -156,17 +301,12 @@ pub fn ln(x: f64) -> Number<f64> {
       r: (-x).ln(),
       i: std::f64::consts::PI,
     },
-    x if x == 0.0 => Number::Inf(true),
+    0.0 => Number::Inf(true),
     _ => Number::R(x.ln()),
   }
 }
 
-// impl Div for Number<T> {
-//   fn div()
-// }
-
 #[cfg(test)]
-
 mod tests {
   use super::Number::*;
   use super::*;
@@ -175,7 +315,7 @@ mod tests {
     // f(x,y) = sqrt(x)/y
     match (x, y) {
       (x, _) if x < 0.0 => Err(NumericalError::InvalidOp),
-      (_, y) if y == 0.0 => Err(NumericalError::DivZero),
+      (_, 0.0) => Err(NumericalError::DivZero),
       (_, y) if y.abs() < 1.0e-300 => Err(NumericalError::Overflow),
       (x, y) => Ok(x.sqrt() / y),
     }
@@ -239,11 +379,118 @@ mod tests {
     let x: f64 = ln(0.1).re();
     assert_eq!(x, 0.1f64.ln());
     assert!(ln(x).R().is_err());
-    assert!(!ln(x).C().is_err());
+    assert!(ln(x).C().is_ok());
 
     assert_eq!(f(0.0, 1.0), Ok(0.0));
     assert_eq!(f(-1.0, 1.0), Err(NumericalError::InvalidOp));
     assert_eq!(f(1.0, 0.0), Err(NumericalError::DivZero));
     assert_eq!(f(1.0, 1.0e-308), Err(NumericalError::Overflow));
   }
+
+  #[test]
+  fn arithmetic() {
+    let z = C { r: 1.0, i: 2.0 };
+    assert_eq!(R(1.0) + R(2.0), R(3.0));
+    assert_eq!(R(1.0) - z, C { r: 0.0, i: -2.0 });
+    assert_eq!(z - R(1.0), C { r: 0.0, i: 2.0 });
+    assert_eq!(R(2.0) * z, C { r: 2.0, i: 4.0 });
+    assert_eq!(z * z, C { r: -3.0, i: 4.0 });
+    assert_eq!(z / z, R(1.0));
+    assert_eq!(R(5.0) / z, C { r: 1.0, i: -2.0 });
+    assert_eq!(z / R(2.0), C { r: 0.5, i: 1.0 });
+    assert_eq!(-z, C { r: -1.0, i: -2.0 });
+    // demotion to real when the imaginary parts cancel
+    assert_eq!((z + C { r: 1.0, i: -2.0 }).R(), Ok(2.0));
+    assert_eq!((sqrt(-4.0) * sqrt(-4.0)).R(), Ok(-4.0));
+    assert_eq!(Number::from_complex(1.0, 0.0).R(), Ok(1.0));
+    // without overflow or underflow of the divisor's squared modulus
+    assert_eq!(C { r: 1e200, i: 1e200 } / C { r: 1e200, i: 1e200 }, R(1.0));
+    assert_eq!(C { r: 1.0, i: 1.0 } / C { r: 1e-200, i: 1e-200 }, R(1e200));
+    assert_eq!(C { r: 1e200, i: -1e200 } / C { r: 1e-200, i: 1e200 }, C { r: -1.0, i: -1.0 });
+    // overflow
+    assert_eq!(R(f64::MAX) + R(f64::MAX), Inf(false));
+    assert_eq!(R(f64::MAX) * R(-2.0), Inf(true));
+    assert_eq!(C { r: f64::MAX, i: 1.0 } * R(2.0), Inf(false));
+    assert_eq!(C { r: 1.0, i: f64::MAX } * R(2.0), NaN(NumericalError::Infinite));
+  }
+
+  #[test]
+  fn infinities() {
+    let z = C { r: 1.0, i: 2.0 };
+    assert_eq!(Inf(false) + R(1.0), Inf(false));
+    assert_eq!(z - Inf(false), Inf(true));
+    assert_eq!(Inf::<f64>(true) + Inf(true), Inf(true));
+    assert_eq!(Inf::<f64>(true) - Inf(false), Inf(true));
+    assert_eq!(Inf::<f64>(false) - Inf(false), NaN(NumericalError::InvalidOp));
+    assert_eq!(Inf(false) * R(-2.0), Inf(true));
+    assert_eq!(Inf::<f64>(true) * Inf(true), Inf(false));
+    assert_eq!(Inf(false) * R(0.0), NaN(NumericalError::InvalidOp));
+    assert_eq!(Inf(false) * z, NaN(NumericalError::Infinite));
+    assert_eq!(Inf(false) / R(-2.0), Inf(true));
+    assert_eq!(R(2.0) / Inf(true), R(0.0));
+    assert_eq!(z / Inf(true), R(0.0));
+    assert_eq!(Inf::<f64>(false) / Inf(true), NaN(NumericalError::InvalidOp));
+    assert_eq!(R(1.0) / R(0.0), NaN(NumericalError::DivZero));
+    assert_eq!(z / C { r: 0.0, i: 0.0 }, NaN(NumericalError::DivZero));
+    assert_eq!(Inf(false) / R(0.0), NaN(NumericalError::DivZero));
+    assert_eq!(R(0.0) / R(0.0), NaN(NumericalError::InvalidOp));
+    assert_eq!(ln(0.0) * R(2.0), Inf(true));
+    assert_eq!(-ln(0.0), Inf(false));
+    assert_eq!(Number::from_real(f64::INFINITY), Inf(false));
+    assert_eq!(Number::from_real(f64::NAN), NaN(NumericalError::InvalidOp));
+  }
+
+  #[test]
+  fn nan() {
+    let x = Inf::<f64>(false) - Inf(false);
+    // errors propagate, the first one winning
+    assert_eq!(x + R(1.0), x);
+    assert_eq!(R(1.0) * x, x);
+    assert_eq!(-x, x);
+    assert_eq!(x / (R(1.0) / R(0.0)), x);
+    assert_eq!((R(1.0) / R(0.0)) / x, NaN(NumericalError::DivZero));
+    assert_eq!(x.R(), Err(NumericalError::InvalidOp));
+    assert_eq!(x.C(), Err(NumericalError::InvalidOp));
+    assert_eq!(Inf::<f64>(false).C(), Err(NumericalError::Infinite));
+  }
+
+  #[test]
+  #[should_panic(expected = "invalid operation!")]
+  fn nan_re() {
+    (Inf::<f64>(false) * R(0.0)).re();
+  }
+
+  #[test]
+  fn mixed() {
+    let z = C { r: 1.0, i: 2.0 };
+    assert_eq!(z + 1.0, C { r: 2.0, i: 2.0 });
+    assert_eq!(1.0 + z, C { r: 2.0, i: 2.0 });
+    assert_eq!(z - 1.0, C { r: 0.0, i: 2.0 });
+    assert_eq!(1.0 - z, C { r: 0.0, i: -2.0 });
+    assert_eq!(z * 2.0, C { r: 2.0, i: 4.0 });
+    assert_eq!(2.0 * z, C { r: 2.0, i: 4.0 });
+    assert_eq!(z / 2.0, C { r: 0.5, i: 1.0 });
+    assert_eq!(5.0 / z, C { r: 1.0, i: -2.0 });
+    assert_eq!(Inf(false) * -1.0, Inf(true));
+    assert_eq!(R(1.0) / 0.0, NaN(NumericalError::DivZero));
+    assert_eq!(R(1.0) + f64::INFINITY, Inf(false));
+    assert_eq!(2.0f32 * R(1.5f32), R(3.0f32));
+  }
+
+  #[test]
+  fn assign() {
+    let mut x = R(1.0);
+    x += C { r: 1.0, i: 1.0 };
+    assert_eq!(x, C { r: 2.0, i: 1.0 });
+    x -= 2.0;
+    assert_eq!(x, C { r: 0.0, i: 1.0 });
+    x *= x;
+    assert_eq!(x, R(-1.0));
+    x /= 4.0;
+    assert_eq!(x, R(-0.25));
+    x /= R(0.0);
+    assert_eq!(x, NaN(NumericalError::DivZero));
+    x += 1.0;
+    assert_eq!(x, NaN(NumericalError::DivZero));
+  }
 }
@@ -0,0 +1,60 @@
+// Performance baseline for the random streams. Per-value groups time a single call (so report ns/sample); bulk
+// groups report throughput in bytes of output. MT19937 and Sobol are labelled "(ffi)" when built with the
+// ffi-mt19937 and ffi-sobol features, which call the C++ implementations one value (MT19937) or point (Sobol) at a
+// time.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::gen::{Dimensionless, RandomStream, pseudo::*, quasi::*, entropy::*, chacha::*};
+
+const N: usize = 1 << 16;
+
+#[cfg(not(feature = "ffi-mt19937"))]
+const MT_NAME: &str = "MT19937";
+#[cfg(feature = "ffi-mt19937")]
+const MT_NAME: &str = "MT19937 (ffi)";
+#[cfg(not(feature = "ffi-sobol"))]
+const SOBOL_NAME: &str = "Sobol";
+#[cfg(feature = "ffi-sobol")]
+const SOBOL_NAME: &str = "Sobol (ffi)";
+
+fn per_value(c: &mut Criterion) {
+  let mut group = c.benchmark_group("next_1");
+  let mut lcg = LCG::new(Some(19937));
+  let mut xorshift = Xorshift64::new(Some(19937));
+  let mut mt = MT19937::new(Some(19937));
+  let mut sobol = Sobol::new(1);
+  let mut chacha = ChaCha::new(8);
+  group.bench_function("LCG", |b| b.iter(|| lcg.next_1()));
+  group.bench_function("Xorshift64", |b| b.iter(|| xorshift.next_1()));
+  group.bench_function(MT_NAME, |b| b.iter(|| mt.next_1()));
+  group.bench_function(SOBOL_NAME, |b| b.iter(|| sobol.next_n(1)));
+  group.bench_function("ChaCha8", |b| b.iter(|| chacha.next_1()));
+  group.finish();
+}
+
+fn bulk<R: RandomStream>(c: &mut Criterion, name: &str, mut make: impl FnMut() -> R) {
+  let mut group = c.benchmark_group("next_n");
+  group.throughput(Throughput::Bytes((N * std::mem::size_of::<u32>()) as u64));
+  let mut rng = make();
+  group.bench_function(BenchmarkId::from_parameter(name), |b| b.iter(|| rng.next_n(N)));
+  group.finish();
+
+  let mut group = c.benchmark_group("uniforms01");
+  group.throughput(Throughput::Bytes((N * std::mem::size_of::<f64>()) as u64));
+  let mut rng = make();
+  group.bench_function(BenchmarkId::from_parameter(name), |b| b.iter(|| rng.uniforms01(N)));
+  group.finish();
+}
+
+fn bulk_all(c: &mut Criterion) {
+  bulk(c, "LCG", || LCG::new(Some(19937)));
+  bulk(c, "Xorshift64", || Xorshift64::new(Some(19937)));
+  bulk(c, MT_NAME, || MT19937::new(Some(19937)));
+  bulk(c, SOBOL_NAME, || Sobol::new(1));
+  for &rounds in &[8, 12, 20] {
+    bulk(c, &format!("ChaCha{}", rounds), || ChaCha::new(rounds));
+  }
+  bulk(c, "EntropySource", EntropySource::new);
+}
+
+criterion_group!(benches, per_value, bulk_all);
+criterion_main!(benches);
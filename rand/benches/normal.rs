@@ -0,0 +1,52 @@
+// Performance baseline for the normal transforms, in ns/sample (single variates) and throughput of bulk sampling.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::gen::{pseudo::*, quasi::*};
+use rand::dist::Dist;
+use rand::dist::continuous::Normal;
+use rand::dist::normal::{polar::Polar, acklam::{InverseCumulative, Method}, ziggurat::Ziggurat};
+
+const N: usize = 1 << 16;
+
+// the generator backends, as in the generators benchmark
+#[cfg(not(feature = "ffi-mt19937"))]
+const MT_NAME: &str = "MT19937";
+#[cfg(feature = "ffi-mt19937")]
+const MT_NAME: &str = "MT19937 (ffi)";
+#[cfg(not(feature = "ffi-sobol"))]
+const SOBOL_NAME: &str = "Sobol";
+#[cfg(feature = "ffi-sobol")]
+const SOBOL_NAME: &str = "Sobol (ffi)";
+
+fn per_sample(c: &mut Criterion) {
+  let mut group = c.benchmark_group("normal_1");
+  let mut polar = Polar::<_, f64>::new(Xorshift64::new(Some(19937)));
+  let mut inverse = InverseCumulative::<_, f64>::new(Xorshift64::new(Some(19937)));
+  let mut ziggurat = Ziggurat::<_, f64>::new(Xorshift64::new(Some(19937)));
+  group.bench_function("Polar", |b| b.iter(|| polar.get_n(1)));
+  group.bench_function("InverseCumulative", |b| b.iter(|| inverse.get_n(1)));
+  group.bench_function("Ziggurat", |b| b.iter(|| ziggurat.get_n(1)));
+  group.finish();
+}
+
+fn bulk(c: &mut Criterion) {
+  let mut group = c.benchmark_group("normal_n");
+  group.throughput(Throughput::Bytes((N * std::mem::size_of::<f64>()) as u64));
+  let mut polar = Normal::<Polar<Xorshift64>>::new(0.0, 1.0, Xorshift64::new(Some(19937)));
+  group.bench_function("Polar", |b| b.iter(|| polar.sample_n(N)));
+  for &(name, method, refine) in &[("Acklam+Halley", Method::Acklam, true), ("Acklam", Method::Acklam, false),
+                                   ("Wichura", Method::Wichura, false)] {
+    let mut inverse = InverseCumulative::<_, f64>::with_method(Xorshift64::new(Some(19937)), method, refine);
+    group.bench_function(BenchmarkId::new("InverseCumulative", name), |b| b.iter(|| inverse.get_n(N)));
+  }
+  let mut sobol = InverseCumulative::<_, f64>::new(Sobol::new(1));
+  group.bench_function(BenchmarkId::new("InverseCumulative", SOBOL_NAME), |b| b.iter(|| sobol.get_n(N)));
+  let mut ziggurat = Ziggurat::<_, f64>::new(Xorshift64::new(Some(19937)));
+  group.bench_function("Ziggurat", |b| b.iter(|| ziggurat.get_n(N)));
+  group.bench_function(BenchmarkId::new("Ziggurat", "batched"), |b| b.iter(|| ziggurat.get_n_batched(N)));
+  let mut mt = Ziggurat::<_, f64>::new(MT19937::new(Some(19937)));
+  group.bench_function(BenchmarkId::new("Ziggurat", MT_NAME), |b| b.iter(|| mt.get_n(N)));
+  group.finish();
+}
+
+criterion_group!(benches, per_sample, bulk);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+// Throughput of block generation versus one value per call
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::gen::{Dimensionless, RandomStream, pseudo::Xorshift64};
+use rand::dist::normal::ziggurat::Ziggurat;
+
+const N: usize = 1 << 16;
+
+fn xorshift(c: &mut Criterion) {
+  let mut group = c.benchmark_group("xorshift64");
+  group.throughput(Throughput::Elements(N as u64));
+  let mut rng = Xorshift64::new(Some(19937));
+  group.bench_function("next_1", |b| b.iter(|| (0..N).map(|_| rng.next_1()).collect::<Vec<u32>>()));
+  group.bench_function("next_n", |b| b.iter(|| rng.next_n(N)));
+  group.bench_function("uniform01", |b| b.iter(|| (0..N).map(|_| rng.uniform01()).collect::<Vec<f64>>()));
+  group.bench_function("uniforms01", |b| b.iter(|| rng.uniforms01(N)));
+  group.finish();
+}
+
+fn ziggurat(c: &mut Criterion) {
+  let mut group = c.benchmark_group("ziggurat");
+  group.throughput(Throughput::Elements(N as u64));
+  let mut z64 = Ziggurat::<_, f64>::new(Xorshift64::new(Some(19937)));
+  let mut z32 = Ziggurat::<_, f32>::new(Xorshift64::new(Some(19937)));
+  group.bench_function(BenchmarkId::new("get_n", "f64"), |b| b.iter(|| z64.get_n(N)));
+  group.bench_function(BenchmarkId::new("get_n_batched", "f64"), |b| b.iter(|| z64.get_n_batched(N)));
+  group.bench_function(BenchmarkId::new("get_n", "f32"), |b| b.iter(|| z32.get_n(N)));
+  group.bench_function(BenchmarkId::new("get_n_batched", "f32"), |b| b.iter(|| z32.get_n_batched(N)));
+  group.finish();
+}
+
+criterion_group!(benches, xorshift, ziggurat);
+criterion_main!(benches);
@@ -1,23 +1,19 @@
-// build.rs
-
-use std::process::Command;
+// build.rs: compiles the C/C++ generators selected by the ffi-* features. The default build needs no C++ toolchain.
 
 fn main() {
-  // ensure lib dir exists
-  Command::new("mkdir").args(&["-p", "lib"]).status().unwrap();
-  Command::new("g++").args(&["../src/ext/SobolImpl.cpp", "-c", "-std=c++14", "-g", "-O2", "-fPIC"])
-                     .current_dir("./lib").status().unwrap();
-  Command::new("ar").args(&["crus", "./libsobol.a", "./SobolImpl.o"])
-                    .current_dir("./lib").status().unwrap();
-  Command::new("g++").args(&["../src/ext/MT19937.cpp", "-c", "-std=c++14", "-g", "-O2", "-fPIC"])
-                     .current_dir("./lib").status().unwrap();
-  Command::new("ar").args(&["crus", "./libmt19937.a", "./MT19937.o"])
-                    .current_dir("./lib").status().unwrap();
+  println!("cargo:rerun-if-changed=build.rs");
+  #[cfg(feature = "ffi-mt19937")]
+  compile("mt19937", "src/ext/MT19937.cpp", "ffi-mt19937");
+  #[cfg(feature = "ffi-sobol")]
+  compile("sobol", "src/ext/SobolImpl.cpp", "ffi-sobol");
+}
 
-  let libdir = "./lib";
-  println!("cargo:rustc-link-search=native={}", libdir);
-  println!("cargo:rustc-link-lib=static=mt19937");
-  println!("cargo:rustc-link-lib=static=sobol");
-  // needed for operator new & delete in MT19937.cpp
-  println!("cargo:rustc-link-lib=stdc++");
-}
\ No newline at end of file
+// cc builds the static library in OUT_DIR and emits the directives to link it and the C++ runtime
+#[cfg(any(feature = "ffi-mt19937", feature = "ffi-sobol"))]
+fn compile(lib: &str, src: &str, feature: &str) {
+  println!("cargo:rerun-if-changed={}", src);
+  println!("cargo:rerun-if-changed=src/ext");
+  if let Err(e) = cc::Build::new().cpp(true).std("c++14").file(src).try_compile(lib) {
+    panic!("failed to compile {} for the {} feature: {}\nbuild without it to use the rust implementation", src, feature, e);
+  }
+}
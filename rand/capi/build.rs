@@ -0,0 +1,30 @@
+// build.rs: generates the C header from the extern "C" functions in src/lib.rs
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+  let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+  let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("rand_capi.h");
+
+  let mut config = cbindgen::Config {
+    language: cbindgen::Language::C,
+    include_guard: Some("RAND_CAPI_H".to_string()),
+    header: Some("/* Generated by cbindgen from rand/capi/src/lib.rs: do not edit */".to_string()),
+    cpp_compat: true,
+    documentation: true,
+    usize_is_size_t: true,
+    ..Default::default()
+  };
+  config.enumeration.rename_variants = cbindgen::RenameRule::ScreamingSnakeCase;
+  config.enumeration.prefix_with_name = true;
+  // the enums are passed as uint32_t, so wouldn't otherwise be exported
+  config.export.include = vec!["RandGeneratorKind".to_string(), "RandNormalMethod".to_string()];
+
+  match cbindgen::Builder::new().with_crate(&crate_dir).with_config(config).generate() {
+    Ok(bindings) => { bindings.write_to_file(&out); }
+    Err(e) => panic!("failed to generate the C header: {}", e),
+  }
+  println!("cargo:rerun-if-changed=src/lib.rs");
+  println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,30 @@
+/* Samples normals through the C interface:
+ *   cargo build --release
+ *   cc -Iinclude examples/normals.c target/release/librand_capi.a -lpthread -ldl -lm -o normals
+ */
+#include <stdio.h>
+#include "rand_capi.h"
+
+int main(void) {
+  RandGenerator *gen = NULL;
+  RandDistribution *dist = NULL;
+  double x[1000];
+  double sum = 0.0, sumsq = 0.0;
+
+  if (rand_generator_create(RAND_GENERATOR_KIND_MT19937, 19937, &gen) != RAND_STATUS_OK) {
+    return 1;
+  }
+  /* the distribution takes the generator */
+  if (rand_normal_create(0.0, 1.0, RAND_NORMAL_METHOD_ZIGGURAT, gen, &dist) != RAND_STATUS_OK) {
+    rand_generator_destroy(gen);
+    return 1;
+  }
+  rand_distribution_fill(dist, x, 1000);
+  for (int i = 0; i < 1000; ++i) {
+    sum += x[i];
+    sumsq += x[i] * x[i];
+  }
+  printf("mean %f variance %f\n", sum / 1000, sumsq / 1000 - sum * sum / 1e6);
+  rand_distribution_destroy(dist);
+  return 0;
+}
@@ -0,0 +1,282 @@
+/* Generated by cbindgen from rand/capi/src/lib.rs: do not edit */
+
+#ifndef RAND_CAPI_H
+#define RAND_CAPI_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The outcome of a call
+ */
+typedef enum RandStatus {
+  RAND_STATUS_OK = 0,
+  /**
+   * a required pointer was null
+   */
+  RAND_STATUS_NULL_POINTER = 1,
+  /**
+   * an argument was out of range, e.g. a negative variance, or a rejection method with a Sobol generator
+   */
+  RAND_STATUS_INVALID_ARGUMENT = 2,
+  /**
+   * the operation isn't defined for this generator, e.g. single values from a multidimensional Sobol sequence
+   */
+  RAND_STATUS_UNSUPPORTED = 3,
+  /**
+   * the library panicked; the handle should be destroyed
+   */
+  RAND_STATUS_PANIC = 4,
+} RandStatus;
+
+/**
+ * The generators that take a seed. The value is passed as a `uint32_t`.
+ */
+typedef enum RandGeneratorKind {
+  RAND_GENERATOR_KIND_LCG = 0,
+  RAND_GENERATOR_KIND_XORSHIFT64 = 1,
+  RAND_GENERATOR_KIND_MT19937 = 2,
+  /**
+   * ChaCha with 20 rounds; `rand_chacha_create` gives a choice of rounds and key
+   */
+  RAND_GENERATOR_KIND_CHACHA20 = 3,
+} RandGeneratorKind;
+
+/**
+ * Algorithms for normal variates. The value is passed as a `uint32_t`.
+ */
+typedef enum RandNormalMethod {
+  /**
+   * Acklam's inverse CDF, which uses every uniform and so is the only method suitable for Sobol
+   */
+  RAND_NORMAL_METHOD_INVERSE = 0,
+  /**
+   * Marsaglia's polar method (rejection)
+   */
+  RAND_NORMAL_METHOD_POLAR = 1,
+  /**
+   * Marsaglia's ziggurat (rejection)
+   */
+  RAND_NORMAL_METHOD_ZIGGURAT = 2,
+} RandNormalMethod;
+
+/**
+ * A distribution, sampled using the generator it was created with
+ */
+typedef struct RandDistribution RandDistribution;
+
+/**
+ * A random number generator
+ */
+typedef struct RandGenerator RandGenerator;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a generator of the given `RandGeneratorKind` from a 32-bit seed, which must be nonzero for the LCG and
+ * Xorshift64 generators.
+ *
+ * # Safety
+ *
+ * `out` must be null or valid for writes. The handle written there must be freed with `rand_generator_destroy`.
+ */
+enum RandStatus rand_generator_create(uint32_t kind,
+                                      uint32_t seed,
+                                      struct RandGenerator **out);
+
+/**
+ * Create a generator of the given `RandGeneratorKind` whose whole state is seeded from `len` bytes, which are hashed
+ * by a seed sequence.
+ *
+ * # Safety
+ *
+ * `bytes` must be valid for `len` reads (it may be null if `len` is zero), and `out` null or valid for writes.
+ */
+enum RandStatus rand_generator_create_from_seed_bytes(uint32_t kind,
+                                                      const uint8_t *bytes,
+                                                      size_t len,
+                                                      struct RandGenerator **out);
+
+/**
+ * Create a Sobol sequence generator of dimension `dim` (1 to 1111). Each draw is `dim` values.
+ *
+ * # Safety
+ *
+ * `out` must be null or valid for writes.
+ */
+enum RandStatus rand_sobol_create(uint32_t dim, struct RandGenerator **out);
+
+/**
+ * Create a deterministic ChaCha generator with 8, 12 or 20 `rounds`, a 32-byte `key` and a `stream` id.
+ *
+ * # Safety
+ *
+ * `key` must be valid for 32 reads, and `out` null or valid for writes.
+ */
+enum RandStatus rand_chacha_create(uint32_t rounds,
+                                   const uint8_t *key,
+                                   uint64_t stream,
+                                   struct RandGenerator **out);
+
+/**
+ * Write the next integer to `out`. Unsupported for Sobol sequences of more than one dimension.
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle, and `out` null or valid for writes.
+ */
+enum RandStatus rand_generator_next(struct RandGenerator *gen, uint32_t *out);
+
+/**
+ * Fill `out` with `n` integers. For Sobol sequences `n` must be a multiple of the dimension.
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle, and `out` valid for `n` writes (it may be null if `n` is zero).
+ */
+enum RandStatus rand_generator_fill_u32(struct RandGenerator *gen,
+                                        uint32_t *out,
+                                        size_t n);
+
+/**
+ * Fill `out` with `n` uniforms on [0,1). For Sobol sequences `n` must be a multiple of the dimension.
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle, and `out` valid for `n` writes (it may be null if `n` is zero).
+ */
+enum RandStatus rand_generator_fill_uniform(struct RandGenerator *gen,
+                                            double *out,
+                                            size_t n);
+
+/**
+ * Return the generator to the state it was created in.
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle.
+ */
+enum RandStatus rand_generator_reset(struct RandGenerator *gen);
+
+/**
+ * Discard the next `n` draws (of `dim` values each for Sobol sequences).
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle.
+ */
+enum RandStatus rand_generator_skip(struct RandGenerator *gen, uint64_t n);
+
+/**
+ * Free a generator. Null is ignored.
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle, which is invalid afterwards.
+ */
+enum RandStatus rand_generator_destroy(struct RandGenerator *gen);
+
+/**
+ * Create a uniform distribution on [l,h) sampled with `gen`.
+ *
+ * On success the distribution takes ownership of `gen`, which must not be used or destroyed afterwards; on failure
+ * it remains with the caller.
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle, and `out` null or valid for writes.
+ */
+enum RandStatus rand_uniform_create(double l,
+                                    double h,
+                                    struct RandGenerator *gen,
+                                    struct RandDistribution **out);
+
+/**
+ * Create a normal distribution sampled with `gen`, using the given `RandNormalMethod`. Sobol generators can only be
+ * used with `RAND_NORMAL_METHOD_INVERSE`.
+ *
+ * Ownership of `gen` is as for `rand_uniform_create`.
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle, and `out` null or valid for writes.
+ */
+enum RandStatus rand_normal_create(double mean,
+                                   double variance,
+                                   uint32_t method,
+                                   struct RandGenerator *gen,
+                                   struct RandDistribution **out);
+
+/**
+ * Create an exponential distribution with rate `lambda` sampled with `gen`, by inversion.
+ *
+ * Ownership of `gen` is as for `rand_uniform_create`.
+ *
+ * # Safety
+ *
+ * `gen` must be null or a live generator handle, and `out` null or valid for writes.
+ */
+enum RandStatus rand_exponential_create(double lambda,
+                                        struct RandGenerator *gen,
+                                        struct RandDistribution **out);
+
+/**
+ * Write the next variate to `out`. Unsupported when sampled with a Sobol sequence of more than one dimension.
+ *
+ * # Safety
+ *
+ * `dist` must be null or a live distribution handle, and `out` null or valid for writes.
+ */
+enum RandStatus rand_distribution_sample(struct RandDistribution *dist,
+                                         double *out);
+
+/**
+ * Fill `out` with `n` variates. With a Sobol sequence `n` must be a multiple of its dimension.
+ *
+ * # Safety
+ *
+ * `dist` must be null or a live distribution handle, and `out` valid for `n` writes (it may be null if `n` is zero).
+ */
+enum RandStatus rand_distribution_fill(struct RandDistribution *dist,
+                                       double *out,
+                                       size_t n);
+
+/**
+ * Return the distribution, and its generator, to the state they were created in.
+ *
+ * # Safety
+ *
+ * `dist` must be null or a live distribution handle.
+ */
+enum RandStatus rand_distribution_reset(struct RandDistribution *dist);
+
+/**
+ * Discard the next `n` variates. Rejection methods use a variable number of uniforms per variate, so this samples
+ * and discards them rather than skipping the generator.
+ *
+ * # Safety
+ *
+ * `dist` must be null or a live distribution handle.
+ */
+enum RandStatus rand_distribution_skip(struct RandDistribution *dist,
+                                       uint64_t n);
+
+/**
+ * Free a distribution and its generator. Null is ignored.
+ *
+ * # Safety
+ *
+ * `dist` must be null or a live distribution handle, which is invalid afterwards.
+ */
+enum RandStatus rand_distribution_destroy(struct RandDistribution *dist);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RAND_CAPI_H */
@@ -0,0 +1,765 @@
+//! A C interface to the rand generators and distributions.
+//!
+//! Generators and distributions are opaque handles, created by the `*_create` functions and freed by the matching
+//! `*_destroy`. Every function returns a `RandStatus`, results being written through pointer arguments, and no panic
+//! crosses the interface: an invalid argument that the library would assert on is reported as
+//! `RAND_STATUS_INVALID_ARGUMENT` where it can be checked up front, and as `RAND_STATUS_PANIC` otherwise.
+//!
+//! The header `include/rand_capi.h` is generated from this file by cbindgen when the crate is built.
+
+use rand::dist::continuous::{Exponential, Normal, Uniform};
+use rand::dist::normal::{acklam, polar::Polar, ziggurat::Ziggurat};
+use rand::dist::Dist;
+use rand::gen::chacha::ChaCha;
+use rand::gen::pseudo::{LCG, MT19937, Xorshift64};
+use rand::gen::quasi::Sobol;
+use rand::gen::seed::{FromSeedSequence, SeedSequence};
+use rand::gen::{Dimensionless, Dimensioned, RandomStream, Rejectable, Resettable};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::{ptr, slice};
+
+/// The outcome of a call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandStatus {
+  Ok = 0,
+  /// a required pointer was null
+  NullPointer = 1,
+  /// an argument was out of range, e.g. a negative variance, or a rejection method with a Sobol generator
+  InvalidArgument = 2,
+  /// the operation isn't defined for this generator, e.g. single values from a multidimensional Sobol sequence
+  Unsupported = 3,
+  /// the library panicked; the handle should be destroyed
+  Panic = 4,
+}
+
+/// The generators that take a seed. The value is passed as a `uint32_t`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandGeneratorKind {
+  Lcg = 0,
+  Xorshift64 = 1,
+  Mt19937 = 2,
+  /// ChaCha with 20 rounds; `rand_chacha_create` gives a choice of rounds and key
+  Chacha20 = 3,
+}
+
+/// Algorithms for normal variates. The value is passed as a `uint32_t`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandNormalMethod {
+  /// Acklam's inverse CDF, which uses every uniform and so is the only method suitable for Sobol
+  Inverse = 0,
+  /// Marsaglia's polar method (rejection)
+  Polar = 1,
+  /// Marsaglia's ziggurat (rejection)
+  Ziggurat = 2,
+}
+
+impl RandGeneratorKind {
+  fn from_u32(kind: u32) -> Option<RandGeneratorKind> {
+    match kind {
+      0 => Some(RandGeneratorKind::Lcg),
+      1 => Some(RandGeneratorKind::Xorshift64),
+      2 => Some(RandGeneratorKind::Mt19937),
+      3 => Some(RandGeneratorKind::Chacha20),
+      _ => None,
+    }
+  }
+}
+
+impl RandNormalMethod {
+  fn from_u32(method: u32) -> Option<RandNormalMethod> {
+    match method {
+      0 => Some(RandNormalMethod::Inverse),
+      1 => Some(RandNormalMethod::Polar),
+      2 => Some(RandNormalMethod::Ziggurat),
+      _ => None,
+    }
+  }
+}
+
+// How a generator was created, so that it can be recreated in its initial state
+#[derive(Debug, Clone)]
+enum GenSpec {
+  Seed(RandGeneratorKind, u32),
+  SeedBytes(RandGeneratorKind, Vec<u8>),
+  Sobol(u32),
+  ChaCha { rounds: usize, key: [u8; 32], stream: u64 },
+}
+
+// only ever held (briefly) before moving into a boxed handle or distribution, so MT19937's size doesn't matter
+#[allow(clippy::large_enum_variant)]
+enum AnyGen {
+  Lcg(LCG),
+  Xorshift64(Xorshift64),
+  Mt19937(MT19937),
+  Sobol(Sobol),
+  ChaCha(ChaCha),
+}
+
+impl GenSpec {
+  fn build(&self) -> AnyGen {
+    match self {
+      GenSpec::Seed(kind, seed) => match kind {
+        RandGeneratorKind::Lcg => AnyGen::Lcg(LCG::new(Some(*seed))),
+        RandGeneratorKind::Xorshift64 => AnyGen::Xorshift64(Xorshift64::new(Some(*seed))),
+        RandGeneratorKind::Mt19937 => AnyGen::Mt19937(MT19937::new(Some(*seed))),
+        RandGeneratorKind::Chacha20 => AnyGen::ChaCha(ChaCha::from_seed_sequence(&SeedSequence::new(&[*seed]))),
+      },
+      GenSpec::SeedBytes(kind, bytes) => match kind {
+        RandGeneratorKind::Lcg => AnyGen::Lcg(LCG::from_seed_bytes(bytes)),
+        RandGeneratorKind::Xorshift64 => AnyGen::Xorshift64(Xorshift64::from_seed_bytes(bytes)),
+        RandGeneratorKind::Mt19937 => AnyGen::Mt19937(MT19937::from_seed_bytes(bytes)),
+        RandGeneratorKind::Chacha20 => AnyGen::ChaCha(ChaCha::from_seed_bytes(bytes)),
+      },
+      GenSpec::Sobol(dim) => AnyGen::Sobol(Sobol::new(*dim)),
+      GenSpec::ChaCha { rounds, key, stream } => AnyGen::ChaCha(ChaCha::from_key(*rounds, *key, *stream)),
+    }
+  }
+}
+
+// Dispatch to whichever generator is held
+macro_rules! with_gen {
+  ($gen:expr, $g:ident => $body:expr) => {
+    match $gen {
+      AnyGen::Lcg($g) => $body,
+      AnyGen::Xorshift64($g) => $body,
+      AnyGen::Mt19937($g) => $body,
+      AnyGen::Sobol($g) => $body,
+      AnyGen::ChaCha($g) => $body,
+    }
+  };
+}
+
+impl AnyGen {
+  // values per draw: the dimension of a Sobol sequence, otherwise 1
+  fn dim(&self) -> usize {
+    match self {
+      AnyGen::Sobol(g) => g.dim() as usize,
+      _ => 1,
+    }
+  }
+
+  fn skip(&mut self, n: u64) {
+    match self {
+      AnyGen::Lcg(g) => skip_by(g, n),
+      AnyGen::Xorshift64(g) => skip_by(g, n),
+      AnyGen::Mt19937(g) => skip_by(g, n),
+      AnyGen::Sobol(g) => skip_by(g, n),
+      // not resettable (it may reseed), so draw and discard
+      AnyGen::ChaCha(g) => {
+        let mut left = n;
+        while left > 0 {
+          let chunk = left.min(1 << 16);
+          g.next_n(chunk as usize);
+          left -= chunk;
+        }
+      }
+    }
+  }
+}
+
+fn skip_by<G: Resettable>(g: &mut G, n: u64) {
+  let mut left = n;
+  while left > 0 {
+    let chunk = left.min(u32::MAX as u64);
+    g.skip(chunk as u32);
+    left -= chunk;
+  }
+}
+
+/// A random number generator
+pub struct RandGenerator {
+  spec: GenSpec,
+  gen: AnyGen,
+}
+
+impl RandGenerator {
+  fn new(spec: GenSpec) -> RandGenerator {
+    RandGenerator { gen: spec.build(), spec }
+  }
+}
+
+// How a distribution was created
+#[derive(Debug, Clone, Copy)]
+enum DistSpec {
+  Uniform { l: f64, h: f64 },
+  Normal { mean: f64, variance: f64, method: RandNormalMethod },
+  Exponential { lambda: f64 },
+}
+
+// Distributions that use every uniform, so can take any generator
+fn streaming<R: RandomStream + 'static>(spec: DistSpec, rng: R) -> Option<Box<dyn Dist<f64>>> {
+  Some(match spec {
+    DistSpec::Uniform { l, h } => Box::new(Uniform::new(l, h, rng)),
+    DistSpec::Normal { mean, variance, method: RandNormalMethod::Inverse } =>
+      Box::new(Normal::<acklam::InverseCumulative<R>>::new(mean, variance, rng)),
+    DistSpec::Exponential { lambda } => Box::new(Exponential::new(lambda, rng)),
+    DistSpec::Normal { .. } => return None,
+  })
+}
+
+fn any<R: RandomStream + Dimensionless + Rejectable + 'static>(spec: DistSpec, rng: R) -> Box<dyn Dist<f64>> {
+  match spec {
+    DistSpec::Normal { mean, variance, method: RandNormalMethod::Polar } =>
+      Box::new(Normal::<Polar<R>>::new(mean, variance, rng)),
+    DistSpec::Normal { mean, variance, method: RandNormalMethod::Ziggurat } =>
+      Box::new(Normal::<Ziggurat<R>>::new(mean, variance, rng)),
+    _ => streaming(spec, rng).unwrap(),
+  }
+}
+
+impl DistSpec {
+  fn check(&self, gen: &AnyGen) -> Result<(), RandStatus> {
+    let valid = match *self {
+      DistSpec::Uniform { l, h } => l.is_finite() && h.is_finite() && h > l,
+      DistSpec::Normal { mean, variance, method } =>
+        mean.is_finite() && variance.is_finite() && variance > 0.0
+          && (method == RandNormalMethod::Inverse || !matches!(gen, AnyGen::Sobol(_))),
+      DistSpec::Exponential { lambda } => lambda.is_finite() && lambda > 0.0,
+    };
+    if valid { Ok(()) } else { Err(RandStatus::InvalidArgument) }
+  }
+
+  // check first: a rejection method with Sobol panics
+  fn build(self, gen: AnyGen) -> Box<dyn Dist<f64>> {
+    match gen {
+      AnyGen::Lcg(g) => any(self, g),
+      AnyGen::Xorshift64(g) => any(self, g),
+      AnyGen::Mt19937(g) => any(self, g),
+      AnyGen::ChaCha(g) => any(self, g),
+      AnyGen::Sobol(g) => streaming(self, g).expect("rejection method with a Sobol generator"),
+    }
+  }
+}
+
+/// A distribution, sampled using the generator it was created with
+pub struct RandDistribution {
+  spec: DistSpec,
+  gen_spec: GenSpec,
+  dim: usize,
+  dist: Box<dyn Dist<f64>>,
+}
+
+// Runs f, reporting a panic as a status rather than unwinding into C
+fn guard<F: FnOnce() -> Result<(), RandStatus>>(f: F) -> RandStatus {
+  match catch_unwind(AssertUnwindSafe(f)) {
+    Ok(Ok(())) => RandStatus::Ok,
+    Ok(Err(status)) => status,
+    Err(_) => RandStatus::Panic,
+  }
+}
+
+unsafe fn out_slice<'a, T>(out: *mut T, n: usize) -> Result<&'a mut [T], RandStatus> {
+  if n == 0 {
+    Ok(&mut [])
+  } else if out.is_null() {
+    Err(RandStatus::NullPointer)
+  } else {
+    Ok(slice::from_raw_parts_mut(out, n))
+  }
+}
+
+unsafe fn write_handle<T>(out: *mut *mut T, value: Result<T, RandStatus>) -> Result<(), RandStatus> {
+  if out.is_null() {
+    return Err(RandStatus::NullPointer);
+  }
+  *out = ptr::null_mut();
+  *out = Box::into_raw(Box::new(value?));
+  Ok(())
+}
+
+fn checked_dim(n: usize, dim: usize) -> Result<(), RandStatus> {
+  if n.is_multiple_of(dim) { Ok(()) } else { Err(RandStatus::InvalidArgument) }
+}
+
+/// Create a generator of the given `RandGeneratorKind` from a 32-bit seed, which must be nonzero for the LCG and
+/// Xorshift64 generators.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes. The handle written there must be freed with `rand_generator_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn rand_generator_create(kind: u32, seed: u32, out: *mut *mut RandGenerator) -> RandStatus {
+  guard(|| {
+    let kind = match RandGeneratorKind::from_u32(kind) {
+      Some(RandGeneratorKind::Lcg) | Some(RandGeneratorKind::Xorshift64) if seed == 0 => None,
+      kind => kind,
+    };
+    write_handle(out, kind.ok_or(RandStatus::InvalidArgument).map(|k| RandGenerator::new(GenSpec::Seed(k, seed))))
+  })
+}
+
+/// Create a generator of the given `RandGeneratorKind` whose whole state is seeded from `len` bytes, which are hashed
+/// by a seed sequence.
+///
+/// # Safety
+///
+/// `bytes` must be valid for `len` reads (it may be null if `len` is zero), and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_generator_create_from_seed_bytes(kind: u32, bytes: *const u8, len: usize,
+                                                               out: *mut *mut RandGenerator) -> RandStatus {
+  guard(|| {
+    let bytes = if len == 0 {
+      Vec::new()
+    } else if bytes.is_null() {
+      return Err(RandStatus::NullPointer);
+    } else {
+      slice::from_raw_parts(bytes, len).to_vec()
+    };
+    let kind = RandGeneratorKind::from_u32(kind).ok_or(RandStatus::InvalidArgument);
+    write_handle(out, kind.map(|k| RandGenerator::new(GenSpec::SeedBytes(k, bytes))))
+  })
+}
+
+/// Create a Sobol sequence generator of dimension `dim` (1 to 1111). Each draw is `dim` values.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_sobol_create(dim: u32, out: *mut *mut RandGenerator) -> RandStatus {
+  guard(|| {
+    let valid = (1..=Sobol::MAX_DIM).contains(&dim);
+    write_handle(out, if valid { Ok(RandGenerator::new(GenSpec::Sobol(dim))) } else { Err(RandStatus::InvalidArgument) })
+  })
+}
+
+/// Create a deterministic ChaCha generator with 8, 12 or 20 `rounds`, a 32-byte `key` and a `stream` id.
+///
+/// # Safety
+///
+/// `key` must be valid for 32 reads, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_chacha_create(rounds: u32, key: *const u8, stream: u64,
+                                            out: *mut *mut RandGenerator) -> RandStatus {
+  guard(|| {
+    if key.is_null() {
+      return Err(RandStatus::NullPointer);
+    }
+    let mut k = [0u8; 32];
+    k.copy_from_slice(slice::from_raw_parts(key, 32));
+    let spec = match rounds {
+      8 | 12 | 20 => Ok(GenSpec::ChaCha { rounds: rounds as usize, key: k, stream }),
+      _ => Err(RandStatus::InvalidArgument),
+    };
+    write_handle(out, spec.map(RandGenerator::new))
+  })
+}
+
+/// Write the next integer to `out`. Unsupported for Sobol sequences of more than one dimension.
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_generator_next(gen: *mut RandGenerator, out: *mut u32) -> RandStatus {
+  guard(|| {
+    let gen = gen.as_mut().ok_or(RandStatus::NullPointer)?;
+    let out = out.as_mut().ok_or(RandStatus::NullPointer)?;
+    if gen.gen.dim() != 1 {
+      return Err(RandStatus::Unsupported);
+    }
+    *out = with_gen!(&mut gen.gen, g => g.next_n(1)[0]);
+    Ok(())
+  })
+}
+
+/// Fill `out` with `n` integers. For Sobol sequences `n` must be a multiple of the dimension.
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle, and `out` valid for `n` writes (it may be null if `n` is zero).
+#[no_mangle]
+pub unsafe extern "C" fn rand_generator_fill_u32(gen: *mut RandGenerator, out: *mut u32, n: usize) -> RandStatus {
+  guard(|| {
+    let gen = gen.as_mut().ok_or(RandStatus::NullPointer)?;
+    let out = out_slice(out, n)?;
+    checked_dim(n, gen.gen.dim())?;
+    out.copy_from_slice(&with_gen!(&mut gen.gen, g => g.next_n(n)));
+    Ok(())
+  })
+}
+
+/// Fill `out` with `n` uniforms on [0,1). For Sobol sequences `n` must be a multiple of the dimension.
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle, and `out` valid for `n` writes (it may be null if `n` is zero).
+#[no_mangle]
+pub unsafe extern "C" fn rand_generator_fill_uniform(gen: *mut RandGenerator, out: *mut f64, n: usize) -> RandStatus {
+  guard(|| {
+    let gen = gen.as_mut().ok_or(RandStatus::NullPointer)?;
+    let out = out_slice(out, n)?;
+    checked_dim(n, gen.gen.dim())?;
+    out.copy_from_slice(&with_gen!(&mut gen.gen, g => g.uniforms01(n)));
+    Ok(())
+  })
+}
+
+/// Return the generator to the state it was created in.
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle.
+#[no_mangle]
+pub unsafe extern "C" fn rand_generator_reset(gen: *mut RandGenerator) -> RandStatus {
+  guard(|| {
+    let gen = gen.as_mut().ok_or(RandStatus::NullPointer)?;
+    gen.gen = gen.spec.build();
+    Ok(())
+  })
+}
+
+/// Discard the next `n` draws (of `dim` values each for Sobol sequences).
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle.
+#[no_mangle]
+pub unsafe extern "C" fn rand_generator_skip(gen: *mut RandGenerator, n: u64) -> RandStatus {
+  guard(|| {
+    let gen = gen.as_mut().ok_or(RandStatus::NullPointer)?;
+    gen.gen.skip(n);
+    Ok(())
+  })
+}
+
+/// Free a generator. Null is ignored.
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle, which is invalid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rand_generator_destroy(gen: *mut RandGenerator) -> RandStatus {
+  guard(|| {
+    if !gen.is_null() {
+      drop(Box::from_raw(gen));
+    }
+    Ok(())
+  })
+}
+
+// Takes the generator if the distribution can be created, otherwise leaves it with the caller
+unsafe fn create_dist(spec: DistSpec, gen: *mut RandGenerator, out: *mut *mut RandDistribution) -> RandStatus {
+  guard(|| {
+    let g = gen.as_ref().ok_or(RandStatus::NullPointer)?;
+    if out.is_null() {
+      return Err(RandStatus::NullPointer);
+    }
+    spec.check(&g.gen)?;
+    let RandGenerator { spec: gen_spec, gen: g } = *Box::from_raw(gen);
+    let dim = g.dim();
+    write_handle(out, Ok(RandDistribution { spec, gen_spec, dim, dist: spec.build(g) }))
+  })
+}
+
+/// Create a uniform distribution on [l,h) sampled with `gen`.
+///
+/// On success the distribution takes ownership of `gen`, which must not be used or destroyed afterwards; on failure
+/// it remains with the caller.
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_uniform_create(l: f64, h: f64, gen: *mut RandGenerator,
+                                             out: *mut *mut RandDistribution) -> RandStatus {
+  create_dist(DistSpec::Uniform { l, h }, gen, out)
+}
+
+/// Create a normal distribution sampled with `gen`, using the given `RandNormalMethod`. Sobol generators can only be
+/// used with `RAND_NORMAL_METHOD_INVERSE`.
+///
+/// Ownership of `gen` is as for `rand_uniform_create`.
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_normal_create(mean: f64, variance: f64, method: u32, gen: *mut RandGenerator,
+                                            out: *mut *mut RandDistribution) -> RandStatus {
+  match RandNormalMethod::from_u32(method) {
+    Some(method) => create_dist(DistSpec::Normal { mean, variance, method }, gen, out),
+    None => RandStatus::InvalidArgument,
+  }
+}
+
+/// Create an exponential distribution with rate `lambda` sampled with `gen`, by inversion.
+///
+/// Ownership of `gen` is as for `rand_uniform_create`.
+///
+/// # Safety
+///
+/// `gen` must be null or a live generator handle, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_exponential_create(lambda: f64, gen: *mut RandGenerator,
+                                                 out: *mut *mut RandDistribution) -> RandStatus {
+  create_dist(DistSpec::Exponential { lambda }, gen, out)
+}
+
+/// Write the next variate to `out`. Unsupported when sampled with a Sobol sequence of more than one dimension.
+///
+/// # Safety
+///
+/// `dist` must be null or a live distribution handle, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rand_distribution_sample(dist: *mut RandDistribution, out: *mut f64) -> RandStatus {
+  guard(|| {
+    let dist = dist.as_mut().ok_or(RandStatus::NullPointer)?;
+    let out = out.as_mut().ok_or(RandStatus::NullPointer)?;
+    if dist.dim != 1 {
+      return Err(RandStatus::Unsupported);
+    }
+    *out = dist.dist.sample_n(1)[0];
+    Ok(())
+  })
+}
+
+/// Fill `out` with `n` variates. With a Sobol sequence `n` must be a multiple of its dimension.
+///
+/// # Safety
+///
+/// `dist` must be null or a live distribution handle, and `out` valid for `n` writes (it may be null if `n` is zero).
+#[no_mangle]
+pub unsafe extern "C" fn rand_distribution_fill(dist: *mut RandDistribution, out: *mut f64, n: usize) -> RandStatus {
+  guard(|| {
+    let dist = dist.as_mut().ok_or(RandStatus::NullPointer)?;
+    let out = out_slice(out, n)?;
+    checked_dim(n, dist.dim)?;
+    out.copy_from_slice(&dist.dist.sample_n(n));
+    Ok(())
+  })
+}
+
+/// Return the distribution, and its generator, to the state they were created in.
+///
+/// # Safety
+///
+/// `dist` must be null or a live distribution handle.
+#[no_mangle]
+pub unsafe extern "C" fn rand_distribution_reset(dist: *mut RandDistribution) -> RandStatus {
+  guard(|| {
+    let dist = dist.as_mut().ok_or(RandStatus::NullPointer)?;
+    dist.dist = dist.spec.build(dist.gen_spec.build());
+    Ok(())
+  })
+}
+
+/// Discard the next `n` variates. Rejection methods use a variable number of uniforms per variate, so this samples
+/// and discards them rather than skipping the generator.
+///
+/// # Safety
+///
+/// `dist` must be null or a live distribution handle.
+#[no_mangle]
+pub unsafe extern "C" fn rand_distribution_skip(dist: *mut RandDistribution, n: u64) -> RandStatus {
+  guard(|| {
+    let dist = dist.as_mut().ok_or(RandStatus::NullPointer)?;
+    checked_dim(n as usize, dist.dim)?;
+    let chunk = (1 << 16) / dist.dim * dist.dim;
+    let mut left = n as usize;
+    while left > 0 {
+      let k = left.min(chunk);
+      dist.dist.sample_n(k);
+      left -= k;
+    }
+    Ok(())
+  })
+}
+
+/// Free a distribution and its generator. Null is ignored.
+///
+/// # Safety
+///
+/// `dist` must be null or a live distribution handle, which is invalid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rand_distribution_destroy(dist: *mut RandDistribution) -> RandStatus {
+  guard(|| {
+    if !dist.is_null() {
+      drop(Box::from_raw(dist));
+    }
+    Ok(())
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn create(kind: RandGeneratorKind, seed: u32) -> *mut RandGenerator {
+    let mut gen = ptr::null_mut();
+    assert_eq!(unsafe { rand_generator_create(kind as u32, seed, &mut gen) }, RandStatus::Ok);
+    gen
+  }
+
+  #[test]
+  fn test_generators() {
+    let kinds = [RandGeneratorKind::Lcg, RandGeneratorKind::Xorshift64, RandGeneratorKind::Mt19937,
+                 RandGeneratorKind::Chacha20];
+    for &kind in &kinds {
+      let gen = create(kind, 19937);
+      unsafe {
+        let mut x = [0u32; 100];
+        assert_eq!(rand_generator_fill_u32(gen, x.as_mut_ptr(), 100), RandStatus::Ok);
+        // the same values as the library
+        let expected = match RandGenerator::new(GenSpec::Seed(kind, 19937)).gen {
+          AnyGen::Lcg(mut g) => g.next_n(100),
+          AnyGen::Xorshift64(mut g) => g.next_n(100),
+          AnyGen::Mt19937(mut g) => g.next_n(100),
+          AnyGen::ChaCha(mut g) => g.next_n(100),
+          AnyGen::Sobol(_) => unreachable!(),
+        };
+        assert_eq!(x[..], expected[..]);
+        // reset, then skip 10 and compare
+        let mut y = 0;
+        assert_eq!(rand_generator_reset(gen), RandStatus::Ok);
+        assert_eq!(rand_generator_skip(gen, 10), RandStatus::Ok);
+        assert_eq!(rand_generator_next(gen, &mut y), RandStatus::Ok);
+        assert_eq!(y, x[10]);
+        let mut u = [0.0f64; 1000];
+        assert_eq!(rand_generator_fill_uniform(gen, u.as_mut_ptr(), 1000), RandStatus::Ok);
+        assert!(u.iter().all(|&u| (0.0..1.0).contains(&u)));
+        assert_eq!(rand_generator_destroy(gen), RandStatus::Ok);
+      }
+    }
+  }
+
+  #[test]
+  fn test_seed_bytes() {
+    let seed = b"a seed of any length";
+    let mut a = [0u32; 8];
+    let mut b = [0u32; 8];
+    unsafe {
+      let mut gen = ptr::null_mut();
+      assert_eq!(rand_generator_create_from_seed_bytes(RandGeneratorKind::Mt19937 as u32, seed.as_ptr(), seed.len(),
+                                                       &mut gen), RandStatus::Ok);
+      rand_generator_fill_u32(gen, a.as_mut_ptr(), 8);
+      rand_generator_reset(gen);
+      rand_generator_fill_u32(gen, b.as_mut_ptr(), 8);
+      rand_generator_destroy(gen);
+    }
+    assert_eq!(a, b);
+    assert_eq!(a[..], MT19937::from_seed_bytes(seed).next_n(8)[..]);
+  }
+
+  #[test]
+  fn test_chacha() {
+    let key = [0u8; 32];
+    let mut x = [0u32; 4];
+    unsafe {
+      let mut gen = ptr::null_mut();
+      assert_eq!(rand_chacha_create(10, key.as_ptr(), 0, &mut gen), RandStatus::InvalidArgument);
+      assert!(gen.is_null());
+      assert_eq!(rand_chacha_create(20, key.as_ptr(), 0, &mut gen), RandStatus::Ok);
+      rand_generator_fill_u32(gen, x.as_mut_ptr(), 4);
+      rand_generator_destroy(gen);
+    }
+    // the zero-key keystream
+    assert_eq!(x, [0xade0_b876, 0x903d_f1a0, 0xe56a_5d40, 0x28bd_8653]);
+  }
+
+  #[test]
+  fn test_sobol() {
+    let mut gen = ptr::null_mut();
+    unsafe {
+      assert_eq!(rand_sobol_create(0, &mut gen), RandStatus::InvalidArgument);
+      assert_eq!(rand_sobol_create(2, &mut gen), RandStatus::Ok);
+      let mut x = [0u32; 4];
+      let mut y = 0;
+      assert_eq!(rand_generator_next(gen, &mut y), RandStatus::Unsupported);
+      assert_eq!(rand_generator_fill_u32(gen, x.as_mut_ptr(), 3), RandStatus::InvalidArgument);
+      assert_eq!(rand_generator_fill_u32(gen, x.as_mut_ptr(), 4), RandStatus::Ok);
+      let mut sobol = Sobol::new(2);
+      assert_eq!(x[..], sobol.next_n(4)[..]);
+      // rejection isn't allowed, and the generator stays with the caller, in the state it was left in
+      let mut dist = ptr::null_mut();
+      let zig = RandNormalMethod::Ziggurat as u32;
+      assert_eq!(rand_normal_create(0.0, 1.0, zig, gen, &mut dist), RandStatus::InvalidArgument);
+      assert!(dist.is_null());
+      let inv = RandNormalMethod::Inverse as u32;
+      assert_eq!(rand_normal_create(0.0, 1.0, inv, gen, &mut dist), RandStatus::Ok);
+      let mut z = [0.0; 2];
+      assert_eq!(rand_distribution_fill(dist, z.as_mut_ptr(), 2), RandStatus::Ok);
+      let expected = Normal::<acklam::InverseCumulative<Sobol>>::new(0.0, 1.0, sobol).sample_n(2);
+      assert_eq!(z[..], expected[..]);
+      rand_distribution_destroy(dist);
+    }
+  }
+
+  #[test]
+  fn test_distributions() {
+    let n = 10_000;
+    let specs = [(DistSpec::Uniform { l: -1.0, h: 3.0 }, 1.0, 16.0 / 12.0),
+                 (DistSpec::Normal { mean: 1.0, variance: 4.0, method: RandNormalMethod::Inverse }, 1.0, 4.0),
+                 (DistSpec::Normal { mean: 1.0, variance: 4.0, method: RandNormalMethod::Polar }, 1.0, 4.0),
+                 (DistSpec::Normal { mean: 1.0, variance: 4.0, method: RandNormalMethod::Ziggurat }, 1.0, 4.0),
+                 (DistSpec::Exponential { lambda: 2.0 }, 0.5, 0.25)];
+    for &(spec, mean, variance) in &specs {
+      let gen = create(RandGeneratorKind::Xorshift64, 19937);
+      let mut dist = ptr::null_mut();
+      let status = unsafe {
+        match spec {
+          DistSpec::Uniform { l, h } => rand_uniform_create(l, h, gen, &mut dist),
+          DistSpec::Normal { mean, variance, method } => rand_normal_create(mean, variance, method as u32, gen, &mut dist),
+          DistSpec::Exponential { lambda } => rand_exponential_create(lambda, gen, &mut dist),
+        }
+      };
+      assert_eq!(status, RandStatus::Ok);
+      let mut x = vec![0.0; n];
+      let mut y = vec![0.0; n];
+      unsafe {
+        assert_eq!(rand_distribution_fill(dist, x.as_mut_ptr(), n), RandStatus::Ok);
+        // reset and skip reproduce the sequence
+        assert_eq!(rand_distribution_reset(dist), RandStatus::Ok);
+        assert_eq!(rand_distribution_skip(dist, 100), RandStatus::Ok);
+        assert_eq!(rand_distribution_sample(dist, &mut y[0]), RandStatus::Ok);
+        assert_eq!(y[0], x[100]);
+        assert_eq!(rand_distribution_destroy(dist), RandStatus::Ok);
+      }
+      let m = x.iter().sum::<f64>() / n as f64;
+      let v = x.iter().map(|x| (x - m) * (x - m)).sum::<f64>() / n as f64;
+      assert!((m - mean).abs() < 4.0 * (variance / n as f64).sqrt(), "{:?} mean {}", spec, m);
+      assert!((v / variance - 1.0).abs() < 0.05, "{:?} variance {}", spec, v);
+    }
+  }
+
+  #[test]
+  fn test_invalid() {
+    let mut gen = ptr::null_mut();
+    let mut dist = ptr::null_mut();
+    let mut x = 0.0;
+    unsafe {
+      assert_eq!(rand_generator_create(4, 1, &mut gen), RandStatus::InvalidArgument);
+      assert_eq!(rand_generator_create(RandGeneratorKind::Lcg as u32, 0, &mut gen), RandStatus::InvalidArgument);
+      assert_eq!(rand_generator_create(0, 0, ptr::null_mut()), RandStatus::NullPointer);
+      assert_eq!(rand_generator_next(ptr::null_mut(), &mut 0), RandStatus::NullPointer);
+      assert_eq!(rand_generator_fill_u32(ptr::null_mut(), ptr::null_mut(), 0), RandStatus::NullPointer);
+      assert_eq!(rand_generator_destroy(ptr::null_mut()), RandStatus::Ok);
+      gen = create(RandGeneratorKind::Lcg, 1);
+      assert_eq!(rand_generator_fill_u32(gen, ptr::null_mut(), 1), RandStatus::NullPointer);
+      assert_eq!(rand_generator_fill_u32(gen, ptr::null_mut(), 0), RandStatus::Ok);
+      assert_eq!(rand_normal_create(0.0, -1.0, 0, gen, &mut dist), RandStatus::InvalidArgument);
+      assert_eq!(rand_normal_create(0.0, 1.0, 3, gen, &mut dist), RandStatus::InvalidArgument);
+      assert_eq!(rand_uniform_create(1.0, 1.0, gen, &mut dist), RandStatus::InvalidArgument);
+      assert_eq!(rand_exponential_create(f64::NAN, gen, &mut dist), RandStatus::InvalidArgument);
+      assert_eq!(rand_distribution_sample(dist, &mut x), RandStatus::NullPointer);
+      assert_eq!(rand_exponential_create(1.0, gen, ptr::null_mut()), RandStatus::NullPointer);
+      // the generator is still the caller's
+      assert_eq!(rand_generator_destroy(gen), RandStatus::Ok);
+    }
+  }
+
+  #[test]
+  fn test_header() {
+    // the committed header matches the one generated by the build script
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/rand_capi.h"));
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/rand_capi.h");
+    if std::env::var_os("RAND_CAPI_UPDATE_HEADER").is_some() {
+      std::fs::write(path, generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(path).unwrap_or_default();
+    assert!(committed == generated, "{} is out of date: rerun the tests with RAND_CAPI_UPDATE_HEADER=1", path);
+  }
+}
@@ -0,0 +1,551 @@
+//! Python bindings for the rand generators and distributions, as the extension module `pyrand`.
+//!
+//! The generators keep their rust semantics: they are seeded (from the clock if no seed is given), `reset` returns
+//! them to their initial state and `skip` discards values, both returning the generator so that calls can be chained.
+//! A distribution takes its generator as the rust constructors do, so a generator that has been passed to a
+//! distribution can't be used again from python. Arrays returned by `next_n`, `uniforms01` and `sample_n` are NumPy
+//! arrays that take ownership of the rust vector, so the values aren't copied.
+
+use numpy::{Element, PyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::dist::continuous::{Exponential, Normal, Uniform};
+use rand::dist::discrete::{Discrete, DiscreteWeighted, WithoutReplacement};
+use rand::dist::normal::{acklam, polar::Polar, ziggurat::Ziggurat};
+use rand::dist::truncated::{Inversion, Rejection, TruncatedExponential, TruncatedNormal};
+use rand::dist::Dist;
+use rand::gen::pseudo::{LCG, MT19937, Xorshift64};
+use rand::gen::quasi::Sobol;
+use rand::gen::seed::FromSeedSequence;
+use rand::gen::{Dimensionless, Dimensioned, RandomStream, Resettable, Seeded};
+
+// How a generator was created, so that a distribution can recreate it in its initial state
+#[derive(Debug, Clone)]
+enum GenSpec {
+  Lcg(u32),
+  Xorshift64(u32),
+  Mt19937(u32),
+  Sobol(u32),
+  LcgBytes(Vec<u8>),
+  Xorshift64Bytes(Vec<u8>),
+  Mt19937Bytes(Vec<u8>),
+}
+
+// only ever held (briefly) before moving into a boxed handle or distribution, so MT19937's size doesn't matter
+#[allow(clippy::large_enum_variant)]
+enum AnyGen {
+  Lcg(LCG),
+  Xorshift64(Xorshift64),
+  Mt19937(MT19937),
+  Sobol(Sobol),
+}
+
+impl GenSpec {
+  fn build(&self) -> AnyGen {
+    match self {
+      GenSpec::Lcg(seed) => AnyGen::Lcg(LCG::new(Some(*seed))),
+      GenSpec::Xorshift64(seed) => AnyGen::Xorshift64(Xorshift64::new(Some(*seed))),
+      GenSpec::Mt19937(seed) => AnyGen::Mt19937(MT19937::new(Some(*seed))),
+      GenSpec::Sobol(dim) => AnyGen::Sobol(Sobol::new(*dim)),
+      GenSpec::LcgBytes(bytes) => AnyGen::Lcg(LCG::from_seed_bytes(bytes)),
+      GenSpec::Xorshift64Bytes(bytes) => AnyGen::Xorshift64(Xorshift64::from_seed_bytes(bytes)),
+      GenSpec::Mt19937Bytes(bytes) => AnyGen::Mt19937(MT19937::from_seed_bytes(bytes)),
+    }
+  }
+}
+
+fn moved() -> PyErr {
+  PyValueError::new_err("the generator has been moved into a distribution")
+}
+
+fn check_dim(n: usize, dim: usize) -> PyResult<()> {
+  if n.is_multiple_of(dim) {
+    Ok(())
+  } else {
+    Err(PyValueError::new_err(format!("n must be a multiple of the dimension {}", dim)))
+  }
+}
+
+// The one-dimensional seeded generators differ only in type and in how they're recreated
+macro_rules! seeded {
+  ($py_name:literal, $name:ident, $gen:ident, $spec:ident, $spec_bytes:ident, $doc:literal) => {
+    #[doc = $doc]
+    #[pyclass(unsendable, name = $py_name)]
+    pub struct $name {
+      spec: GenSpec,
+      gen: Option<$gen>,
+    }
+
+    impl $name {
+      fn get(&mut self) -> PyResult<&mut $gen> {
+        self.gen.as_mut().ok_or_else(moved)
+      }
+    }
+
+    #[pymethods]
+    impl $name {
+      /// Seeded from the clock if no seed is given
+      #[new]
+      #[pyo3(signature = (seed=None))]
+      fn new(seed: Option<u32>) -> PyResult<Self> {
+        if seed == Some(0) && GenSpec::$spec(0).nonzero_seed() {
+          return Err(PyValueError::new_err("the seed must be nonzero"));
+        }
+        let gen = $gen::new(seed);
+        Ok($name { spec: GenSpec::$spec(gen.seed()), gen: Some(gen) })
+      }
+
+      /// Seed the whole state from any number of bytes
+      #[staticmethod]
+      fn from_seed_bytes(bytes: Vec<u8>) -> Self {
+        $name { gen: Some($gen::from_seed_bytes(&bytes)), spec: GenSpec::$spec_bytes(bytes) }
+      }
+
+      #[getter]
+      fn seed(&mut self) -> PyResult<u32> {
+        Ok(self.get()?.seed())
+      }
+
+      fn next_1(&mut self) -> PyResult<u32> {
+        Ok(self.get()?.next_1())
+      }
+
+      fn uniform01(&mut self) -> PyResult<f64> {
+        Ok(self.get()?.uniform01())
+      }
+
+      /// n integers as a uint32 array
+      fn next_n<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<Bound<'py, PyArray1<u32>>> {
+        Ok(PyArray1::from_vec(py, self.get()?.next_n(n)))
+      }
+
+      /// n uniforms on [0,1) as a float64 array
+      fn uniforms01<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(PyArray1::from_vec(py, self.get()?.uniforms01(n)))
+      }
+
+      /// Return to the initial state
+      fn reset(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+        slf.get()?.reset();
+        Ok(slf)
+      }
+
+      /// Discard n values
+      fn skip(mut slf: PyRefMut<'_, Self>, n: u32) -> PyResult<PyRefMut<'_, Self>> {
+        slf.get()?.skip(n);
+        Ok(slf)
+      }
+    }
+  };
+}
+
+impl GenSpec {
+  // LCG and Xorshift64 would stick at zero
+  fn nonzero_seed(&self) -> bool {
+    matches!(self, GenSpec::Lcg(_) | GenSpec::Xorshift64(_))
+  }
+}
+
+seeded!("LCG", PyLCG, LCG, Lcg, LcgBytes, "C++11's minstd linear congruential generator");
+seeded!("Xorshift64", PyXorshift64, Xorshift64, Xorshift64, Xorshift64Bytes, "64-bit xorshift generator");
+seeded!("MT19937", PyMT19937, MT19937, Mt19937, Mt19937Bytes, "Mersenne twister");
+
+/// Sobol quasirandom sequence. Each point is dim values, so n must be a multiple of dim and skip counts points.
+#[pyclass(unsendable, name = "Sobol")]
+pub struct PySobol {
+  spec: GenSpec,
+  gen: Option<Sobol>,
+}
+
+impl PySobol {
+  fn get(&mut self) -> PyResult<&mut Sobol> {
+    self.gen.as_mut().ok_or_else(moved)
+  }
+}
+
+#[pymethods]
+impl PySobol {
+  #[new]
+  fn new(dim: u32) -> PyResult<Self> {
+    if !(1..=Sobol::MAX_DIM).contains(&dim) {
+      return Err(PyValueError::new_err(format!("the dimension must be between 1 and {}", Sobol::MAX_DIM)));
+    }
+    Ok(PySobol { spec: GenSpec::Sobol(dim), gen: Some(Sobol::new(dim)) })
+  }
+
+  #[getter]
+  fn dim(&mut self) -> PyResult<u32> {
+    Ok(self.get()?.dim())
+  }
+
+  fn next_n<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<Bound<'py, PyArray1<u32>>> {
+    let gen = self.get()?;
+    check_dim(n, gen.dim() as usize)?;
+    Ok(PyArray1::from_vec(py, gen.next_n(n)))
+  }
+
+  fn uniforms01<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<Bound<'py, PyArray1<f64>>> {
+    let gen = self.get()?;
+    check_dim(n, gen.dim() as usize)?;
+    Ok(PyArray1::from_vec(py, gen.uniforms01(n)))
+  }
+
+  fn reset(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+    slf.get()?.reset();
+    Ok(slf)
+  }
+
+  /// Discard n points
+  fn skip(mut slf: PyRefMut<'_, Self>, n: u32) -> PyResult<PyRefMut<'_, Self>> {
+    slf.get()?.skip(n);
+    Ok(slf)
+  }
+}
+
+// Moves the rust generator out of a python generator object, in whatever state it's in
+fn take(gen: &Bound<'_, PyAny>, rejection: bool) -> PyResult<(GenSpec, AnyGen)> {
+  macro_rules! take_from {
+    ($($class:ident => $variant:ident),*) => {
+      $(
+        if let Ok(g) = gen.cast::<$class>() {
+          let mut g = g.try_borrow_mut()?;
+          if rejection && matches!(g.spec, GenSpec::Sobol(_)) {
+            return Err(PyValueError::new_err("rejection methods can't be used with Sobol sequences"));
+          }
+          let taken = g.gen.take().ok_or_else(moved)?;
+          return Ok((g.spec.clone(), AnyGen::$variant(taken)));
+        }
+      )*
+    };
+  }
+  take_from!(PyLCG => Lcg, PyXorshift64 => Xorshift64, PyMT19937 => Mt19937, PySobol => Sobol);
+  Err(PyValueError::new_err("expected a generator: LCG, Xorshift64, MT19937 or Sobol"))
+}
+
+// Applies a generic constructor to whichever generator is held; Sobol only for streaming (non-rejection) methods
+macro_rules! any_gen {
+  ($gen:expr, $r:ident => $body:expr) => {
+    match $gen {
+      AnyGen::Lcg($r) => $body,
+      AnyGen::Xorshift64($r) => $body,
+      AnyGen::Mt19937($r) => $body,
+      AnyGen::Sobol($r) => $body,
+    }
+  };
+  ($gen:expr, $r:ident => $body:expr, rejection) => {
+    match $gen {
+      AnyGen::Lcg($r) => $body,
+      AnyGen::Xorshift64($r) => $body,
+      AnyGen::Mt19937($r) => $body,
+      AnyGen::Sobol(_) => unreachable!("rejection method with a Sobol generator"),
+    }
+  };
+}
+
+type Build<T> = Box<dyn Fn(AnyGen) -> Box<dyn Dist<T>>>;
+
+// A distribution together with what's needed to recreate it, and the dimension of its generator
+struct Sampler<T> {
+  spec: GenSpec,
+  build: Build<T>,
+  dist: Box<dyn Dist<T>>,
+  dim: usize,
+}
+
+impl<T: Element> Sampler<T> {
+  fn new(gen: &Bound<'_, PyAny>, rejection: bool, build: Build<T>) -> PyResult<Sampler<T>> {
+    let (spec, g) = take(gen, rejection)?;
+    let dim = if let GenSpec::Sobol(dim) = spec { dim as usize } else { 1 };
+    Ok(Sampler { dist: build(g), spec, build, dim })
+  }
+
+  fn sample_n<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<Bound<'py, PyArray1<T>>> {
+    check_dim(n, self.dim)?;
+    Ok(PyArray1::from_vec(py, self.dist.sample_n(n)))
+  }
+
+  fn reset(&mut self) {
+    self.dist = (self.build)(self.spec.build());
+  }
+
+  // rejection methods use a variable number of values per variate, so sample and discard
+  fn skip(&mut self, n: usize) -> PyResult<()> {
+    check_dim(n, self.dim)?;
+    self.dist.sample_n(n);
+    Ok(())
+  }
+}
+
+// The methods common to all distributions, around a constructor
+macro_rules! distribution {
+  ($py_name:literal, $name:ident, $t:ty, $doc:literal, $($new:tt)*) => {
+    #[doc = $doc]
+    #[pyclass(unsendable, name = $py_name)]
+    pub struct $name {
+      inner: Sampler<$t>,
+    }
+
+    #[pymethods]
+    impl $name {
+      $($new)*
+
+      /// n variates as an array
+      fn sample_n<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<Bound<'py, PyArray1<$t>>> {
+        self.inner.sample_n(py, n)
+      }
+
+      /// Return the distribution and its generator to their initial state
+      fn reset(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner.reset();
+        slf
+      }
+
+      /// Discard n variates
+      fn skip(mut slf: PyRefMut<'_, Self>, n: usize) -> PyResult<PyRefMut<'_, Self>> {
+        slf.inner.skip(n)?;
+        Ok(slf)
+      }
+    }
+  };
+}
+
+fn check(valid: bool, msg: &str) -> PyResult<()> {
+  if valid { Ok(()) } else { Err(PyValueError::new_err(msg.to_string())) }
+}
+
+distribution!("Uniform", PyUniform, f64, "Continuous uniform on [l,h)",
+  #[new]
+  fn new(l: f64, h: f64, gen: &Bound<'_, PyAny>) -> PyResult<Self> {
+    check(l.is_finite() && h.is_finite() && h > l, "h must be greater than l")?;
+    let build: Build<f64> = Box::new(move |g| any_gen!(g, r => Box::new(Uniform::new(l, h, r))));
+    Ok(PyUniform { inner: Sampler::new(gen, false, build)? })
+  }
+);
+
+distribution!("Normal", PyNormal, f64,
+  "Normal, by method \"inverse\" (Acklam's inverse CDF, the default and the only one for Sobol), \"polar\" or \"ziggurat\"",
+  #[new]
+  #[pyo3(signature = (mean, variance, gen, method="inverse"))]
+  fn new(mean: f64, variance: f64, gen: &Bound<'_, PyAny>, method: &str) -> PyResult<Self> {
+    check(variance > 0.0, "the variance must be positive")?;
+    let build: Build<f64> = match method {
+      "inverse" =>
+        Box::new(move |g| any_gen!(g, r => Box::new(Normal::<acklam::InverseCumulative<_>>::new(mean, variance, r)))),
+      "polar" => Box::new(move |g| any_gen!(g, r => Box::new(Normal::<Polar<_>>::new(mean, variance, r)), rejection)),
+      "ziggurat" =>
+        Box::new(move |g| any_gen!(g, r => Box::new(Normal::<Ziggurat<_>>::new(mean, variance, r)), rejection)),
+      _ => return Err(PyValueError::new_err("method must be \"inverse\", \"polar\" or \"ziggurat\"")),
+    };
+    Ok(PyNormal { inner: Sampler::new(gen, method != "inverse", build)? })
+  }
+);
+
+distribution!("Exponential", PyExponential, f64, "Exponential with rate lambda_, by inversion",
+  #[new]
+  fn new(lambda_: f64, gen: &Bound<'_, PyAny>) -> PyResult<Self> {
+    check(lambda_ > 0.0, "lambda_ must be positive")?;
+    let build: Build<f64> = Box::new(move |g| any_gen!(g, r => Box::new(Exponential::new(lambda_, r))));
+    Ok(PyExponential { inner: Sampler::new(gen, false, build)? })
+  }
+);
+
+distribution!("TruncatedNormal", PyTruncatedNormal, f64,
+  "Normal truncated to [a,b], by method \"inversion\" (the default, suitable for Sobol) or \"rejection\" (efficient in the far tails)",
+  #[new]
+  #[pyo3(signature = (mean, variance, a, b, gen, method="inversion"))]
+  fn new(mean: f64, variance: f64, a: f64, b: f64, gen: &Bound<'_, PyAny>, method: &str) -> PyResult<Self> {
+    check(variance > 0.0, "the variance must be positive")?;
+    check(a < b, "a must be less than b")?;
+    let build: Build<f64> = match method {
+      "inversion" =>
+        Box::new(move |g| any_gen!(g, r => Box::new(TruncatedNormal::<Inversion<_>>::new(mean, variance, a, b, r)))),
+      "rejection" => Box::new(move |g| any_gen!(g, r =>
+        Box::new(TruncatedNormal::<Rejection<_>>::new(mean, variance, a, b, r)), rejection)),
+      _ => return Err(PyValueError::new_err("method must be \"inversion\" or \"rejection\"")),
+    };
+    Ok(PyTruncatedNormal { inner: Sampler::new(gen, method == "rejection", build)? })
+  }
+);
+
+distribution!("TruncatedExponential", PyTruncatedExponential, f64,
+  "Exponential with rate lambda_ truncated to [a,b], by inversion",
+  #[new]
+  fn new(lambda_: f64, a: f64, b: f64, gen: &Bound<'_, PyAny>) -> PyResult<Self> {
+    check(lambda_ > 0.0, "lambda_ must be positive")?;
+    check(a >= 0.0 && a < b, "a must be nonnegative and less than b")?;
+    let build: Build<f64> = Box::new(move |g| any_gen!(g, r => Box::new(TruncatedExponential::new(lambda_, a, b, r))));
+    Ok(PyTruncatedExponential { inner: Sampler::new(gen, false, build)? })
+  }
+);
+
+distribution!("Discrete", PyDiscrete, i64, "Discrete uniform over the given values",
+  #[new]
+  fn new(values: Vec<i64>, gen: &Bound<'_, PyAny>) -> PyResult<Self> {
+    check(!values.is_empty(), "values must not be empty")?;
+    let build: Build<i64> = Box::new(move |g| any_gen!(g, r => Box::new(Discrete::new(&values, r))));
+    Ok(PyDiscrete { inner: Sampler::new(gen, false, build)? })
+  }
+);
+
+distribution!("DiscreteWeighted", PyDiscreteWeighted, i64, "The given values, with probabilities that sum to 1",
+  #[new]
+  fn new(values: Vec<i64>, weights: Vec<f64>, gen: &Bound<'_, PyAny>) -> PyResult<Self> {
+    check(!values.is_empty() && values.len() == weights.len(), "values and weights must be nonempty and the same length")?;
+    check(weights.iter().all(|w| (0.0..=1.0).contains(w)) && (weights.iter().sum::<f64>() - 1.0).abs() < f64::EPSILON,
+          "weights must be probabilities summing to 1")?;
+    let a: Vec<(i64, f64)> = values.into_iter().zip(weights).collect();
+    let build: Build<i64> = Box::new(move |g| any_gen!(g, r => Box::new(DiscreteWeighted::new(&a, r))));
+    Ok(PyDiscreteWeighted { inner: Sampler::new(gen, false, build)? })
+  }
+);
+
+/// Draws without replacement from a population with counts[i] of values[i]
+#[pyclass(unsendable, name = "WithoutReplacement")]
+pub struct PyWithoutReplacement {
+  inner: Sampler<i64>,
+  // remaining population, which sample_n can't exceed
+  left: u64,
+  total: u64,
+}
+
+#[pymethods]
+impl PyWithoutReplacement {
+  #[new]
+  fn new(values: Vec<i64>, counts: Vec<u32>, gen: &Bound<'_, PyAny>) -> PyResult<Self> {
+    check(!values.is_empty() && values.len() == counts.len(), "values and counts must be nonempty and the same length")?;
+    let total = counts.iter().map(|&c| c as u64).sum::<u64>();
+    check(total <= u32::MAX as u64, "the population is too large")?;
+    let a: Vec<(i64, u32)> = values.into_iter().zip(counts).collect();
+    let build: Build<i64> = Box::new(move |g| any_gen!(g, r => Box::new(WithoutReplacement::new(&a, r))));
+    Ok(PyWithoutReplacement { inner: Sampler::new(gen, false, build)?, left: total, total })
+  }
+
+  /// n draws as an array; n can't exceed the remaining population
+  fn sample_n<'py>(&mut self, py: Python<'py>, n: usize) -> PyResult<Bound<'py, PyArray1<i64>>> {
+    check(n as u64 <= self.left, "not enough of the population remains")?;
+    let v = self.inner.sample_n(py, n)?;
+    self.left -= n as u64;
+    Ok(v)
+  }
+
+  /// Restore the population and the generator's initial state
+  fn reset(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+    slf.inner.reset();
+    slf.left = slf.total;
+    slf
+  }
+
+  /// Discard n draws
+  fn skip(mut slf: PyRefMut<'_, Self>, n: usize) -> PyResult<PyRefMut<'_, Self>> {
+    check(n as u64 <= slf.left, "not enough of the population remains")?;
+    slf.inner.skip(n)?;
+    slf.left -= n as u64;
+    Ok(slf)
+  }
+
+  /// The size of the remaining population
+  #[getter]
+  fn remaining(&self) -> u64 {
+    self.left
+  }
+}
+
+#[pymodule]
+fn pyrand(m: &Bound<'_, PyModule>) -> PyResult<()> {
+  m.add_class::<PyLCG>()?;
+  m.add_class::<PyXorshift64>()?;
+  m.add_class::<PyMT19937>()?;
+  m.add_class::<PySobol>()?;
+  m.add_class::<PyUniform>()?;
+  m.add_class::<PyNormal>()?;
+  m.add_class::<PyExponential>()?;
+  m.add_class::<PyTruncatedNormal>()?;
+  m.add_class::<PyTruncatedExponential>()?;
+  m.add_class::<PyDiscrete>()?;
+  m.add_class::<PyDiscreteWeighted>()?;
+  m.add_class::<PyWithoutReplacement>()?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pyo3::types::PyDict;
+
+  // runs python code with the module's classes in scope, returning the value of `result`
+  fn run<T: for<'a, 'py> FromPyObject<'a, 'py>>(code: &str) -> PyResult<T> {
+    Python::initialize();
+    Python::attach(|py| {
+      let m = PyModule::new(py, "pyrand")?;
+      pyrand(&m)?;
+      let globals = PyDict::new(py);
+      for name in m.dict().keys() {
+        let name = name.extract::<String>()?;
+        globals.set_item(&name, m.getattr(&name)?)?;
+      }
+      py.run(&std::ffi::CString::new(code).unwrap(), Some(&globals), None)?;
+      globals.get_item("result")?.unwrap().extract::<T>().map_err(Into::into)
+    })
+  }
+
+  #[test]
+  fn test_generators() {
+    // the same values as rust, and reset and skip are chainable
+    let x: Vec<u32> = run("g = MT19937(19937)\nresult = [g.next_1() for _ in range(10)] + [g.reset().skip(9).next_1()]")
+      .unwrap();
+    let mut rng = MT19937::new(Some(19937));
+    assert_eq!(x[..10], rng.next_n(10)[..]);
+    assert_eq!(x[10], x[9]);
+    let seed: u32 = run("result = Xorshift64().seed").unwrap();
+    assert!(seed > 0);
+    assert_eq!(run::<u32>("result = LCG(19937).seed").unwrap(), 19937);
+    let x: u32 = run("result = LCG.from_seed_bytes(b'any length of seed').next_1()").unwrap();
+    assert_eq!(x, LCG::from_seed_bytes(b"any length of seed").next_1());
+    let u: f64 = run("result = Xorshift64(19937).uniform01()").unwrap();
+    assert_eq!(u, Xorshift64::new(Some(19937)).uniform01());
+    assert_eq!(run::<u32>("result = Sobol(3).skip(2).dim").unwrap(), 3);
+  }
+
+  #[test]
+  fn test_invalid() {
+    for code in ["result = LCG(0)", "result = Sobol(0)", "result = Normal(0.0, -1.0, LCG(1))",
+                 "result = Uniform(1.0, 0.0, LCG(1))", "result = Normal(0.0, 1.0, Sobol(1), method='polar')",
+                 "result = Normal(0.0, 1.0, LCG(1), method='box-muller')", "result = Exponential(1.0, 1.0)",
+                 "result = DiscreteWeighted([1, 2], [0.5, 0.6], LCG(1))", "result = Sobol(2).skip(1).next_n(3)",
+                 "result = WithoutReplacement([1, 2], [1, 1], LCG(1)).skip(3)"] {
+      let err = run::<Py<PyAny>>(code).unwrap_err();
+      Python::attach(|py| assert!(err.is_instance_of::<PyValueError>(py), "{}: {}", code, err));
+    }
+  }
+
+  #[test]
+  fn test_moved() {
+    // a generator passed to a distribution can't be used again, unless the distribution couldn't be created
+    let code = "g = LCG(19937)\nd = Exponential(2.0, g)\nresult = g.next_1()";
+    assert!(run::<u32>(code).unwrap_err().to_string().contains("moved"));
+    let code = "g = Sobol(1)\ntry:\n  Normal(0.0, 1.0, g, method='ziggurat')\nexcept ValueError:\n  pass\nresult = g.dim";
+    assert_eq!(run::<u32>(code).unwrap(), 1);
+  }
+
+  #[test]
+  fn test_distributions() {
+    // the distribution takes the generator in its current state, and reset returns to the generator's initial state
+    Python::initialize();
+    Python::attach(|py| {
+      let m = PyModule::new(py, "pyrand").unwrap();
+      pyrand(&m).unwrap();
+      let gen = m.getattr("Xorshift64").unwrap().call1((19937,)).unwrap();
+      gen.call_method1("skip", (10,)).unwrap();
+      let dist = m.getattr("Normal").unwrap().call1((1.0, 4.0, &gen, "ziggurat")).unwrap();
+      let mut dist = dist.cast::<PyNormal>().unwrap().borrow_mut();
+      let mut rng = Xorshift64::new(Some(19937));
+      rng.skip(10);
+      let x = dist.inner.dist.sample_n(100);
+      assert_eq!(x, Normal::<Ziggurat<Xorshift64>>::new(1.0, 4.0, rng).sample_n(100));
+      dist.inner.reset();
+      dist.inner.skip(10).unwrap();
+      let y = dist.inner.dist.sample_n(90);
+      assert_eq!(y, Normal::<Ziggurat<Xorshift64>>::new(1.0, 4.0, Xorshift64::new(Some(19937))).sample_n(100)[10..]);
+    });
+  }
+}
@@ -0,0 +1,61 @@
+# Run after `maturin develop`, with pytest
+import numpy as np
+import pytest
+
+import pyrand
+
+
+def test_arrays():
+    g = pyrand.MT19937(19937)
+    x = g.next_n(1000)
+    assert x.dtype == np.uint32 and x.shape == (1000,)
+    # the array's memory is the rust vector, kept alive by its base object rather than copied into a numpy buffer
+    assert not x.flags.owndata and not isinstance(x.base, np.ndarray)
+    assert x.flags.writeable
+    assert (g.reset().next_n(1000) == x).all()
+    u = g.reset().uniforms01(1000)
+    assert u.dtype == np.float64
+    assert np.allclose(u, x / 2.0**32)
+
+
+def test_skip():
+    g = pyrand.Xorshift64(19937)
+    x = g.next_n(100)
+    assert (g.reset().skip(10).next_n(90) == x[10:]).all()
+    s = pyrand.Sobol(2)
+    p = s.uniforms01(8)
+    assert (s.reset().skip(2).uniforms01(4) == p[4:]).all()
+    with pytest.raises(ValueError):
+        s.next_n(3)
+
+
+def test_distributions():
+    n = 100_000
+    for d, mean, var in [(pyrand.Uniform(-1.0, 3.0, pyrand.LCG(19937)), 1.0, 16.0 / 12.0),
+                         (pyrand.Normal(1.0, 4.0, pyrand.Xorshift64(19937), method="ziggurat"), 1.0, 4.0),
+                         (pyrand.Normal(1.0, 4.0, pyrand.Sobol(1)), 1.0, 4.0),
+                         (pyrand.Exponential(2.0, pyrand.MT19937(19937)), 0.5, 0.25)]:
+        x = d.sample_n(n)
+        assert x.dtype == np.float64
+        assert abs(x.mean() - mean) < 4 * np.sqrt(var / n)
+        assert abs(x.var() / var - 1) < 0.05
+        assert (d.reset().skip(10).sample_n(10) == x[10:20]).all()
+
+
+def test_discrete():
+    d = pyrand.DiscreteWeighted([1, 2, 3], [0.25, 0.25, 0.5], pyrand.MT19937(19937))
+    x = d.sample_n(10_000)
+    assert x.dtype == np.int64 and set(np.unique(x)) == {1, 2, 3}
+    w = pyrand.WithoutReplacement([0, 1], [3, 2], pyrand.LCG(19937))
+    assert sorted(w.sample_n(5)) == [0, 0, 0, 1, 1]
+    assert w.remaining == 0
+    with pytest.raises(ValueError):
+        w.sample_n(1)
+    assert w.reset().remaining == 5
+
+
+def test_moved():
+    g = pyrand.LCG(19937)
+    pyrand.Discrete([1, 2, 3], g)
+    with pytest.raises(ValueError):
+        g.next_n(1)
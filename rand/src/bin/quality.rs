@@ -0,0 +1,27 @@
+// Runs the default quality battery against the pseudorandom generators
+// usage: quality [seed]
+
+use rand::gen::RandomStream;
+use rand::gen::pseudo::*;
+use rand::quality::{Battery, Verdict};
+
+fn report<R: RandomStream>(name: &str, battery: &Battery, rng: &mut R) {
+  println!("{}", name);
+  println!("{:<20}{:>14}{:>14}  verdict", "test", "statistic", "p-value");
+  let results = battery.run(rng);
+  for result in &results {
+    println!("{}", result);
+  }
+  let failures = results.iter().filter(|r| r.verdict == Verdict::Fail).count();
+  let suspicious = results.iter().filter(|r| r.verdict == Verdict::Suspicious).count();
+  println!("{} tests, {} suspicious, {} failed\n", results.len(), suspicious, failures);
+}
+
+fn main() {
+  let seed = std::env::args().nth(1).map(|s| s.parse::<u32>().expect("seed must be a 32-bit unsigned integer"));
+  let battery = Battery::default();
+
+  report("LCG", &battery, &mut LCG::new(seed));
+  report("Xorshift64", &battery, &mut Xorshift64::new(seed));
+  report("MT19937", &battery, &mut MT19937::new(seed));
+}
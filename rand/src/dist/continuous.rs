@@ -1,59 +1,64 @@
-
 //use crate::dist::*;
 use crate::gen::{RandomStream, Dimensionless, Rejectable};
 use crate::dist::normal::polar::Polar;
 use crate::dist::normal::acklam::InverseCumulative;
+use crate::dist::normal::ziggurat::Ziggurat;
+use crate::dist::real::Real;
 use crate::dist::Dist;
+use alloc::vec::Vec;
+
+// The distributions are generic in the floating-point type F of the variates (f64 if unspecified). For normals the
+// transform must have the same type, e.g. Normal<Polar<R, f32>, f32>.
 
 #[derive(Debug)]
-pub struct Uniform<R> {
-  l: f64,
-  s: f64,
+pub struct Uniform<R, F = f64> {
+  l: F,
+  s: F,
   rng: R
 }
 
 #[derive(Debug)]
-pub struct Normal<T> {
-  mu: f64,
-  sigma: f64,
+pub struct Normal<T, F = f64> {
+  mu: F,
+  sigma: F,
   transform: T
 }
 
 #[derive(Debug)]
-pub struct Exponential<R> {
-  lambda: f64,
+pub struct Exponential<R, F = f64> {
+  lambda: F,
   rng: R
 }
 
-impl<R: RandomStream> Uniform<R> {
-  pub fn new(l: f64, h: f64, rng: R) -> Uniform<R> {
+impl<R: RandomStream, F: Real> Uniform<R, F> {
+  pub fn new(l: F, h: F, rng: R) -> Uniform<R, F> {
     assert!(h > l);
     Uniform{l, s: h-l, rng}
   }
 }
 
-impl<R: RandomStream> Dist<f64> for Uniform<R> {
+impl<R: RandomStream, F: Real> Dist<F> for Uniform<R, F> {
   // fn sample_1(&mut self) -> f64 {
   //   self.rng.uniform01() * self.s + self.l
   // }
 
-  fn sample_n(&mut self, n: usize) -> Vec<f64> {
-    self.rng.uniforms01(n).iter().map(|&x| self.l + self.s * x).collect()
+  fn sample_n(&mut self, n: usize) -> Vec<F> {
+    F::uniforms01(&mut self.rng, n).iter().map(|&x| self.l + self.s * x).collect()
   }
 }
 
-impl<R: RandomStream> Normal<InverseCumulative<R>> {
-  pub fn new(mean: f64, variance: f64, rng: R) -> Normal<InverseCumulative<R>> {
-    assert!(variance > 0.0);
+impl<R: RandomStream, F: Real> Normal<InverseCumulative<R, F>, F> {
+  pub fn new(mean: F, variance: F, rng: R) -> Normal<InverseCumulative<R, F>, F> {
+    assert!(variance > F::zero());
     Normal{mu: mean, sigma: variance.sqrt(), transform: InverseCumulative::new(rng) }
   }
 
-  pub fn sample_1(&mut self) -> f64 {
+  pub fn sample_1(&mut self) -> F {
     self.mu + self.sigma * self.transform.get_n(1)[0]
   }
 }
 
-impl<R: RandomStream> Dist<f64> for Normal<InverseCumulative<R>> {
+impl<R: RandomStream, F: Real> Dist<F> for Normal<InverseCumulative<R, F>, F> {
   /// Returns a vector of n normal variates
   ///
   /// # Arguments
@@ -69,17 +74,17 @@ impl<R: RandomStream> Dist<f64> for Normal<InverseCumulative<R>> {
   /// use rand::dist::{Dist, continuous::*, normal::*};
   /// use rand::dist::normal::acklam::InverseCumulative;
   /// // init Mersenne Twister using system clock
-  /// let mut normdist = Normal::<InverseCumulative<MT19937>>::new(0.0, 1.0, MT19937::new(None));
+  /// let mut normdist = Normal::<InverseCumulative<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937)));
   /// let v = normdist.sample_n(100);
   /// ```
-  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+  fn sample_n(&mut self, n: usize) -> Vec<F> {
     self.transform.get_n(n).iter().map(|&r| self.mu + self.sigma * r).collect()
   }
 }
 
-impl<R: RandomStream + Dimensionless + Rejectable> Normal<Polar<R>> {
-  pub fn new(mean: f64, variance: f64, rng: R) -> Normal<Polar<R>> {
-    assert!(variance > 0.0);
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Normal<Polar<R, F>, F> {
+  pub fn new(mean: F, variance: F, rng: R) -> Normal<Polar<R, F>, F> {
+    assert!(variance > F::zero());
     Normal{mu: mean, sigma: variance.sqrt(), transform: Polar::new(rng) }
   }
 
@@ -88,7 +93,7 @@ impl<R: RandomStream + Dimensionless + Rejectable> Normal<Polar<R>> {
   // }
 }
 
-impl<R: RandomStream + Dimensionless + Rejectable> Dist<f64> for Normal<Polar<R>> {
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Dist<F> for Normal<Polar<R, F>, F> {
   /// Returns a vector of n normal variates
   ///
   /// # Arguments
@@ -104,39 +109,65 @@ impl<R: RandomStream + Dimensionless + Rejectable> Dist<f64> for Normal<Polar<R>
   /// use rand::dist::{Dist, continuous::*, normal::*};
   /// use rand::dist::normal::polar::Polar;
   /// // init Mersenne Twister using system clock
-  /// let mut normdist = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(None));
+  /// let mut normdist = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937)));
   /// let v = normdist.sample_n(100);
   /// ```
-  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+  fn sample_n(&mut self, n: usize) -> Vec<F> {
+    self.transform.get_n(n).iter().map(|&r| self.mu + self.sigma * r).collect()
+  }
+}
+
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Normal<Ziggurat<R, F>, F> {
+  pub fn new(mean: F, variance: F, rng: R) -> Normal<Ziggurat<R, F>, F> {
+    assert!(variance > F::zero());
+    Normal{mu: mean, sigma: variance.sqrt(), transform: Ziggurat::new(rng) }
+  }
+}
+
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Dist<F> for Normal<Ziggurat<R, F>, F> {
+  /// Returns a vector of n normal variates
+  ///
+  /// # Example
+  /// ```
+  /// // Sample 100 single-precision normal variates using Marsaglia's Ziggurat method
+  /// use rand::gen::{*, pseudo::*};
+  /// use rand::dist::{Dist, continuous::*};
+  /// use rand::dist::normal::ziggurat::Ziggurat;
+  /// let mut normdist = Normal::<Ziggurat<Xorshift64, f32>, f32>::new(0.0, 1.0, Xorshift64::new(Some(19937)));
+  /// let v: Vec<f32> = normdist.sample_n(100);
+  /// ```
+  fn sample_n(&mut self, n: usize) -> Vec<F> {
     self.transform.get_n(n).iter().map(|&r| self.mu + self.sigma * r).collect()
   }
 }
 
 // TODO implement a transform layer (so can add e.g. Ziggurat)
 // currently hard-coded to inverse
-impl<R: RandomStream> Exponential<R> {
-  pub fn new(lambda: f64, rng: R) -> Exponential<R> {
-    assert!(lambda > 0.0);
+impl<R: RandomStream, F: Real> Exponential<R, F> {
+  pub fn new(lambda: F, rng: R) -> Exponential<R, F> {
+    assert!(lambda > F::zero());
     Exponential{lambda, rng}
   }
 }
 
-impl<R: RandomStream> Dist<f64> for Exponential<R> {
+impl<R: RandomStream, F: Real> Dist<F> for Exponential<R, F> {
   // fn sample_1<R: RandomStream + Dimensionless>(&mut self, rng: &mut R) -> f64 {
   //   -rng.uniform01().ln() / self.lambda
   // }
 
-  fn sample_n(&mut self, n: usize) -> Vec<f64> {
-    self.rng.uniforms01(n).iter().map(|&r| -r.ln() / self.lambda).collect()
+  fn sample_n(&mut self, n: usize) -> Vec<F> {
+    // 1 - r is in (0,1] exactly, for f32 too, so there are no infinities
+    F::uniforms01(&mut self.rng, n).iter().map(|&r| -(F::one() - r).ln() / self.lambda).collect()
   }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test {
   use super::*;
   use crate::gen::pseudo::*;
   use crate::gen::quasi::*;
   use crate::gen::entropy::*;
+  use crate::gen::Resettable;
 
   const TRIALS: usize = 60000;
 
@@ -217,6 +248,50 @@ mod test {
     Normal::<Polar<LCG>>::new(0.0, 0.0, LCG::new(None));
   }
 
+  #[test]
+  fn test_f32() {
+    fn moments(v: &[f32]) -> (f64, f64) {
+      let m = v.iter().map(|&x| x as f64).sum::<f64>() / v.len() as f64;
+      (m, v.iter().map(|&x| (x as f64 - m).powi(2)).sum::<f64>() / v.len() as f64)
+    }
+    let tol = 4.0 / (TRIALS as f64).sqrt();
+
+    let v = Uniform::new(-1.0f32, 1.0, LCG::new(Some(19937))).sample_n(TRIALS);
+    assert!(v.iter().all(|&x| (-1.0..1.0).contains(&x)));
+    let (m, var) = moments(&v);
+    assert!(m.abs() < tol && (var - 1.0 / 3.0).abs() < tol);
+
+    let (m, var) = moments(&Exponential::new(2.0f32, Xorshift64::new(Some(19937))).sample_n(TRIALS));
+    assert!((m - 0.5).abs() < tol && (var - 0.25).abs() < tol);
+
+    let normals: Vec<Vec<f32>> = vec![
+      Normal::<InverseCumulative<Sobol, f32>, f32>::new(1.0, 4.0, Sobol::new(1)).sample_n(TRIALS)[1..].to_vec(),
+      Normal::<Polar<MT19937, f32>, f32>::new(1.0, 4.0, MT19937::new(Some(19937))).sample_n(TRIALS),
+      Normal::<Ziggurat<Xorshift64, f32>, f32>::new(1.0, 4.0, Xorshift64::new(Some(19937))).sample_n(TRIALS)];
+    for v in &normals {
+      let (m, var) = moments(v);
+      assert!((m - 1.0).abs() < 2.0 * tol && (var - 4.0).abs() < 8.0 * tol);
+    }
+  }
+
+  #[test]
+  fn test_exponential_f32_finite() {
+    // a single-precision uniform is exactly 0 with probability 2^-24: this stream's first is its 30052739th value
+    let mut rng = Xorshift64::new(Some(19937));
+    rng.skip(30052000);
+    assert!(Exponential::new(1.0f32, rng).sample_n(1000).iter().all(|x| x.is_finite()));
+  }
+
+  #[test]
+  fn test_normal_ziggurat_xorshift() {
+    // the ziggurat transform is exact so f32 and f64 variates agree to single precision
+    let x = Normal::<Ziggurat<Xorshift64>>::new(0.0, 2.0, Xorshift64::new(Some(19937))).sample_n(TRIALS);
+    let y = Normal::<Ziggurat<Xorshift64, f32>, f32>::new(0.0, 2.0, Xorshift64::new(Some(19937))).sample_n(TRIALS);
+    let mu: f64 = x.iter().sum::<f64>() / (TRIALS as f64);
+    assert!(mu.abs() < 4.0 * (2.0 / (TRIALS as f64)).sqrt());
+    assert!(x.iter().zip(y).filter(|(&x, y)| (x - *y as f64).abs() > 1e-5 * x.abs().max(1.0)).count() < 10);
+  }
+
   #[test]
   fn test_normal_quasi() {
     // can't use rejection sampling with quasirandom generator
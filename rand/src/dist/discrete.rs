@@ -3,6 +3,8 @@ extern crate num;
 use num::Num;
 
 use crate::dist::*;
+use crate::gen::*;
+use alloc::vec::Vec;
 
 #[derive(Debug)]
 pub struct Discrete<R, T> {
@@ -72,7 +74,7 @@ impl<R: RandomStream, T: Num + Clone + Copy> DiscreteWeighted<R, T> {
     a.iter().fold(0.0, |_, p| { assert!(p.1 >= 0.0 && p.1 <= 1.0); p.1 } );
     let p = a.iter().fold(Vec::with_capacity(a.len()), |mut acc, p| { s += p.1; acc.push(s); acc });
     // check probabilities sum to unity
-    assert!(p.last().unwrap().abs() - 1.0 < std::f64::EPSILON);
+    assert!((p.last().unwrap() - 1.0).abs() < f64::EPSILON);
     DiscreteWeighted{ v: a.iter().fold(Vec::with_capacity(a.len()), |mut acc, p| { acc.push(p.0); acc }),
               p, rng }
   }
@@ -130,7 +132,56 @@ impl<R: RandomStream, T: Num + Clone + Copy> Dist<T> for WithoutReplacement<R, T
   }
 }
 
-#[cfg(test)]
+/// Walker's alias method (Vose's construction): O(1) sampling from a discrete distribution over 0..n
+#[derive(Debug, Clone)]
+pub struct AliasTable {
+  prob: Vec<f64>,
+  alias: Vec<usize>
+}
+
+impl AliasTable {
+  /// p must be nonnegative and sum to unity
+  pub fn new(p: &[f64]) -> AliasTable {
+    assert!(!p.is_empty());
+    assert!(p.iter().all(|&pi| pi >= 0.0));
+    assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    let n = p.len();
+    let mut prob = p.iter().map(|&pi| pi * n as f64).collect::<Vec<f64>>();
+    let mut alias = (0..n).collect::<Vec<usize>>();
+    let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| prob[i] < 1.0);
+    while let (Some(s), Some(&l)) = (small.pop(), large.last()) {
+      alias[s] = l;
+      prob[l] -= 1.0 - prob[s];
+      if prob[l] < 1.0 {
+        large.pop();
+        small.push(l);
+      }
+    }
+    // anything left over is (up to rounding) exactly 1
+    for i in small.into_iter().chain(large) {
+      prob[i] = 1.0;
+    }
+    AliasTable{ prob, alias }
+  }
+
+  pub fn len(&self) -> usize {
+    self.prob.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.prob.is_empty()
+  }
+
+  /// Map a single uniform variate in [0,1) to an index: the integer part selects the column and the fractional
+  /// part decides between it and its alias
+  pub fn sample_1(&self, u: f64) -> usize {
+    let x = u * self.prob.len() as f64;
+    let i = (x as usize).min(self.prob.len() - 1);
+    if x - (i as f64) < self.prob[i] { i } else { self.alias[i] }
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod test {
   use super::*;
   use crate::gen::pseudo::*;
@@ -141,7 +192,7 @@ mod test {
   #[test]
   fn test_discrete_lcg() {
     let mut h = vec![0; 6];
-    let mut die = Discrete::new(&vec![1,2,3,4,5,6], LCG::new(Some(19937)));
+    let mut die = Discrete::new(&[1,2,3,4,5,6], LCG::new(Some(19937)));
     let r = die.sample_n(TRIALS);
     for i in 0..TRIALS {
       h[r[i] as usize - 1] += 1;
@@ -163,7 +214,7 @@ mod test {
   #[test]
   fn test_discrete_xorshift() {
     let mut h = vec![0; 6];
-    let mut die = Discrete::new(&vec![1,2,3,4,5,6], Xorshift64::new(Some(19937)));
+    let mut die = Discrete::new(&[1,2,3,4,5,6], Xorshift64::new(Some(19937)));
     for _ in 0..TRIALS {
       h[die.sample_n(1)[0] as usize-1] += 1;
     }
@@ -189,7 +240,7 @@ mod test {
   fn test_discrete_flat_weighted_xorshift() {
     let mut h = vec![0; 6];
     let p = 1.0 / 6.0;
-    let mut fair_die = DiscreteWeighted::new(&vec![(1, p), (2, p), (3, p), (4, p), (5, p), (6, p)], Xorshift64::new(Some(19937)));
+    let mut fair_die = DiscreteWeighted::new(&[(1, p), (2, p), (3, p), (4, p), (5, p), (6, p)], Xorshift64::new(Some(19937)));
     for _ in 0..TRIALS {
       h[fair_die.sample_n(1)[0] as usize-1] += 1;
     }
@@ -203,15 +254,15 @@ mod test {
 
   #[test]
   fn test_discrete_weighted_xorshift() {
-    let mut h = vec![0; 6];
-    let mut fair_die = DiscreteWeighted::new(&vec![(1, 0.5), (2, 0.1), (3, 0.1), (4, 0.1), (5, 0.1), (6, 0.1)], Xorshift64::new(Some(19937)));
+    let mut h = [0; 6];
+    let mut fair_die = DiscreteWeighted::new(&[(1, 0.5), (2, 0.1), (3, 0.1), (4, 0.1), (5, 0.1), (6, 0.1)], Xorshift64::new(Some(19937)));
     for _ in 0..TRIALS {
       h[fair_die.sample_n(1)[0] as usize-1] += 1;
     }
     let lo = (TRIALS as f64 / 10.0 - 1.0 * (TRIALS as f64).sqrt()) as i32;
     let hi = (TRIALS as f64 / 10.0 + 1.0 * (TRIALS as f64).sqrt()) as i32;
-    for i in 1..h.len() {
-      assert!(h[i] > lo && h[i] < hi);
+    for &n in h.iter().skip(1) {
+      assert!(n > lo && n < hi);
     }
   }
 
@@ -225,7 +276,7 @@ mod test {
   #[test]
   #[should_panic]
   fn test_discrete_weighted_invalid2() {
-    DiscreteWeighted::new(&vec![(1, 0.0),(2, 1.1),(3, -0.1)], LCG::new(None));
+    DiscreteWeighted::new(&[(1, 0.0),(2, 1.1),(3, -0.1)], LCG::new(None));
   }
 
   #[test]
@@ -278,4 +329,33 @@ mod test {
     let v: Vec<(u32, u32)> = vec![];
     WithoutReplacement::new(&v, LCG::new(None));
   }
+
+  #[test]
+  fn test_alias_table() {
+    let p = [0.5, 0.1, 0.0, 0.25, 0.15];
+    let table = AliasTable::new(&p);
+    assert_eq!(table.len(), 5);
+    let mut h = [0; 5];
+    for u in Xorshift64::new(Some(19937)).uniforms01(TRIALS) {
+      h[table.sample_1(u)] += 1;
+    }
+    assert_eq!(h[2], 0);
+    for (&n, &pi) in h.iter().zip(&p) {
+      assert!((n as f64 - pi * TRIALS as f64).abs() < 4.0 * (TRIALS as f64 * pi * (1.0 - pi)).sqrt() + 1.0);
+    }
+    // deterministic (1d quasirandom) inputs reproduce the probabilities closely
+    let mut h = [0; 5];
+    for u in Sobol::new(1).uniforms01(1024) {
+      h[table.sample_1(u)] += 1;
+    }
+    for (&n, &pi) in h.iter().zip(&p) {
+      assert!((n as f64 - pi * 1024.0).abs() < 2.0);
+    }
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_alias_table_invalid() {
+    AliasTable::new(&[0.5, 0.6]);
+  }
 }
@@ -0,0 +1,348 @@
+// Uniform sampling on geometric domains: spheres, balls, simplices, discs, triangles and polygons
+use crate::gen::RandomStream;
+use crate::dist::Dist;
+use crate::dist::discrete::AliasTable;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+#[cfg(feature = "std")]
+use vector::Vector;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+/// Uniform points on the unit sphere in n dimensions, i.e. the surface of the n-ball, by normalising vectors of
+/// standard normals (Muller's method)
+#[derive(Debug)]
+pub struct Sphere<D> {
+  dim: usize,
+  normals: D
+}
+
+/// Uniform points in the unit n-ball: a vector x of standard normals scaled by 1/sqrt(|x|^2 + 2E) where E is a
+/// standard exponential (Barthe, Guedon, Mendelson & Naor, Ann. Probab. 33, 2005)
+#[derive(Debug)]
+pub struct Ball<D, E> {
+  dim: usize,
+  normals: D,
+  exponentials: E
+}
+
+/// Uniform points on the probability simplex, i.e. n nonnegative coordinates summing to 1 (a flat Dirichlet), by
+/// normalising standard exponentials
+#[derive(Debug)]
+pub struct Simplex<E> {
+  dim: usize,
+  exponentials: E
+}
+
+/// Uniform points in the unit disc, from 2 uniforms per point (so suitable for a 2-dimensional Sobol sequence)
+#[derive(Debug)]
+pub struct Disc<R> {
+  rng: R
+}
+
+/// Uniform points in a triangle, from 2 uniforms per point, reflecting those that fall in the other half of the
+/// parallelogram spanned by the edges (so suitable for a 2-dimensional Sobol sequence)
+#[derive(Debug)]
+pub struct Triangle<R> {
+  vertices: [[f64; 2]; 3],
+  rng: R
+}
+
+/// Uniform points in a simple (i.e. non-self-intersecting) polygon, which is triangulated by ear clipping. Each point
+/// takes 3 uniforms, the first choosing a triangle in proportion to its area.
+#[derive(Debug)]
+pub struct Polygon<R> {
+  triangles: Vec<[[f64; 2]; 3]>,
+  areas: AliasTable,
+  rng: R
+}
+
+impl<D: Dist<f64>> Sphere<D> {
+  /// `normals` samples standard normals, e.g. `Ziggurat<Xorshift64>`
+  pub fn new(dim: usize, normals: D) -> Sphere<D> {
+    assert!(dim > 0);
+    Sphere{dim, normals}
+  }
+
+  pub fn dim(&self) -> usize {
+    self.dim
+  }
+}
+
+impl<D: Dist<f64>> Dist<Vec<f64>> for Sphere<D> {
+  fn sample_n(&mut self, n: usize) -> Vec<Vec<f64>> {
+    self.normals.sample_n(n * self.dim).chunks(self.dim).map(|x| scale(x, 1.0 / norm(x))).collect()
+  }
+}
+
+/// Points on the unit sphere in 3 dimensions
+#[cfg(feature = "std")]
+impl<D: Dist<f64>> Dist<Vector> for Sphere<D> {
+  fn sample_n(&mut self, n: usize) -> Vec<Vector> {
+    assert_eq!(self.dim, 3);
+    self.normals.sample_n(3 * n).chunks(3).map(|x| Vector::new(x[0], x[1], x[2]) / norm(x)).collect()
+  }
+}
+
+impl<D: Dist<f64>, E: Dist<f64>> Ball<D, E> {
+  /// `normals` samples standard normals, e.g. `Ziggurat<Xorshift64>`, and `exponentials` exponentials with unit mean,
+  /// e.g. `exponential::ziggurat::Ziggurat<Xorshift64>`
+  pub fn new(dim: usize, normals: D, exponentials: E) -> Ball<D, E> {
+    assert!(dim > 0);
+    Ball{dim, normals, exponentials}
+  }
+
+  pub fn dim(&self) -> usize {
+    self.dim
+  }
+
+  // the scale factor for each point
+  fn scales(&mut self, x: &[f64], n: usize) -> Vec<f64> {
+    let e = self.exponentials.sample_n(n);
+    x.chunks(self.dim).zip(e).map(|(x, e)| 1.0 / (x.iter().map(|x| x * x).sum::<f64>() + 2.0 * e).sqrt()).collect()
+  }
+}
+
+impl<D: Dist<f64>, E: Dist<f64>> Dist<Vec<f64>> for Ball<D, E> {
+  fn sample_n(&mut self, n: usize) -> Vec<Vec<f64>> {
+    let x = self.normals.sample_n(n * self.dim);
+    let s = self.scales(&x, n);
+    x.chunks(self.dim).zip(s).map(|(x, s)| scale(x, s)).collect()
+  }
+}
+
+/// Points in the unit ball in 3 dimensions
+#[cfg(feature = "std")]
+impl<D: Dist<f64>, E: Dist<f64>> Dist<Vector> for Ball<D, E> {
+  fn sample_n(&mut self, n: usize) -> Vec<Vector> {
+    assert_eq!(self.dim, 3);
+    let x = self.normals.sample_n(3 * n);
+    let s = self.scales(&x, n);
+    x.chunks(3).zip(s).map(|(x, s)| Vector::new(x[0], x[1], x[2]) * s).collect()
+  }
+}
+
+impl<E: Dist<f64>> Simplex<E> {
+  /// `exponentials` samples exponentials, e.g. `exponential::ziggurat::Ziggurat<Xorshift64>` (the scale cancels)
+  pub fn new(dim: usize, exponentials: E) -> Simplex<E> {
+    assert!(dim > 0);
+    Simplex{dim, exponentials}
+  }
+
+  pub fn dim(&self) -> usize {
+    self.dim
+  }
+}
+
+impl<E: Dist<f64>> Dist<Vec<f64>> for Simplex<E> {
+  fn sample_n(&mut self, n: usize) -> Vec<Vec<f64>> {
+    self.exponentials.sample_n(n * self.dim).chunks(self.dim).map(|x| scale(x, 1.0 / x.iter().sum::<f64>())).collect()
+  }
+}
+
+impl<R: RandomStream> Disc<R> {
+  pub fn new(rng: R) -> Disc<R> {
+    Disc{rng}
+  }
+}
+
+impl<R: RandomStream> Dist<[f64; 2]> for Disc<R> {
+  fn sample_n(&mut self, n: usize) -> Vec<[f64; 2]> {
+    // the area within radius r is proportional to r^2
+    self.rng.uniforms01(2 * n).chunks(2).map(|u| {
+      let (r, theta) = (u[0].sqrt(), 2.0 * PI * u[1]);
+      [r * theta.cos(), r * theta.sin()]
+    }).collect()
+  }
+}
+
+impl<R: RandomStream> Triangle<R> {
+  pub fn new(vertices: [[f64; 2]; 3], rng: R) -> Triangle<R> {
+    assert!(cross(vertices[0], vertices[1], vertices[2]) != 0.0, "triangle has zero area");
+    Triangle{vertices, rng}
+  }
+}
+
+impl<R: RandomStream> Dist<[f64; 2]> for Triangle<R> {
+  fn sample_n(&mut self, n: usize) -> Vec<[f64; 2]> {
+    self.rng.uniforms01(2 * n).chunks(2).map(|u| in_triangle(&self.vertices, u[0], u[1])).collect()
+  }
+}
+
+impl<R: RandomStream> Polygon<R> {
+  /// `vertices` in order around the polygon, in either direction
+  pub fn new(vertices: &[[f64; 2]], rng: R) -> Polygon<R> {
+    assert!(vertices.len() > 2);
+    let triangles = triangulate(vertices);
+    let areas = triangles.iter().map(|t| cross(t[0], t[1], t[2]).abs()).collect::<Vec<_>>();
+    let total = areas.iter().sum::<f64>();
+    assert!(total > 0.0, "polygon has zero area");
+    let areas = AliasTable::new(&areas.iter().map(|a| a / total).collect::<Vec<_>>());
+    Polygon{triangles, areas, rng}
+  }
+}
+
+impl<R: RandomStream> Dist<[f64; 2]> for Polygon<R> {
+  fn sample_n(&mut self, n: usize) -> Vec<[f64; 2]> {
+    self.rng.uniforms01(3 * n).chunks(3)
+      .map(|u| in_triangle(&self.triangles[self.areas.sample_1(u[0])], u[1], u[2])).collect()
+  }
+}
+
+fn norm(x: &[f64]) -> f64 {
+  x.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+fn scale(x: &[f64], s: f64) -> Vec<f64> {
+  x.iter().map(|x| x * s).collect()
+}
+
+// twice the signed area of the triangle abc, positive if anticlockwise
+fn cross(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+  (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+// the point a + u(b-a) + v(c-a), reflected into the triangle if u + v > 1
+fn in_triangle(t: &[[f64; 2]; 3], u: f64, v: f64) -> [f64; 2] {
+  let (u, v) = if u + v > 1.0 { (1.0 - u, 1.0 - v) } else { (u, v) };
+  let [a, b, c] = *t;
+  [a[0] + u * (b[0] - a[0]) + v * (c[0] - a[0]), a[1] + u * (b[1] - a[1]) + v * (c[1] - a[1])]
+}
+
+// split a simple polygon into triangles by repeatedly cutting off an ear, i.e. a convex vertex whose triangle with its
+// neighbours contains no other vertex
+fn triangulate(p: &[[f64; 2]]) -> Vec<[[f64; 2]; 3]> {
+  let signed_area = (1..p.len() - 1).map(|i| cross(p[0], p[i], p[i + 1])).sum::<f64>();
+  // anticlockwise
+  let mut idx: Vec<usize> = if signed_area > 0.0 { (0..p.len()).collect() } else { (0..p.len()).rev().collect() };
+  let mut triangles = Vec::with_capacity(p.len() - 2);
+  while idx.len() > 3 {
+    let m = idx.len();
+    let corner = |i: usize| [p[idx[(i + m - 1) % m]], p[idx[i]], p[idx[(i + 1) % m]]];
+    let inside = |q: [f64; 2], [a, b, c]: [[f64; 2]; 3]| {
+      q != a && q != b && q != c && cross(a, b, q) >= 0.0 && cross(b, c, q) >= 0.0 && cross(c, a, q) >= 0.0
+    };
+    let area = |i: usize| {
+      let t = corner(i);
+      cross(t[0], t[1], t[2])
+    };
+    let i = (0..m).find(|&i| area(i) > 0.0 && idx.iter().all(|&j| !inside(p[j], corner(i))))
+      // otherwise drop a vertex in the middle of a straight edge
+      .or_else(|| (0..m).find(|&i| area(i) == 0.0))
+      .expect("polygon isn't simple");
+    if area(i) > 0.0 {
+      triangles.push(corner(i));
+    }
+    idx.remove(i);
+  }
+  triangles.push([p[idx[0]], p[idx[1]], p[idx[2]]]);
+  triangles
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::gen::quasi::*;
+  use crate::dist::normal::ziggurat::Ziggurat;
+  use crate::dist::exponential::ziggurat::Ziggurat as ExpZiggurat;
+
+  const N: usize = 20000;
+
+  // within 4 standard errors of a proportion p
+  fn close_p(count: usize, p: f64) -> bool {
+    (count as f64 / N as f64 - p).abs() < 4.0 * (p * (1.0 - p) / N as f64).sqrt()
+  }
+
+  #[test]
+  fn sphere() {
+    for dim in 1..6 {
+      let v: Vec<Vec<f64>> = Sphere::new(dim, Ziggurat::<_>::new(Xorshift64::new(Some(19937)))).sample_n(N);
+      assert!(v.iter().all(|x| x.len() == dim && (norm(x) - 1.0).abs() < 1e-15));
+      // each coordinate is symmetric, with E[x^2] = 1/dim
+      assert!(close_p(v.iter().filter(|x| x[0] > 0.0).count(), 0.5));
+      let m2 = v.iter().map(|x| x[dim - 1] * x[dim - 1]).sum::<f64>() / N as f64;
+      assert!((m2 - 1.0 / dim as f64).abs() < 0.02);
+    }
+    let v: Vec<Vector> = Sphere::new(3, Ziggurat::<_>::new(MT19937::new(Some(19937)))).sample_n(N);
+    assert!(v.iter().all(|x| x.is_normalized()));
+    // Archimedes: the height is uniform on [-1,1]
+    assert!(close_p(v.iter().filter(|x| x.k > 0.5).count(), 0.25));
+  }
+
+  #[test]
+  fn ball() {
+    for dim in 1..6 {
+      let mut ball = Ball::new(dim, Ziggurat::<_>::new(Xorshift64::new(Some(19937))),
+                               ExpZiggurat::<_>::new(Xorshift64::new(Some(1))));
+      let v: Vec<Vec<f64>> = ball.sample_n(N);
+      assert!(v.iter().all(|x| x.len() == dim && norm(x) < 1.0));
+      // the volume within radius r is r^dim
+      assert!(close_p(v.iter().filter(|x| norm(x) < 0.8).count(), 0.8f64.powi(dim as i32)));
+    }
+    let mut ball = Ball::new(3, Ziggurat::<_>::new(MT19937::new(Some(19937))),
+                             ExpZiggurat::<_>::new(MT19937::new(Some(1))));
+    let v: Vec<Vector> = ball.sample_n(N);
+    assert!(v.iter().all(|x| x.modulus() < 1.0));
+    assert!(close_p(v.iter().filter(|x| x.modulus() < 0.5).count(), 0.125));
+    assert!(close_p(v.iter().filter(|x| x.i > 0.0).count(), 0.5));
+  }
+
+  #[test]
+  fn simplex() {
+    let v = Simplex::new(4, ExpZiggurat::<_>::new(Xorshift64::new(Some(19937)))).sample_n(N);
+    assert!(v.iter().all(|x| x.iter().all(|&x| x >= 0.0) && (x.iter().sum::<f64>() - 1.0).abs() < 1e-15));
+    // the marginals are Beta(1, dim-1), so P(x > t) = (1-t)^(dim-1)
+    for i in 0..4 {
+      assert!(close_p(v.iter().filter(|x| x[i] > 0.3).count(), 0.7f64.powi(3)));
+    }
+  }
+
+  #[test]
+  fn disc() {
+    let v = Disc::new(LCG::new(Some(19937))).sample_n(N);
+    assert!(v.iter().all(|x| norm(x) < 1.0));
+    assert!(close_p(v.iter().filter(|x| norm(&x[..]) < 0.5).count(), 0.25));
+    assert!(close_p(v.iter().filter(|x| x[0] > 0.0 && x[1] > 0.0).count(), 0.25));
+    // the first Sobol point (1/2, 1/2) is halfway round at radius sqrt(1/2)
+    let x = Disc::new(Sobol::new(2)).sample_n(1)[0];
+    assert!((x[0] + 0.5f64.sqrt()).abs() < 1e-15 && x[1].abs() < 1e-15);
+  }
+
+  #[test]
+  fn triangle() {
+    let t = [[0.0, 0.0], [2.0, 0.0], [0.0, 1.0]];
+    let v = Triangle::new(t, Sobol::new(2)).sample_n(N);
+    assert!(v.iter().all(|&x| x[0] >= 0.0 && x[1] >= 0.0 && x[0] + 2.0 * x[1] <= 2.0));
+    // the mean is the centroid
+    let mean = v.iter().fold([0.0, 0.0], |m, x| [m[0] + x[0] / N as f64, m[1] + x[1] / N as f64]);
+    assert!((mean[0] - 2.0 / 3.0).abs() < 1e-3 && (mean[1] - 1.0 / 3.0).abs() < 1e-3);
+    let v = Triangle::new(t, MT19937::new(Some(19937))).sample_n(N);
+    // a quarter of the area is in the corner triangle with half the sides
+    assert!(close_p(v.iter().filter(|x| x[0] + 2.0 * x[1] < 1.0).count(), 0.25));
+  }
+
+  #[test]
+  #[should_panic]
+  fn degenerate_triangle() {
+    Triangle::new([[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]], LCG::new(Some(19937)));
+  }
+
+  #[test]
+  fn polygon() {
+    // an L shape (non-convex) of area 3, clockwise, with a vertex in the middle of an edge
+    let l = [[0.0, 0.0], [0.0, 2.0], [1.0, 2.0], [1.0, 1.0], [2.0, 1.0], [2.0, 0.0], [1.0, 0.0]];
+    let triangles = triangulate(&l);
+    assert!((triangles.iter().map(|t| cross(t[0], t[1], t[2])).sum::<f64>() - 6.0).abs() < 1e-15);
+    assert!(triangles.iter().all(|t| cross(t[0], t[1], t[2]) >= 0.0));
+    let v = Polygon::new(&l, Xorshift64::new(Some(19937))).sample_n(N);
+    assert!(v.iter().all(|x| (0.0..=2.0).contains(&x[0]) && (0.0..=2.0).contains(&x[1]) &&
+                             (x[0] <= 1.0 || x[1] <= 1.0)));
+    for square in &[[0.0, 0.0], [0.0, 1.0], [1.0, 0.0]] {
+      let inside = v.iter().filter(|x| x[0] - square[0] < 1.0 && x[1] - square[1] < 1.0 && x[0] >= square[0] &&
+                                       x[1] >= square[1]).count();
+      assert!(close_p(inside, 1.0 / 3.0));
+    }
+  }
+}
@@ -0,0 +1,298 @@
+// Distributions built from observed data, and kernel density estimation
+use crate::gen::RandomStream;
+use crate::dist::Dist;
+use crate::dist::normal;
+use crate::dist::fit::mean_var;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+/// Bandwidth of a Gaussian kernel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bandwidth {
+  /// Silverman's rule of thumb, 0.9 min(sd, IQR/1.34) n^-1/5, which is robust to outliers and multimodal data. If
+  /// the IQR is zero the sd is used instead (or |min| or 1 if that's zero too), as R's `bw.nrd0` does.
+  Silverman,
+  /// Scott's rule, 1.06 sd n^-1/5, optimal for normally distributed data
+  Scott,
+  Fixed(f64)
+}
+
+impl Bandwidth {
+  /// The bandwidth for (sorted) data
+  fn select(self, x: &[f64]) -> f64 {
+    let h = match self {
+      Bandwidth::Fixed(h) => h,
+      _ => {
+        assert!(x.len() > 1);
+        let n = x.len() as f64;
+        // the unbiased standard deviation
+        let sd = (mean_var(x).1 * n / (n - 1.0)).sqrt();
+        let spread = match self {
+          // as R's bw.nrd0, falling back to the sd, |x[0]| and then 1 when the IQR (and sd) are zero
+          Bandwidth::Silverman => {
+            let iqr = (quantile(x, 0.75) - quantile(x, 0.25)) / 1.34;
+            [sd.min(iqr), sd, x[0].abs(), 1.0].iter().copied().find(|&s| s > 0.0).unwrap() * 0.9
+          },
+          _ => sd * 1.06
+        };
+        spread * n.powf(-0.2)
+      }
+    };
+    assert!(h > 0.0, "bandwidth must be positive");
+    h
+  }
+}
+
+// sort, rejecting NaNs and infinities
+fn sorted(x: &[f64]) -> Vec<f64> {
+  assert!(!x.is_empty() && x.iter().all(|x| x.is_finite()));
+  let mut x = x.to_vec();
+  x.sort_by(f64::total_cmp);
+  x
+}
+
+// linear interpolation between the order statistics, at (i/(n-1), x[i])
+fn quantile(x: &[f64], p: f64) -> f64 {
+  assert!((0.0..=1.0).contains(&p));
+  let h = p * (x.len() - 1) as f64;
+  let i = (h as usize).min(x.len() - 1);
+  match x.get(i + 1) {
+    Some(&next) => x[i] + (h - i as f64) * (next - x[i]),
+    None => x[i]
+  }
+}
+
+/// The empirical distribution of a sample, which is sampled using one of the methods below
+#[derive(Debug)]
+pub struct Empirical<T> {
+  x: Vec<f64>,
+  method: T
+}
+
+/// Bootstrap resampling, i.e. drawing the observed values uniformly with replacement
+#[derive(Debug)]
+pub struct Bootstrap<R> {
+  rng: R
+}
+
+/// The smoothed bootstrap: a resampled value plus Gaussian noise scaled by the bandwidth, which samples the kernel
+/// density estimate. The variance is that of the data plus the square of the bandwidth.
+#[derive(Debug)]
+pub struct Smoothed<R, D> {
+  h: f64,
+  rng: R,
+  normals: D
+}
+
+/// Inversion of the empirical CDF interpolated linearly between the order statistics, so that the samples are
+/// continuous on [min, max]. This consumes exactly one variate per sample so can be used with quasirandom sequences.
+#[derive(Debug)]
+pub struct Interpolated<R> {
+  rng: R
+}
+
+impl<T> Empirical<T> {
+  /// The proportion of the data less than or equal to y
+  pub fn cdf(&self, y: f64) -> f64 {
+    self.x.partition_point(|&x| x <= y) as f64 / self.x.len() as f64
+  }
+
+  /// The interpolated inverse of the CDF, i.e. the sample quantile
+  pub fn inv_cdf(&self, p: f64) -> f64 {
+    quantile(&self.x, p)
+  }
+
+  pub fn len(&self) -> usize {
+    self.x.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.x.is_empty()
+  }
+
+  // the value resampled by a uniform
+  fn resample(&self, u: f64) -> f64 {
+    let n = self.x.len();
+    self.x[((u * n as f64) as usize).min(n - 1)]
+  }
+}
+
+impl<R: RandomStream> Empirical<Bootstrap<R>> {
+  pub fn new(x: &[f64], rng: R) -> Empirical<Bootstrap<R>> {
+    Empirical{x: sorted(x), method: Bootstrap{rng}}
+  }
+}
+
+impl<R: RandomStream> Dist<f64> for Empirical<Bootstrap<R>> {
+  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+    self.method.rng.uniforms01(n).iter().map(|&u| self.resample(u)).collect()
+  }
+}
+
+impl<R: RandomStream, D: Dist<f64>> Empirical<Smoothed<R, D>> {
+  /// `normals` samples standard normals, e.g. `Normal::<Ziggurat<Xorshift64>>::new(0.0, 1.0, ...)`
+  pub fn new(x: &[f64], bandwidth: Bandwidth, rng: R, normals: D) -> Empirical<Smoothed<R, D>> {
+    let x = sorted(x);
+    let h = bandwidth.select(&x);
+    Empirical{x, method: Smoothed{h, rng, normals}}
+  }
+
+  pub fn bandwidth(&self) -> f64 {
+    self.method.h
+  }
+}
+
+impl<R: RandomStream, D: Dist<f64>> Dist<f64> for Empirical<Smoothed<R, D>> {
+  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+    let u = self.method.rng.uniforms01(n);
+    let z = self.method.normals.sample_n(n);
+    u.iter().zip(z).map(|(&u, z)| self.resample(u) + self.method.h * z).collect()
+  }
+}
+
+impl<R: RandomStream> Empirical<Interpolated<R>> {
+  pub fn new(x: &[f64], rng: R) -> Empirical<Interpolated<R>> {
+    assert!(x.len() > 1);
+    Empirical{x: sorted(x), method: Interpolated{rng}}
+  }
+}
+
+impl<R: RandomStream> Dist<f64> for Empirical<Interpolated<R>> {
+  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+    self.method.rng.uniforms01(n).iter().map(|&u| quantile(&self.x, u)).collect()
+  }
+}
+
+/// Kernel density estimate with a Gaussian kernel
+#[derive(Debug)]
+pub struct Kde {
+  x: Vec<f64>,
+  h: f64
+}
+
+impl Kde {
+  pub fn new(x: &[f64], bandwidth: Bandwidth) -> Kde {
+    let x = sorted(x);
+    let h = bandwidth.select(&x);
+    Kde{x, h}
+  }
+
+  pub fn bandwidth(&self) -> f64 {
+    self.h
+  }
+
+  pub fn pdf(&self, y: f64) -> f64 {
+    self.x.iter().map(|&x| normal::pdf(y, x, self.h)).sum::<f64>() / self.x.len() as f64
+  }
+
+  pub fn cdf(&self, y: f64) -> f64 {
+    self.x.iter().map(|&x| normal::cdf(y, x, self.h)).sum::<f64>() / self.x.len() as f64
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::gen::quasi::*;
+  use crate::dist::continuous::Normal;
+  use crate::dist::normal::ziggurat::Ziggurat;
+  use compare::assert_close;
+
+  const DATA: [f64; 5] = [3.0, 1.0, 5.0, 2.0, 4.0];
+
+  #[test]
+  fn empirical_cdf() {
+    let e = Empirical::<Bootstrap<_>>::new(&DATA, LCG::new(Some(19937)));
+    assert_eq!(e.len(), 5);
+    assert_eq!(e.cdf(0.5), 0.0);
+    assert_eq!(e.cdf(1.0), 0.2);
+    assert_eq!(e.cdf(3.5), 0.6);
+    assert_eq!(e.cdf(5.0), 1.0);
+    assert_eq!(e.inv_cdf(0.0), 1.0);
+    assert_eq!(e.inv_cdf(0.5), 3.0);
+    assert_close!(e.inv_cdf(0.1), 1.4);
+    assert_eq!(e.inv_cdf(1.0), 5.0);
+  }
+
+  #[test]
+  fn bootstrap() {
+    let v = Empirical::<Bootstrap<_>>::new(&DATA, MT19937::new(Some(19937))).sample_n(10000);
+    // every value is drawn, with equal probability
+    for x in &DATA {
+      let p = v.iter().filter(|&y| y == x).count() as f64 / 10000.0;
+      assert!((p - 0.2).abs() < 3.0 * (0.2f64 * 0.8 / 10000.0).sqrt());
+    }
+  }
+
+  #[test]
+  fn smoothed() {
+    let x = Normal::<Ziggurat<Xorshift64>>::new(0.0, 1.0, Xorshift64::new(Some(1))).sample_n(1000);
+    let normals = Normal::<Ziggurat<Xorshift64>>::new(0.0, 1.0, Xorshift64::new(Some(19937)));
+    let mut e = Empirical::<Smoothed<_, _>>::new(&x, Bandwidth::Scott, MT19937::new(Some(19937)), normals);
+    let (m, v) = mean_var(&x);
+    let h = e.bandwidth();
+    // summed in a different order from the sorted data
+    assert_close!(h, 1.06 * (v * 1000.0 / 999.0).sqrt() * 1000.0f64.powf(-0.2), rel = 1e-14);
+    let (sm, sv) = mean_var(&e.sample_n(100000));
+    assert!((sm - m).abs() < 3.0 * ((v + h * h) / 100000.0).sqrt());
+    assert_close!(sv, v + h * h, rel = 0.02);
+  }
+
+  #[test]
+  fn interpolated() {
+    // the first Sobol point is the median
+    let mut e = Empirical::<Interpolated<_>>::new(&DATA, Sobol::new(1));
+    assert_eq!(e.sample_n(3), [3.0, 4.0, 2.0]);
+    let v = Empirical::<Interpolated<_>>::new(&DATA, Xorshift64::new(Some(19937))).sample_n(10000);
+    assert!(v.iter().all(|x| (1.0..=5.0).contains(x)));
+    assert!((mean_var(&v).0 - 3.0).abs() < 0.05);
+  }
+
+  #[test]
+  fn kde() {
+    // IQR / 1.34 = 2 / 1.34 < sd = 2.5^1/2
+    let k = Kde::new(&DATA, Bandwidth::Silverman);
+    assert_close!(k.bandwidth(), 0.9 * 2.0 / 1.34 * 5.0f64.powf(-0.2), rel = 1e-15);
+    assert_close!(Kde::new(&DATA, Bandwidth::Scott).bandwidth(), 1.06 * 2.5f64.sqrt() * 5.0f64.powf(-0.2), rel = 1e-15);
+    // the density integrates to the CDF
+    let dx = 0.001;
+    let integral = (0..10000).map(|i| k.pdf(-2.0 + dx * (i as f64 + 0.5)) * dx).sum::<f64>();
+    assert_close!(integral, k.cdf(8.0) - k.cdf(-2.0), rel = 1e-6);
+    assert_close!(k.cdf(3.0), 0.5, rel = 1e-15);
+    let k = Kde::new(&[0.0], Bandwidth::Fixed(2.0));
+    assert_eq!(k.pdf(1.0), normal::pdf(1.0, 0.0, 2.0));
+
+    // approaches the density of the data
+    let x = Normal::<Ziggurat<Xorshift64>>::new(0.0, 1.0, Xorshift64::new(Some(19937))).sample_n(10000);
+    let k = Kde::new(&x, Bandwidth::Silverman);
+    for &y in &[-2.0, -1.0, 0.0, 0.5, 1.5] {
+      assert!((k.pdf(y) - normal::pdf(y, 0.0, 1.0)).abs() < 0.02);
+    }
+  }
+
+  #[test]
+  fn kde_zero_iqr() {
+    // the sd is used when the IQR is zero
+    let x = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 5.0];
+    let sd = (mean_var(&x).1 * 7.0 / 6.0).sqrt();
+    assert_close!(Kde::new(&x, Bandwidth::Silverman).bandwidth(), 0.9 * sd * 7.0f64.powf(-0.2), rel = 1e-15);
+    // then |min|, then 1, when the data are constant
+    assert_close!(Kde::new(&[-2.0; 3], Bandwidth::Silverman).bandwidth(), 0.9 * 2.0 * 3.0f64.powf(-0.2));
+    assert_close!(Kde::new(&[0.0; 3], Bandwidth::Silverman).bandwidth(), 0.9 * 3.0f64.powf(-0.2));
+  }
+
+  #[test]
+  #[should_panic]
+  fn kde_constant() {
+    Kde::new(&[1.0, 1.0, 1.0], Bandwidth::Scott);
+  }
+
+  #[test]
+  #[should_panic]
+  fn empirical_nan() {
+    Empirical::<Bootstrap<_>>::new(&[1.0, f64::NAN], LCG::new(Some(19937)));
+  }
+}
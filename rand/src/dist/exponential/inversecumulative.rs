@@ -4,6 +4,7 @@ use crate::gen::RandomStream;
 use crate::gen::Rejectable;
 use crate::gen::Dimensionless;
 use super::inv_cdf;
+use alloc::vec::Vec;
 // adapted from Marsaglia & Tsang The Ziggurat Method for Generating Random Variables https://core.ac.uk/download/pdf/6287927.pdf
 
 // Marsaglia's Ziggurat method of sampling exponentials
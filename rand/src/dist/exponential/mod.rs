@@ -1,5 +1,11 @@
+use crate::dist::fit::Estimate;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
 
+#[cfg(feature = "alloc")]
 pub mod inversecumulative;
+#[cfg(feature = "alloc")]
 pub mod ziggurat;
 
 // Algorithms to transform uniform variates to exponential
@@ -30,18 +36,41 @@ pub fn inv_cdf(f: f64, lambda: f64) -> f64 {
   -(1.0-f).ln() / lambda
 }
 
-#[cfg(test)]
+/// Maximum likelihood estimate of lambda, the reciprocal of the mean
+pub fn fit(x: &[f64]) -> Estimate {
+  assert!(!x.is_empty() && x.iter().all(|&x| x >= 0.0));
+  let n = x.len() as f64;
+  let lambda = n / x.iter().sum::<f64>();
+  Estimate{value: lambda, std_err: lambda / n.sqrt()}
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod test {
   use super::*;
-  use crate::dist::compare::close_rel_eps;
-
+  use compare::assert_close;
+  use crate::gen::pseudo::*;
+  use crate::dist::Dist;
+  use crate::dist::continuous::Exponential;
 
   #[test]
   fn exp_basics() {
     for i in 1..10 {
       let x = i as f64;
-      assert!(close_rel_eps(inv_cdf(cdf(x, 1.0), 1.0), x, Some(1024.0 * std::f64::EPSILON)));
-      assert!(close_rel_eps(inv_pdf(pdf(x, 1.0), 1.0), x, Some(1024.0 * std::f64::EPSILON)));
+      assert_close!(inv_cdf(cdf(x, 1.0), 1.0), x, rel = 1024.0 * f64::EPSILON);
+      assert_close!(inv_pdf(pdf(x, 1.0), 1.0), x, rel = 1024.0 * f64::EPSILON);
     }
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn exp_fit() {
+    assert_eq!(fit(&[0.5, 1.0, 2.5]), Estimate{value: 0.75, std_err: 0.75 / 3.0f64.sqrt()});
+    let lambda = fit(&Exponential::new(2.0, Xorshift64::new(Some(19937))).sample_n(10000));
+    assert!((lambda.value - 2.0).abs() < 3.0 * lambda.std_err);
+  }
+
+  #[test]
+  #[should_panic]
+  fn exp_fit_negative() {
+    fit(&[1.0, -1.0]);
+  }
+}
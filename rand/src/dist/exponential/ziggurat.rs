@@ -3,41 +3,49 @@
 use crate::gen::RandomStream;
 use crate::gen::Rejectable;
 use crate::gen::Dimensionless;
+use crate::dist::Dist;
+use crate::dist::real::Real;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
 
 // adapted from Marsaglia & Tsang The Ziggurat Method for Generating Random Variables https://core.ac.uk/download/pdf/6287927.pdf
 
 const NSTRIPS: usize = 256;
 
-// Marsaglia's Ziggurat method of sampling exponentials
-pub struct Ziggurat<R> {
+// Marsaglia's Ziggurat method of sampling exponentials. The tables are computed in double precision and stored in F.
+pub struct Ziggurat<R, F = f64> {
   rng: R,
   //v: f64,
-  x: [f64; NSTRIPS+1],
-  r: [f64; NSTRIPS]
+  x: [F; NSTRIPS+1],
+  r: [F; NSTRIPS]
 }
 
-impl<R: RandomStream + Dimensionless + Rejectable> Ziggurat<R> {
-  pub fn new(rng: R) -> Ziggurat<R> {
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Ziggurat<R, F> {
+  pub fn new(rng: R) -> Ziggurat<R, F> {
     static X1: f64 = 7.697117470131487; /* start of the right tail */
     static V: f64 = 3.949659822581572e-3;
 
     let mut x = [0.0; NSTRIPS+1];
     let mut r = [0.0; NSTRIPS];
 
-    let f = V * X1.exp();
-    x[0] = V / f;
+    // [0] is the bottom strip (including the tail): V / f(R)
+    x[0] = V * X1.exp();
     x[1] = X1;
-    //x[NSTRIPS] = 0.0;
-
+    // x[NSTRIPS] = 0.0;
     for i in 2..NSTRIPS {
-      x[i] = (-2.0 * (V / x[i-1] + f).ln()).sqrt();
-      x[i] = -(V / x[i-1] + (-f).exp()).ln();
+      x[i] = -(V / x[i-1] + (-x[i-1]).exp()).ln();
     }
     for i in 0..NSTRIPS {
       r[i] = x[i+1] / x[i];
     }
 
-    Ziggurat{rng, x, r}
+    let mut xf = [F::zero(); NSTRIPS+1];
+    let mut rf = [F::zero(); NSTRIPS];
+    for (xf, &x) in xf.iter_mut().zip(x.iter()) { *xf = F::of_f64(x); }
+    for (rf, &r) in rf.iter_mut().zip(r.iter()) { *rf = F::of_f64(r); }
+    Ziggurat{rng, x: xf, r: rf}
   }
 
 // voidzigset(unsignedlongjsrseed)
@@ -76,42 +84,46 @@ impl<R: RandomStream + Dimensionless + Rejectable> Ziggurat<R> {
 //   }
 // }
 
-  // TODO
-  fn tail(&mut self, dmin: f64, neg: bool) -> f64 {
-    let mut x;
-    let mut y;
-    loop {
-      x = self.rng.uniform01().ln() / dmin;
-      y = self.rng.uniform01().ln();
-      if -2.0 * y >= x * x { break };
-    }
-    if neg { x - dmin } else { dmin - x }
+  // the exponential distribution is memoryless so the tail beyond dmin is just a shifted exponential
+  fn tail(&mut self, dmin: F) -> F {
+    dmin - (F::one() - F::uniform01(&mut self.rng)).ln()
   }
 
-  fn get_impl(&mut self) -> f64 {
+  fn get_impl(&mut self) -> F {
     loop {
-      let u = 2.0 * self.rng.uniform01() - 1.0;
+      let u = F::uniform01(&mut self.rng);
       let i = self.rng.next_1() as usize % NSTRIPS;
-      if u.abs() < self.r[i] { return u * self.x[i]; }
-      if i == 0 { return self.tail(self.x[1], u < 0.0); }
+      /* first try the rectangular boxes */
+      if u < self.r[i] { return u * self.x[i]; }
+      /* bottom box: sample from the tail */
+      if i == 0 { return self.tail(self.x[1]); }
+      /* is this a sample from the wedges? */
       let x = u * self.x[i];
-      let f0 = (-0.5 * (self.x[i] * self.x[i] - x * x)).exp();
-      let f1 = (-0.5 * (self.x[i+1] * self.x[i+1] - x * x)).exp();
-      if f1 + self.rng.uniform01() * (f0 - f1) < 1.0 { return x; }
+      let f0 = (x - self.x[i]).exp();
+      let f1 = (x - self.x[i+1]).exp();
+      if f1 + F::uniform01(&mut self.rng) * (f0 - f1) < F::one() { return x; }
     }
   }
 
-  pub fn get_n(&mut self, n: usize) -> Vec<f64> {
+  pub fn get_n(&mut self, n: usize) -> Vec<F> {
     (0..n).map(|_| self.get_impl()).collect()
   }
 
 }
 
+/// Unit-rate exponential variates
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Dist<F> for Ziggurat<R, F> {
+  fn sample_n(&mut self, n: usize) -> Vec<F> {
+    self.get_n(n)
+  }
+}
+
 
 #[cfg(test)]
 mod test {
   use super::*;
   use crate::gen::pseudo::*;
+  use crate::dist::exponential::cdf;
 
   const N: usize = 60000;
 
@@ -123,7 +135,30 @@ mod test {
     // mean should be < 1/lambda (=1) so sum should be ~N
     let eps = 2.0 * (N as f64).sqrt();
     assert!(v.iter().sum::<f64>() < (N as f64) + eps);
-    //assert!(v.iter().sum::<f64>() > (N as f64) - eps);
+    assert!(v.iter().sum::<f64>() > (N as f64) - eps);
+    assert!(v.iter().all(|&x| x >= 0.0));
+    // compare the empirical and exact CDFs
+    for &x in &[0.1, 0.5, 1.0, 2.0, 5.0, 8.0] {
+      let f = v.iter().filter(|&&vi| vi <= x).count() as f64 / N as f64;
+      assert!((f - cdf(x, 1.0)).abs() < 2.0 / (N as f64).sqrt());
+    }
+  }
+
+  #[test]
+  fn dist() {
+    let mut z = Ziggurat::new(Xorshift64::new(Some(19937)));
+    let m = z.sample_n(N).iter().sum::<f64>() / N as f64;
+    assert!((m - 1.0).abs() < 4.0 / (N as f64).sqrt());
+  }
+
+  #[test]
+  fn dist_f32() {
+    let v = Ziggurat::<_, f32>::new(MT19937::new(Some(19937))).sample_n(N);
+    assert!(v.iter().all(|&x| x >= 0.0 && x.is_finite()));
+    for &x in &[0.1f32, 0.5, 1.0, 2.0, 5.0, 8.0] {
+      let f = v.iter().filter(|&&vi| vi <= x).count() as f64 / N as f64;
+      assert!((f - cdf(x as f64, 1.0)).abs() < 2.0 / (N as f64).sqrt());
+    }
   }
 
 }
@@ -0,0 +1,47 @@
+// Fitting distribution parameters to observed data
+//
+// Each distribution module has a `fit` function returning estimates of the parameters of its `pdf` (or `pmf`), in the
+// same order. They are maximum likelihood estimates, closed-form except for the gamma shape which is found by Newton
+// iteration, and the standard errors are asymptotic, from the Fisher information at the estimates (except for the
+// uniform bounds, whose MLEs aren't asymptotically normal).
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+/// An estimated parameter and its standard error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+  pub value: f64,
+  pub std_err: f64
+}
+
+/// The log-likelihood of the data under a density (or mass function), e.g. to compare fits of different distributions
+pub fn log_likelihood<T: Copy, F: Fn(T) -> f64>(x: &[T], pdf: F) -> f64 {
+  x.iter().map(|&x| pdf(x).ln()).sum()
+}
+
+// mean and (biased, i.e. the MLE of the) variance
+pub(crate) fn mean_var(x: &[f64]) -> (f64, f64) {
+  let n = x.len() as f64;
+  let mean = x.iter().sum::<f64>() / n;
+  (mean, x.iter().map(|&x| (x - mean) * (x - mean)).sum::<f64>() / n)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::dist::{normal, exponential};
+  use compare::assert_close;
+
+  #[test]
+  fn likelihood() {
+    let x = [0.5, 1.0, 2.0];
+    let l = exponential::fit(&x);
+    // at the MLE the log-likelihood is n (ln lambda - 1)
+    assert_close!(log_likelihood(&x, |x| exponential::pdf(x, l.value)), 3.0 * (l.value.ln() - 1.0), rel = 1e-15);
+    // the normal fits better than a wider one
+    let (mu, sigma) = normal::fit(&x);
+    assert!(log_likelihood(&x, |x| normal::pdf(x, mu.value, sigma.value)) >
+            log_likelihood(&x, |x| normal::pdf(x, mu.value, 2.0 * sigma.value)));
+  }
+}
@@ -1,15 +1,30 @@
-use crate::gen::*;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
+#[cfg(feature = "alloc")]
 pub trait Dist<T> {
   fn sample_n(&mut self, n: usize) -> Vec<T>;
 }
 
+#[cfg(feature = "alloc")]
 pub mod discrete;
+#[cfg(feature = "alloc")]
 pub mod continuous;
+pub mod real;
 
 pub mod uniform;
 pub mod normal;
 pub mod exponential;
+pub mod gamma;
+pub mod poisson;
+#[cfg(feature = "alloc")]
+pub mod truncated;
+#[cfg(feature = "alloc")]
+pub mod empirical;
+#[cfg(feature = "alloc")]
+pub mod domain;
 
 pub mod moments;
+pub mod fit;
+#[cfg(feature = "std")]
 pub mod compare;
\ No newline at end of file
@@ -1,10 +1,13 @@
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
 
 #[derive(Debug)]
 pub struct Moments {
-  mean: f64,
-  variance: f64,
-  skew: f64,
-  kurtosis: f64
+  pub mean: f64,
+  pub variance: f64,
+  pub skew: f64,
+  pub kurtosis: f64
 }
 
 impl Moments {
@@ -18,7 +21,55 @@ impl Moments {
   }
 }
 
-#[cfg(test)]
+/// Running mean and variance that can be accumulated one value at a time and merged (Chan et al.), so that
+/// partial results computed independently (e.g. in parallel) can be combined
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OnlineMoments {
+  n: usize,
+  mean: f64,
+  m2: f64
+}
+
+impl OnlineMoments {
+  pub fn new() -> OnlineMoments {
+    OnlineMoments::default()
+  }
+
+  pub fn add(&mut self, x: f64) -> &mut Self {
+    self.n += 1;
+    let d = x - self.mean;
+    self.mean += d / self.n as f64;
+    self.m2 += d * (x - self.mean);
+    self
+  }
+
+  /// NB merging is not associative in floating point, so for reproducible results merge in a fixed order
+  pub fn merge(&mut self, other: &OnlineMoments) -> &mut Self {
+    if other.n > 0 {
+      let n = self.n + other.n;
+      let d = other.mean - self.mean;
+      self.mean += d * other.n as f64 / n as f64;
+      self.m2 += other.m2 + d * d * self.n as f64 * other.n as f64 / n as f64;
+      self.n = n;
+    }
+    self
+  }
+
+  pub fn count(&self) -> usize {
+    self.n
+  }
+
+  pub fn mean(&self) -> f64 {
+    self.mean
+  }
+
+  /// Unbiased sample variance
+  pub fn variance(&self) -> f64 {
+    self.m2 / (self.n as f64 - 1.0)
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod test {
   use super::*;
   use crate::gen::pseudo::*;
@@ -47,4 +98,24 @@ mod test {
     assert!(mq.skew.abs() < err_q);
     assert!((mq.kurtosis - 3.0).abs() < 10.0 * err_q);
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn online_moments() {
+    let v = Normal::<Polar<MT19937>>::new(1.0, 2.0, MT19937::new(Some(19937))).sample_n(1000);
+    let mut all = OnlineMoments::new();
+    v.iter().for_each(|&x| { all.add(x); });
+    let m = Moments::new(&v);
+    assert!((all.mean() - m.mean).abs() < 1e-14);
+    assert!((all.variance() - m.variance * 1000.0 / 999.0).abs() < 1e-12);
+
+    let mut merged = OnlineMoments::new();
+    for chunk in v.chunks(300) {
+      let mut part = OnlineMoments::new();
+      chunk.iter().for_each(|&x| { part.add(x); });
+      merged.merge(&part);
+    }
+    assert_eq!(merged.count(), 1000);
+    assert!((merged.mean() - all.mean()).abs() < 1e-14);
+    assert!((merged.variance() - all.variance()).abs() < 1e-12);
+  }
+}
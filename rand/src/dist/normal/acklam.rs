@@ -1,24 +1,64 @@
 // Algorithms to transform uniform variates to normal
+#[cfg(feature = "alloc")]
 use crate::gen::RandomStream;
 use crate::dist::normal::*;
+use crate::special;
+#[cfg(feature = "alloc")]
+use crate::dist::real::Real;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+
+/// Approximation used for the inverse normal CDF
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+  /// Peter Acklam's rational approximation, relative error below 1.15e-9
+  Acklam,
+  /// Wichura's algorithm AS241 (PPND16), relative error around 1e-16
+  Wichura,
+}
 
-
-// Acklam's inverse cumulative normal appproximation
+// Inverse cumulative normal transform, by default using Acklam's approximation refined with a Halley step. For f32
+// variates the uniforms are generated in single precision and the inversion is done in double.
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
-pub struct InverseCumulative<R>
+pub struct InverseCumulative<R, F = f64>
 {
-  rng: R
+  rng: R,
+  method: Method,
+  refine: bool,
+  float: PhantomData<F>,
 }
 
-pub fn inv_cdf(x: f64, mu: f64, sigma: f64) -> f64 {
-  standard_inv_cdf(x) * sigma + mu
+/// Inverse normal CDF using the given approximation, optionally refined with a step of Halley's method using the
+/// normal CDF, which gives full machine precision
+pub fn inv_cdf(x: f64, mu: f64, sigma: f64, method: Method, refine: bool) -> f64 {
+  standard_inv_cdf(x, method, refine) * sigma + mu
 }
 // standard (zero mean unit variance) implementation provided for efficient in below algorithms
 
-#[allow(clippy::excessive_precision)]
+fn standard_inv_cdf(x: f64, method: Method, refine: bool) -> f64 {
+  // is is_nan necessary?
+  assert!((0.0..=1.0).contains(&x));
+
+  if x == 0.0 { return f64::NEG_INFINITY; }
+  if x >= 1.0 { return  f64::INFINITY; }
+
+  let u = match method {
+    Method::Acklam => acklam(x),
+    Method::Wichura => special::ndtri(x),
+  };
+  if refine { halley(u, x) } else { u }
+}
 
 // Peter Acklam's inverse cumulative standard normal approximation
-fn standard_inv_cdf(x: f64) -> f64
+#[allow(clippy::excessive_precision)]
+fn acklam(x: f64) -> f64
 {
   const A0: f64 = -3.969683028665376e+01;
   const A1: f64 =  2.209460984245205e+02;
@@ -45,14 +85,8 @@ fn standard_inv_cdf(x: f64) -> f64
   const D2: f64 =  2.445134137142996e+00;
   const D3: f64 =  3.754408661907416e+00;
 
-  let mut t;
-  let mut u;
-
-  // is is_nan necessary?
-  assert!((0.0..=1.0).contains(&x));
-
-  if x == 0.0 { return std::f64::NEG_INFINITY; }
-  if x >= 1.0 { return  std::f64::INFINITY; }
+  let t;
+  let u;
 
   // q = min(x, 1.0 - x);
   let q = match x {
@@ -62,53 +96,65 @@ fn standard_inv_cdf(x: f64) -> f64
 
   if q > 0.02425 {
     /* Rational approximation for central region. */
-    u = q-0.5;
-    t = u*u;
-    u = u*(((((A0*t+A1)*t+A2)*t+A3)*t+A4)*t+A5) / (((((B0*t+B1)*t+B2)*t+B3)*t+B4)*t+1.0);
+    let v = q-0.5;
+    t = v*v;
+    u = v*(((((A0*t+A1)*t+A2)*t+A3)*t+A4)*t+A5) / (((((B0*t+B1)*t+B2)*t+B3)*t+B4)*t+1.0);
   } else {
     /* Rational approximation for tail region. */
     t = (-2.0*q.ln()).sqrt();
     u = (((((C0*t+C1)*t+C2)*t+C3)*t+C4)*t+C5) / ((((D0*t+D1)*t+D2)*t+D3)*t+1.0);
   }
 
-  /* The relative error of the approximation has absolute value less
-    than 1.15e-9.  One iteration of Halley's rational method (third
-    order) gives full machine precision... */
-  t = standard_cdf(u)-q;    /* error */
-  t = t*SQRT2PI*(u*u/2.0).exp();   /* f(u)/df(u) */
-  u = u-t/(1.0+u*t/2.0);     /* Halley's method */
-
   match x {
     x if x > 0.5 => -u,
     _ => u
   }
 }
 
-impl<R: RandomStream> InverseCumulative<R> {
+// One iteration of Halley's rational method (third order) applied to the lower tail, where the CDF is accurate
+fn halley(u: f64, x: f64) -> f64 {
+  let (u, q) = if x > 0.5 { (-u, 1.0 - x) } else { (u, x) };
+  let mut t = standard_cdf(u)-q;    /* error */
+  t = t*SQRT2PI*(u*u/2.0).exp();   /* f(u)/df(u) */
+  let u = u-t/(1.0+u*t/2.0);     /* Halley's method */
+  if x > 0.5 { -u } else { u }
+}
+
+#[cfg(feature = "alloc")]
+impl<R: RandomStream, F: Real> InverseCumulative<R, F> {
 
-  pub fn new(rng: R) -> InverseCumulative<R> {
-    InverseCumulative{ rng }
+  pub fn new(rng: R) -> InverseCumulative<R, F> {
+    InverseCumulative::with_method(rng, Method::Acklam, true)
   }
 
-  pub fn get_n(&mut self, n: usize) -> Vec<f64> {
-    self.rng.uniforms01(n).iter().map(|&x| standard_inv_cdf(x)).collect()
+  /// Use the given approximation, optionally refined with a Halley step
+  pub fn with_method(rng: R, method: Method, refine: bool) -> InverseCumulative<R, F> {
+    InverseCumulative{ rng, method, refine, float: PhantomData }
+  }
+
+  pub fn get_n(&mut self, n: usize) -> Vec<F> {
+    let (method, refine) = (self.method, self.refine);
+    F::uniforms01(&mut self.rng, n).iter()
+      .map(|&x| F::of_f64(standard_inv_cdf(x.to_f64().unwrap(), method, refine))).collect()
   }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
+#[allow(clippy::excessive_precision)]
 mod test {
   use super::*;
   use crate::gen::pseudo::*;
   use crate::gen::quasi::*;
+  use compare::assert_close;
   //use crate::gen::entropy::*;
 
   #[test]
   fn acklam_pseudo() {
     const N: usize = 10000;
     let f = (0..N).map(|i| (i as f64)/(N as f64)).collect::<Vec<f64>>();
-    let x = f.iter().map(|&fi| standard_inv_cdf(fi)).collect::<Vec<f64>>();
+    let x = f.iter().map(|&fi| standard_inv_cdf(fi, Method::Acklam, true)).collect::<Vec<f64>>();
     for i in 0..N {
-      assert!((f[i] - standard_cdf(x[i])).abs() < std::f64::EPSILON);
+      assert!((f[i] - standard_cdf(x[i])).abs() < f64::EPSILON);
     }
 
     let mut acklam = InverseCumulative::new(MT19937::new(Some(19937)));
@@ -121,9 +167,9 @@ mod test {
   fn acklam_sobol() {
     const N: usize = 10000;
     let f = (0..N).map(|i| (i as f64)/(N as f64)).collect::<Vec<f64>>();
-    let x = f.iter().map(|&fi| standard_inv_cdf(fi)).collect::<Vec<f64>>();
+    let x = f.iter().map(|&fi| standard_inv_cdf(fi, Method::Acklam, true)).collect::<Vec<f64>>();
     for i in 0..N {
-      assert!((f[i] - standard_cdf(x[i])).abs() < std::f64::EPSILON);
+      assert!((f[i] - standard_cdf(x[i])).abs() < f64::EPSILON);
     }
 
     let mut acklam = InverseCumulative::new(Sobol::new(1));
@@ -132,4 +178,31 @@ mod test {
     assert!(v.iter().sum::<f64>().abs() < 3.0);
   }
 
+  #[test]
+  fn tails() {
+    // reference quantiles computed to 22 digits
+    let cases = [(1e-300, -37.04709629936119923655), (1e-100, -21.27345356096532429418),
+                 (1e-20, -9.262340089798407579572), (1e-10, -6.3613409024040561991),
+                 (0.001, -3.090232306167813535358), (0.02, -2.053748910631823044339),
+                 (0.3, -0.5244005127080408159695), (0.75, 0.6744897501960817432022),
+                 (0.999999, 4.753424308817087765688)];
+    for &(p, x) in &cases {
+      assert_close!(inv_cdf(p, 0.0, 1.0, Method::Wichura, false), x, rel = 4.0 * f64::EPSILON);
+      assert_close!(inv_cdf(p, 0.0, 1.0, Method::Wichura, true), x, rel = 4.0 * f64::EPSILON);
+      assert_close!(inv_cdf(p, 0.0, 1.0, Method::Acklam, true), x, rel = 4.0 * f64::EPSILON);
+      assert_close!(inv_cdf(p, 0.0, 1.0, Method::Acklam, false), x, rel = 1.15e-9);
+    }
+    assert_eq!(inv_cdf(0.5, 1.0, 2.0, Method::Wichura, false), 1.0);
+    assert_eq!(inv_cdf(0.0, 0.0, 1.0, Method::Wichura, true), f64::NEG_INFINITY);
+  }
+
+  #[test]
+  fn wichura_stream() {
+    let mut a = InverseCumulative::<_, f64>::with_method(Sobol::new(1), Method::Wichura, false);
+    let mut b = InverseCumulative::<_, f64>::new(Sobol::new(1));
+    for (x, y) in a.get_n(1000).iter().zip(b.get_n(1000)) {
+      assert!((x - y).abs() < 1e-13);
+    }
+  }
+
 }
@@ -1,17 +1,18 @@
 //use crate::gen::*;
+use crate::special::erfc;
+use crate::dist::fit::{Estimate, mean_var};
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
 
+#[cfg(feature = "alloc")]
 pub mod polar;
 pub mod acklam;
+#[cfg(feature = "alloc")]
 pub mod ziggurat;
 
-// use C libm implementation (as erfc not in rust libm)
-//#[link(name = "m")] // libm.so already linked?
-extern {
-  fn erfc(x: f64) -> f64;
-}
-
-const SQRT2PI: f64 = 2.0 * std::f64::consts::SQRT_2 / std::f64::consts::FRAC_2_SQRT_PI;
-const R_SQRT2PI: f64 = std::f64::consts::FRAC_2_SQRT_PI / (2.0 * std::f64::consts::SQRT_2);
+const SQRT2PI: f64 = 2.0 * core::f64::consts::SQRT_2 / core::f64::consts::FRAC_2_SQRT_PI;
+const R_SQRT2PI: f64 = core::f64::consts::FRAC_2_SQRT_PI / (2.0 * core::f64::consts::SQRT_2);
 
 pub fn pdf(x: f64, mu: f64, sigma: f64) -> f64 {
   (-0.5*((x-mu)/sigma).powi(2)).exp() * R_SQRT2PI / sigma
@@ -21,6 +22,15 @@ pub fn cdf(x: f64, mu: f64, sigma: f64) -> f64 {
   standard_cdf((x-mu)/sigma) 
 } 
 
+/// Maximum likelihood estimates of mu and sigma, i.e. the mean and the (biased) standard deviation
+pub fn fit(x: &[f64]) -> (Estimate, Estimate) {
+  assert!(x.len() > 1);
+  let n = x.len() as f64;
+  let (mean, var) = mean_var(x);
+  let sigma = var.sqrt();
+  (Estimate{value: mean, std_err: sigma / n.sqrt()}, Estimate{value: sigma, std_err: sigma / (2.0 * n).sqrt()})
+}
+
 // pub fn inv_cdf(x: f64, mu: f64, sigma: f64) -> f64 {
 //   //standard_inv_cdf(x) * sigma + mu
 // }
@@ -28,7 +38,34 @@ pub fn cdf(x: f64, mu: f64, sigma: f64) -> f64 {
 
 // standard normal i.e. zero mean unit variance 
 fn standard_cdf(x: f64) -> f64 {
-  0.5 * unsafe { erfc(-x * std::f64::consts::FRAC_1_SQRT_2) }
+  0.5 * erfc(-x * core::f64::consts::FRAC_1_SQRT_2)
 }
 
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::dist::Dist;
+  use crate::dist::continuous::Normal;
+  use compare::assert_close;
 
+  #[test]
+  fn normal_fit() {
+    let (mu, sigma) = fit(&[1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(mu, Estimate{value: 2.5, std_err: 1.25f64.sqrt() / 2.0});
+    assert_close!(sigma.value, 1.25f64.sqrt());
+    assert_close!(sigma.std_err, 1.25f64.sqrt() / 8.0f64.sqrt());
+
+    let x = Normal::<polar::Polar<MT19937>>::new(1.0, 4.0, MT19937::new(Some(19937))).sample_n(10000);
+    let (mu, sigma) = fit(&x);
+    assert!((mu.value - 1.0).abs() < 3.0 * mu.std_err);
+    assert!((sigma.value - 2.0).abs() < 3.0 * sigma.std_err);
+    assert_close!(mu.std_err, 0.02, rel = 0.05);
+  }
+
+  #[test]
+  #[should_panic]
+  fn normal_fit_one() {
+    fit(&[1.0]);
+  }
+}
@@ -2,30 +2,33 @@
 use crate::gen::RandomStream;
 use crate::gen::Rejectable;
 use crate::gen::Dimensionless;
+use crate::dist::real::Real;
+use alloc::vec::Vec;
 
 // Marsaglia's polar method of sampling normals
 #[derive(Debug)]
-pub struct Polar<R> {
+pub struct Polar<R, F = f64> {
   rng: R,
   is_cached: bool,
-  cached_val: f64
+  cached_val: F
 }
 
-impl<R: RandomStream + Dimensionless + Rejectable> Polar<R> {
-  pub fn new(rng: R) -> Polar<R> {
-    Polar{rng, is_cached: false, cached_val: std::f64::NAN}
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Polar<R, F> {
+  pub fn new(rng: R) -> Polar<R, F> {
+    Polar{rng, is_cached: false, cached_val: F::nan()}
   }
 
-  fn get_impl(&mut self) -> f64 {
+  fn get_impl(&mut self) -> F {
     if self.is_cached {
       self.is_cached = false;
       return self.cached_val;
     }
+    let two = F::of_f64(2.0);
     loop {
-      let (x,y) = (self.rng.uniform01() * 2.0 - 1.0, self.rng.uniform01() * 2.0 - 1.0);
+      let (x,y) = (F::uniform01(&mut self.rng) * two - F::one(), F::uniform01(&mut self.rng) * two - F::one());
       let s = x*x + y*y;
-      if s > 0.0 && s < 1.0 {
-        let m = (-2.0 * s.ln() / s).sqrt();
+      if s > F::zero() && s < F::one() {
+        let m = (-two * s.ln() / s).sqrt();
         self.is_cached = true;
         self.cached_val = y * m;
         return x * m;
@@ -33,13 +36,13 @@ impl<R: RandomStream + Dimensionless + Rejectable> Polar<R> {
     }
   }
 
-  pub fn get_n(&mut self, n: usize) -> Vec<f64> {
+  pub fn get_n(&mut self, n: usize) -> Vec<F> {
     (0..n).map(|_| self.get_impl()).collect()
   }
 }
 
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test {
   use super::*;
   use crate::gen::pseudo::*;
@@ -51,7 +54,7 @@ mod test {
   //   let f = (0..N).map(|i| (i as f64)/(N as f64)).collect::<Vec<f64>>();
   //   let x = f.iter().map(|&fi| standard_inv_cdf(fi)).collect::<Vec<f64>>();
   //   for i in 0..N {
-  //     assert!((f[i] - standard_cdf(x[i])).abs() < std::f64::EPSILON);
+  //     assert!((f[i] - standard_cdf(x[i])).abs() < f64::EPSILON);
   //   }
 
     let mut polar = Polar::new(MT19937::new(Some(77027465)));
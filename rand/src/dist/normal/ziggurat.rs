@@ -2,21 +2,50 @@
 use crate::gen::RandomStream;
 use crate::gen::Rejectable;
 use crate::gen::Dimensionless;
+use crate::gen::Rewindable;
+use crate::dist::Dist;
+use crate::dist::real::Real;
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
 
 // adapted from https://www.doornik.com/research/ziggurat.pdf
 
 const NSTRIPS: usize = 128;
 
-// Marsaglia's Ziggurat method of sampling normals
-pub struct Ziggurat<R> {
+// Marsaglia's Ziggurat method of sampling normals. The tables are computed in double precision and stored in F.
+pub struct Ziggurat<R, F = f64> {
   rng: R,
   //v: f64,
-  x: [f64; NSTRIPS+1],
-  r: [f64; NSTRIPS]
+  x: [F; NSTRIPS+1],
+  r: [F; NSTRIPS]
 }
 
-impl<R: RandomStream + Dimensionless + Rejectable> Ziggurat<R> {
-  pub fn new(rng: R) -> Ziggurat<R> {
+// sample from the tail beyond dmin (=x[1])
+fn tail<F: Real>(dmin: F, neg: bool, mut uniform01: impl FnMut() -> F) -> F {
+  let mut x;
+  let mut y;
+  loop {
+    x = uniform01().ln() / dmin;
+    y = uniform01().ln();
+    if -F::of_f64(2.0) * y >= x * x { break };
+  }
+  if neg { x - dmin } else { dmin - x }
+}
+
+impl<R, F: Real> Ziggurat<R, F> {
+  // whether x, in the wedge of strip i, is under the density
+  fn wedge(&self, i: usize, x: F, u: F) -> bool {
+    let half = F::of_f64(0.5);
+    let f0 = (-half * (self.x[i] * self.x[i] - x * x)).exp();
+    let f1 = (-half * (self.x[i+1] * self.x[i+1] - x * x)).exp();
+    f1 + u * (f0 - f1) < F::one()
+  }
+}
+
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Ziggurat<R, F> {
+  pub fn new(rng: R) -> Ziggurat<R, F> {
 
     static X1: f64 = 3.442619855899; /* start of the right tail */
     static V: f64 = 9.91256303526217e-3;
@@ -24,18 +53,24 @@ impl<R: RandomStream + Dimensionless + Rejectable> Ziggurat<R> {
     let mut x = [0.0; NSTRIPS+1];
     let mut r = [0.0; NSTRIPS];
 
-    let f = (0.5 * X1 * X1).exp();
+    // f is the density (up to normalisation) at the top of the previous strip
+    let mut f = (-0.5 * X1 * X1).exp();
     x[0] = V / f;
     x[1] = X1;
     //x[NSTRIPS] = 0.0;
     for i in 2..NSTRIPS {
       x[i] = (-2.0 * (V / x[i-1] + f).ln()).sqrt();
+      f = (-0.5 * x[i] * x[i]).exp();
     }
     for i in 0..NSTRIPS {
       r[i] = x[i+1] / x[i];
     }
 
-    Ziggurat{rng, x, r}
+    let mut xf = [F::zero(); NSTRIPS+1];
+    let mut rf = [F::zero(); NSTRIPS];
+    for (xf, &x) in xf.iter_mut().zip(x.iter()) { *xf = F::of_f64(x); }
+    for (rf, &r) in rf.iter_mut().zip(r.iter()) { *rf = F::of_f64(r); }
+    Ziggurat{rng, x: xf, r: rf}
   }
 
   // #define ZIGNOR_C 128                   /* number of blocks */
@@ -73,17 +108,6 @@ impl<R: RandomStream + Dimensionless + Rejectable> Ziggurat<R> {
   //   return iNegative ? x - dMin : dMin - x;
   // }
 
-  // TODO remove dmin (=x[1])
-  fn tail(&mut self, dmin: f64, neg: bool) -> f64 {
-    let mut x;
-    let mut y;
-    loop {
-      x = self.rng.uniform01().ln() / dmin;
-      y = self.rng.uniform01().ln();
-      if -2.0 * y >= x * x { break };
-    }
-    if neg { x - dmin } else { dmin - x }
-  }
 
   //double  DRanNormalZig(void){
     // unsigned int i;
@@ -104,38 +128,177 @@ impl<R: RandomStream + Dimensionless + Rejectable> Ziggurat<R> {
     //   }
     // }
 
-  fn get_impl(&mut self) -> f64 {
+  fn get_impl(&mut self) -> F {
     loop {
-      let u = 2.0 * self.rng.uniform01() - 1.0;
+      let u = F::of_f64(2.0) * F::uniform01(&mut self.rng) - F::one();
       let i = self.rng.next_1() as usize % NSTRIPS;
       if u.abs() < self.r[i] { return u * self.x[i]; }
-      if i == 0 { return self.tail(self.x[1], u < 0.0); }
+      let rng = &mut self.rng;
+      if i == 0 { return tail(self.x[1], u < F::zero(), || F::uniform01(rng)); }
       let x = u * self.x[i];
-      let f0 = (-0.5 * (self.x[i] * self.x[i] - x * x)).exp();
-      let f1 = (-0.5 * (self.x[i+1] * self.x[i+1] - x * x)).exp();
-      if f1 + self.rng.uniform01() * (f0 - f1) < 1.0 { return x; }
+      let v = F::uniform01(&mut self.rng);
+      if self.wedge(i, x, v) { return x; }
     }
   }
 
-  pub fn get_n(&mut self, n: usize) -> Vec<f64> {
+  pub fn get_n(&mut self, n: usize) -> Vec<F> {
     (0..n).map(|_| self.get_impl()).collect()
   }
 }
 
+/// Generation in blocks, for generators that can return integers drawn ahead
+impl<R: RandomStream + Rewindable + Rejectable, F: Real> Ziggurat<R, F> {
+  /// As `get_n`, but drawing integers from the generator in blocks (which e.g. `Xorshift64` generates with SIMD) and
+  /// testing the rectangles in a tight loop. The variates, and the state of the generator afterwards, are identical
+  /// to `get_n`.
+  pub fn get_n_batched(&mut self, n: usize) -> Vec<F> {
+    let x: Vec<f64> = self.x.iter().map(|x| x.to_f64().unwrap()).collect();
+    let r: Vec<f64> = self.r.iter().map(|r| r.to_f64().unwrap()).collect();
+    let mut result = Vec::with_capacity(n);
+    let mut ahead = Ahead { buf: Vec::new(), pos: 0 };
+    while result.len() < n {
+      // enough for the remaining variates if there are no rejections, so little is left over to rewind
+      let pairs = (ahead.fill(&mut self.rng, (2 * (n - result.len())).min(BLOCK)) / 2).min(n - result.len());
+      let raw = &ahead.buf[ahead.pos..ahead.pos + 2 * pairs];
+      let k = rectangles(raw, &x, &r, &mut result);
+      ahead.pos += 2 * k;
+      if k == pairs {
+        continue;
+      }
+      // the first rejection: the tail or a wedge, drawing further uniforms from the block
+      let u = F::of_f64(2.0) * F::uniform_from_u32(ahead.buf[ahead.pos]) - F::one();
+      let i = ahead.buf[ahead.pos + 1] as usize % NSTRIPS;
+      ahead.pos += 2;
+      let rng = &mut self.rng;
+      if i == 0 {
+        result.push(tail(self.x[1], u < F::zero(), || F::uniform_from_u32(ahead.next(rng))));
+      } else {
+        let x = u * self.x[i];
+        let v = F::uniform_from_u32(ahead.next(rng));
+        if self.wedge(i, x, v) { result.push(x); }
+      }
+    }
+    self.rng.rewind(ahead.buf.len() - ahead.pos);
+    result
+  }
+}
+
+// maximum number of integers drawn ahead
+const BLOCK: usize = 1024;
+
+// integers drawn ahead from the generator, of which those from pos are unused
+struct Ahead {
+  buf: Vec<u32>,
+  pos: usize,
+}
+
+impl Ahead {
+  // ensure there are at least 2 unused, drawing n more if not, and return the (even) number available
+  fn fill<R: RandomStream>(&mut self, rng: &mut R, n: usize) -> usize {
+    if self.buf.len() - self.pos < 2 {
+      self.buf.drain(..self.pos);
+      self.buf.append(&mut rng.next_n(n.max(2)));
+      self.pos = 0;
+    }
+    (self.buf.len() - self.pos) & !1
+  }
+
+  fn next<R: RandomStream>(&mut self, rng: &mut R) -> u32 {
+    if self.pos == self.buf.len() {
+      self.buf = rng.next_n(16);
+      self.pos = 0;
+    }
+    self.pos += 1;
+    self.buf[self.pos - 1]
+  }
+}
+
+// Accept variates (u, i) from consecutive pairs of integers while they lie in the rectangles, returning the number
+// of pairs accepted. The uniforms are exact in f64 for either precision, and the product of u and x[i] is exact
+// before rounding to F, so the variates are identical to those calculated in F. (A gather-based AVX2 version of this
+// loop was slower than the scalar one.)
+fn rectangles<F: Real>(raw: &[u32], x: &[f64], r: &[f64], result: &mut Vec<F>) -> usize {
+  for (k, pair) in raw.chunks_exact(2).enumerate() {
+    let u = 2.0 * F::uniform_from_u32(pair[0]).to_f64().unwrap() - 1.0;
+    let i = pair[1] as usize % NSTRIPS;
+    if u.abs() >= r[i] {
+      return k;
+    }
+    result.push(F::of_f64(u * x[i]));
+  }
+  raw.len() / 2
+}
+
+/// Standard normal variates
+impl<R: RandomStream + Dimensionless + Rejectable, F: Real> Dist<F> for Ziggurat<R, F> {
+  fn sample_n(&mut self, n: usize) -> Vec<F> {
+    self.get_n(n)
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
   use crate::gen::pseudo::*;
+  use crate::dist::normal::cdf;
+  use compare::assert_close;
 
   const N: usize = 60000;
 
+  #[test]
+  fn table() {
+    // every strip has area V, i.e. x[i] (f(x[i+1]) - f(x[i])) = V with f(x) = exp(-x^2/2), and the base strip, with
+    // the tail, too (the last strip, which is determined by the others, only to the precision of X1 and V)
+    let z = Ziggurat::<_, f64>::new(Xorshift64::new(Some(19937)));
+    let f = |x: f64| (-0.5 * x * x).exp();
+    let v = 9.91256303526217e-3;
+    assert_close!(z.x[0] * f(z.x[1]), v);
+    for i in 1..NSTRIPS - 1 {
+      assert_close!(z.x[i] * (f(z.x[i+1]) - f(z.x[i])), v, rel = 1e-12);
+    }
+    assert_close!(z.x[NSTRIPS - 1] * (1.0 - f(z.x[NSTRIPS - 1])), v, rel = 1e-8);
+    assert_eq!(z.x[NSTRIPS], 0.0);
+  }
+
   #[test]
   fn test_ziggurat() {
 
     let mut z = Ziggurat::new(MT19937::new(Some(19937)));
     let v = z.get_n(N);
-    // mean should be < 1/sqrt(N) so sum should be < sqrt(N)
-    assert!(v.iter().sum::<f64>() < (N as f64).sqrt());
+    // the standard error of the mean is 1/sqrt(N) so |sum| should be < 4 sqrt(N)
+    assert!(v.iter().sum::<f64>().abs() < 4.0 * (N as f64).sqrt());
+    // the variance should be 1
+    assert!((v.iter().map(|x| x * x).sum::<f64>() / N as f64 - 1.0).abs() < 4.0 * (2.0 / N as f64).sqrt());
+    // compare the empirical and exact CDFs, including the tail
+    for &x in &[-3.5, -1.0, 0.0, 0.5, 2.0, 3.0] {
+      let f = v.iter().filter(|&&vi| vi <= x).count() as f64 / N as f64;
+      assert!((f - cdf(x, 0.0, 1.0)).abs() < 2.0 / (N as f64).sqrt());
+    }
+  }
+
+  #[test]
+  fn batched() {
+    fn check<F: Real>() {
+      let mut a = Ziggurat::<_, F>::new(Xorshift64::new(Some(19937)));
+      let mut b = Ziggurat::<_, F>::new(Xorshift64::new(Some(19937)));
+      // in several calls of different lengths, so blocks are left over and rewound
+      for &n in &[1, 7, 100, 5000, N] {
+        assert_eq!(a.get_n_batched(n), b.get_n(n));
+      }
+      assert_eq!(a.rng.next_1(), b.rng.next_1());
+    }
+    check::<f64>();
+    check::<f32>();
+  }
+
+  #[test]
+  fn test_ziggurat_f32() {
+    let mut z = Ziggurat::<_, f32>::new(Xorshift64::new(Some(19937)));
+    let v = z.sample_n(N);
+    for &x in &[-3.5f32, -1.0, 0.0, 0.5, 2.0, 3.0] {
+      let f = v.iter().filter(|&&vi| vi <= x).count() as f64 / N as f64;
+      assert!((f - cdf(x as f64, 0.0, 1.0)).abs() < 2.0 / (N as f64).sqrt());
+    }
   }
 }
 
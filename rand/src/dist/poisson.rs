@@ -0,0 +1,61 @@
+// poisson.rs: the Poisson distribution with mean lambda
+
+use crate::dist::fit::Estimate;
+use crate::special::{ln_gamma, gamma_q};
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+pub fn pmf(k: u32, lambda: f64) -> f64 {
+  assert!(lambda >= 0.0);
+  if lambda == 0.0 {
+    if k == 0 { 1.0 } else { 0.0 }
+  } else {
+    (k as f64 * lambda.ln() - lambda - ln_gamma(k as f64 + 1.0)).exp()
+  }
+}
+
+pub fn cdf(k: u32, lambda: f64) -> f64 {
+  assert!(lambda >= 0.0);
+  gamma_q(k as f64 + 1.0, lambda)
+}
+
+/// Maximum likelihood estimate of lambda, the mean
+pub fn fit(x: &[u32]) -> Estimate {
+  assert!(!x.is_empty());
+  let n = x.len() as f64;
+  let lambda = x.iter().map(|&k| k as f64).sum::<f64>() / n;
+  Estimate{value: lambda, std_err: (lambda / n).sqrt()}
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+  use super::*;
+  use compare::assert_close;
+  use crate::gen::pseudo::*;
+  use crate::gen::RandomStream;
+  use alloc::vec::Vec;
+
+  #[test]
+  fn poisson_basics() {
+    assert_close!(pmf(0, 2.0), (-2.0f64).exp(), rel = 1e-15);
+    assert_close!(pmf(3, 2.0), 8.0 / 6.0 * (-2.0f64).exp(), rel = 1e-15);
+    assert_eq!(pmf(0, 0.0), 1.0);
+    assert_eq!(pmf(1, 0.0), 0.0);
+    let mut sum = 0.0;
+    for k in 0..20 {
+      sum += pmf(k, 3.5);
+      assert_close!(cdf(k, 3.5), sum, rel = 1e-14);
+    }
+  }
+
+  #[test]
+  fn poisson_fit() {
+    assert_eq!(fit(&[1, 2, 6]), Estimate{value: 3.0, std_err: 1.0});
+    // sample by inverting the CDF
+    let x: Vec<u32> = LCG::new(Some(19937)).uniforms01(10000).iter()
+      .map(|&u| (0..).find(|&k| cdf(k, 4.0) > u).unwrap()).collect();
+    let lambda = fit(&x);
+    assert!((lambda.value - 4.0).abs() < 3.0 * lambda.std_err);
+  }
+}
@@ -0,0 +1,91 @@
+// Floating-point types that distributions can be sampled in
+#[cfg(feature = "alloc")]
+use crate::gen::RandomStream;
+use crate::gen::Dimensionless;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+/// A floating-point type that variates can be generated in. Uniforms are produced at the native precision of the
+/// type, i.e. 24 bits for f32 and 32 bits for f64, rather than being rounded from a wider type.
+pub trait Real: num_traits::Float + Debug {
+  /// n uniforms on [0,1)
+  #[cfg(feature = "alloc")]
+  fn uniforms01<R: RandomStream>(rng: &mut R, n: usize) -> Vec<Self>;
+  /// 1 uniform on [0,1)
+  fn uniform01<R: Dimensionless>(rng: &mut R) -> Self;
+  /// conversion from a (constant) double, rounding if necessary
+  fn of_f64(x: f64) -> Self;
+  /// the number of (leading) bits of a 32-bit integer used for a uniform
+  const UNIFORM_BITS: u32;
+  /// the uniform on [0,1) corresponding to a 32-bit integer, as generated by streams that scale their integers
+  fn uniform_from_u32(r: u32) -> Self;
+}
+
+impl Real for f64 {
+  #[cfg(feature = "alloc")]
+  fn uniforms01<R: RandomStream>(rng: &mut R, n: usize) -> Vec<f64> {
+    rng.uniforms01(n)
+  }
+
+  fn uniform01<R: Dimensionless>(rng: &mut R) -> f64 {
+    rng.uniform01()
+  }
+
+  fn of_f64(x: f64) -> f64 {
+    x
+  }
+
+  const UNIFORM_BITS: u32 = 32;
+
+  fn uniform_from_u32(r: u32) -> f64 {
+    r as f64 / 4294967296.0
+  }
+}
+
+impl Real for f32 {
+  #[cfg(feature = "alloc")]
+  fn uniforms01<R: RandomStream>(rng: &mut R, n: usize) -> Vec<f32> {
+    rng.uniforms01_f32(n)
+  }
+
+  fn uniform01<R: Dimensionless>(rng: &mut R) -> f32 {
+    rng.uniform01_f32()
+  }
+
+  fn of_f64(x: f64) -> f32 {
+    x as f32
+  }
+
+  const UNIFORM_BITS: u32 = 24;
+
+  fn uniform_from_u32(r: u32) -> f32 {
+    (r >> 8) as f32 / 16777216.0
+  }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::gen::quasi::*;
+
+  #[test]
+  fn native_precision() {
+    // every f32 uniform is a multiple of 2^-24 in [0,1)
+    let scale = 2.0f32.powi(24);
+    for u in f32::uniforms01(&mut Xorshift64::new(Some(19937)), 10000).into_iter()
+      .chain(f32::uniforms01(&mut LCG::new(Some(19937)), 10000))
+      .chain(f32::uniforms01(&mut Sobol::new(1), 10000)) {
+      assert!((0.0..1.0).contains(&u));
+      assert_eq!((u * scale).fract(), 0.0);
+    }
+    // the f32 uniform is the f64 one truncated to 24 bits, so never rounds up to 1
+    let mut a = MT19937::new(Some(19937));
+    let mut b = MT19937::new(Some(19937));
+    for _ in 0..10000 {
+      let (u, v) = (f32::uniform01(&mut a), f64::uniform01(&mut b));
+      assert!(u as f64 <= v && v - (u as f64) < 2.0f64.powi(-24));
+    }
+  }
+}
@@ -0,0 +1,264 @@
+// Distributions truncated to an interval [a,b]
+use crate::gen::{RandomStream, Dimensionless, Rejectable};
+use crate::dist::Dist;
+use crate::dist::normal;
+use crate::dist::normal::acklam::{self, Method};
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+/// Any continuous distribution truncated to [a,b], sampled by inverting its CDF over the restricted range. This
+/// consumes exactly one variate per sample so can be used with quasirandom sequences.
+pub struct Truncated<G, R> {
+  lo: f64,
+  hi: f64,
+  inv_cdf: G,
+  rng: R,
+}
+
+impl<G: Fn(f64) -> f64, R: RandomStream> Truncated<G, R> {
+  /// `cdf` is only evaluated at the endpoints. In the far upper tail, where the CDF rounds to 1, use the reflected
+  /// distribution instead.
+  pub fn new<F: Fn(f64) -> f64>(cdf: F, inv_cdf: G, a: f64, b: f64, rng: R) -> Truncated<G, R> {
+    assert!(a < b);
+    let (lo, hi) = (cdf(a), cdf(b));
+    assert!(lo < hi, "interval has zero probability");
+    Truncated { lo, hi, inv_cdf, rng }
+  }
+}
+
+impl<G: Fn(f64) -> f64, R: RandomStream> Dist<f64> for Truncated<G, R> {
+  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+    self.rng.uniforms01(n).iter().map(|&u| (self.inv_cdf)(self.lo + (self.hi - self.lo) * u)).collect()
+  }
+}
+
+/// Transform sampling by inversion of the CDF over the truncated range, suitable for any random stream
+#[derive(Debug)]
+pub struct Inversion<R> {
+  rng: R,
+}
+
+/// Transform sampling using Robert's rejection method (Statistics and Computing 5, 1995), with a uniform or
+/// exponential proposal depending on the width of the interval, which is efficient however far into the tail the
+/// interval lies. Intervals containing the mean are sampled by inversion.
+#[derive(Debug)]
+pub struct Rejection<R> {
+  rng: R,
+}
+
+/// Normal distribution truncated to [a,b]. Internally the interval is standardised, and reflected if necessary
+/// so that it lies mostly below the mean, where the normal CDF is accurate.
+#[derive(Debug)]
+pub struct TruncatedNormal<T> {
+  mu: f64,
+  sigma: f64,
+  // standardised interval
+  a: f64,
+  b: f64,
+  // standard normal CDF at a and b
+  lo: f64,
+  hi: f64,
+  reflect: bool,
+  transform: T,
+}
+
+// standardise and if necessary reflect [a,b] so that a + b <= 0
+fn standardise(mu: f64, sigma: f64, a: f64, b: f64) -> (f64, f64, bool) {
+  assert!(a < b);
+  let (a, b) = ((a - mu) / sigma, (b - mu) / sigma);
+  if a + b > 0.0 { (-b, -a, true) } else { (a, b, false) }
+}
+
+impl<T> TruncatedNormal<T> {
+  fn new_with(mean: f64, variance: f64, a: f64, b: f64, transform: T) -> TruncatedNormal<T> {
+    assert!(variance > 0.0);
+    let sigma = variance.sqrt();
+    let (a, b, reflect) = standardise(mean, sigma, a, b);
+    let (lo, hi) = (normal::cdf(a, 0.0, 1.0), normal::cdf(b, 0.0, 1.0));
+    TruncatedNormal { mu: mean, sigma, a, b, lo, hi, reflect, transform }
+  }
+
+  // map a standardised variate back to the original scale
+  fn scale(&self, z: f64) -> f64 {
+    self.mu + self.sigma * if self.reflect { -z } else { z }
+  }
+
+  // inverse CDF over the standardised interval
+  fn invert(&self, u: f64) -> f64 {
+    acklam::inv_cdf(self.lo + (self.hi - self.lo) * u, 0.0, 1.0, Method::Wichura, false).max(self.a).min(self.b)
+  }
+}
+
+impl<R: RandomStream> TruncatedNormal<Inversion<R>> {
+  /// Panics if the interval is so far into the tail that its probability rounds to zero, for which use `Rejection`
+  pub fn new(mean: f64, variance: f64, a: f64, b: f64, rng: R) -> TruncatedNormal<Inversion<R>> {
+    let d = TruncatedNormal::new_with(mean, variance, a, b, Inversion { rng });
+    assert!(d.lo < d.hi, "interval has zero probability");
+    d
+  }
+}
+
+impl<R: RandomStream> Dist<f64> for TruncatedNormal<Inversion<R>> {
+  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+    self.transform.rng.uniforms01(n).iter().map(|&u| self.scale(self.invert(u))).collect()
+  }
+}
+
+impl<R: RandomStream + Dimensionless + Rejectable> TruncatedNormal<Rejection<R>> {
+  pub fn new(mean: f64, variance: f64, a: f64, b: f64, rng: R) -> TruncatedNormal<Rejection<R>> {
+    TruncatedNormal::new_with(mean, variance, a, b, Rejection { rng })
+  }
+
+  pub fn sample_1(&mut self) -> f64 {
+    // after reflection a one-sided interval lies in the lower tail; sample its mirror image [-b,-a] in the upper
+    let z = if self.b < 0.0 {
+      -self.tail(-self.b, -self.a)
+    } else {
+      let u = self.transform.rng.uniform01();
+      self.invert(u)
+    };
+    self.scale(z)
+  }
+
+  // sample the standard normal on [a,b] with 0 <= a < b
+  fn tail(&mut self, a: f64, b: f64) -> f64 {
+    let s = (a * a + 4.0).sqrt();
+    // Robert's condition for the uniform proposal to be more efficient than the exponential
+    let uniform = b - a < 2.0 * 0.5f64.exp() / (a + s) * ((a * a - a * s) / 4.0).exp();
+    let rng = &mut self.transform.rng;
+    loop {
+      if uniform {
+        let z = a + (b - a) * rng.uniform01();
+        if rng.uniform01() <= (0.5 * (a * a - z * z)).exp() {
+          return z;
+        }
+      } else {
+        // exponential proposal shifted to a, with the optimal rate
+        let alpha = 0.5 * (a + s);
+        let z = a - (1.0 - rng.uniform01()).ln() / alpha;
+        if z <= b && rng.uniform01() <= (-0.5 * (z - alpha) * (z - alpha)).exp() {
+          return z;
+        }
+      }
+    }
+  }
+}
+
+impl<R: RandomStream + Dimensionless + Rejectable> Dist<f64> for TruncatedNormal<Rejection<R>> {
+  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+    (0..n).map(|_| self.sample_1()).collect()
+  }
+}
+
+/// Exponential distribution truncated to [a,b], sampled by inversion. By the memoryless property this is a
+/// shifted exponential truncated to [0,b-a], so is accurate however far a lies in the tail.
+#[derive(Debug)]
+pub struct TruncatedExponential<R> {
+  lambda: f64,
+  a: f64,
+  // probability of [0,b-a] under the unshifted distribution
+  p: f64,
+  rng: R,
+}
+
+impl<R: RandomStream> TruncatedExponential<R> {
+  pub fn new(lambda: f64, a: f64, b: f64, rng: R) -> TruncatedExponential<R> {
+    assert!(lambda > 0.0);
+    assert!(a >= 0.0 && a < b);
+    TruncatedExponential { lambda, a, p: -(-lambda * (b - a)).exp_m1(), rng }
+  }
+}
+
+impl<R: RandomStream> Dist<f64> for TruncatedExponential<R> {
+  fn sample_n(&mut self, n: usize) -> Vec<f64> {
+    self.rng.uniforms01(n).iter().map(|&u| self.a - (-self.p * u).ln_1p() / self.lambda).collect()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::gen::quasi::*;
+  use crate::dist::exponential;
+
+  const N: usize = 100000;
+
+  fn mean(x: &[f64]) -> f64 {
+    x.iter().sum::<f64>() / x.len() as f64
+  }
+
+  // mean of the standard normal truncated to [a,b], reflected into the lower tail for accuracy
+  fn normal_mean(a: f64, b: f64) -> f64 {
+    if a + b > 0.0 {
+      return -normal_mean(-b, -a);
+    }
+    (normal::pdf(a, 0.0, 1.0) - normal::pdf(b, 0.0, 1.0)) / (normal::cdf(b, 0.0, 1.0) - normal::cdf(a, 0.0, 1.0))
+  }
+
+  #[test]
+  fn normal_inversion_sobol() {
+    for &(a, b) in &[(-1.0, 2.0), (1.5, f64::INFINITY), (f64::NEG_INFINITY, -3.0), (20.0, 21.0)] {
+      let x = TruncatedNormal::<Inversion<Sobol>>::new(0.0, 1.0, a, b, Sobol::new(1)).sample_n(1024);
+      assert!(x.iter().all(|&xi| xi >= a && xi <= b));
+      // skip the first point, which is an endpoint
+      assert!((mean(&x[1..]) - normal_mean(a, b)).abs() < 1e-2);
+    }
+  }
+
+  #[test]
+  fn normal_rejection_tails() {
+    // exponential proposal far in the tail, uniform proposal for a narrow interval, and inversion in the centre
+    for &(a, b) in &[(10.0, f64::INFINITY), (f64::NEG_INFINITY, -40.0), (8.0, 8.1), (0.0, 0.5), (-0.5, 3.0)] {
+      let mut d = TruncatedNormal::<Rejection<MT19937>>::new(0.0, 1.0, a, b, MT19937::new(Some(19937)));
+      let x = d.sample_n(N);
+      assert!(x.iter().all(|&xi| xi >= a && xi <= b));
+      let m = mean(&x);
+      let expected = if a == f64::NEG_INFINITY { -40.02496875 } else { normal_mean(a, b) };
+      assert!((m - expected).abs() < 4e-3);
+    }
+  }
+
+  #[test]
+  fn normal_scaled() {
+    // N(1, 4) truncated to [3, 5] is 1 + 2 Z with Z truncated to [1, 2]
+    let mut d = TruncatedNormal::<Rejection<Xorshift64>>::new(1.0, 4.0, 3.0, 5.0, Xorshift64::new(Some(19937)));
+    assert!((mean(&d.sample_n(N)) - (1.0 + 2.0 * normal_mean(1.0, 2.0))).abs() < 1e-2);
+  }
+
+  #[test]
+  fn exponential() {
+    let (lambda, a, b) = (2.0, 1.0, 3.0);
+    let w: f64 = b - a;
+    let expected = a + 1.0 / lambda - w * (-lambda * w).exp() / -(-lambda * w).exp_m1();
+    let x = TruncatedExponential::new(lambda, a, b, MT19937::new(Some(19937))).sample_n(N);
+    assert!(x.iter().all(|&xi| xi >= a && xi <= b));
+    assert!((mean(&x) - expected).abs() < 1e-2);
+    // far tail, where the CDF is 1 to machine precision
+    let x = TruncatedExponential::new(1.0, 100.0, f64::INFINITY, Sobol::new(1)).sample_n(1024);
+    assert!((mean(&x) - 101.0).abs() < 1e-2);
+  }
+
+  #[test]
+  fn generic() {
+    let mut d = Truncated::new(|x| exponential::cdf(x, 1.0), |u| exponential::inv_cdf(u, 1.0), 0.5, 2.0,
+                               Xorshift64::new(Some(19937)));
+    let x = d.sample_n(N);
+    let mut e = TruncatedExponential::new(1.0, 0.5, 2.0, Xorshift64::new(Some(19937)));
+    assert!(x.iter().zip(e.sample_n(N)).all(|(x, y)| (x - y).abs() < 1e-12));
+  }
+
+  #[test]
+  #[should_panic]
+  fn empty() {
+    TruncatedNormal::<Inversion<LCG>>::new(0.0, 1.0, 1.0, 1.0, LCG::new(None));
+  }
+
+  #[test]
+  #[should_panic(expected = "interval has zero probability")]
+  fn inversion_far_tail() {
+    TruncatedNormal::<Inversion<LCG>>::new(0.0, 1.0, -40.0, -39.0, LCG::new(Some(19937)));
+  }
+}
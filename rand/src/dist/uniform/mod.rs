@@ -1,4 +1,8 @@
 // uniform mod.rs
+use crate::dist::fit::Estimate;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
 
 // Algorithms to transform uniform variates
 
@@ -29,17 +33,48 @@ pub fn inv_cdf(f: f64, l: f64, h: f64) -> f64 {
   l + h * f
 }
 
-#[cfg(test)]
+/// Maximum likelihood estimates of l and h, the smallest and largest values. They're biased inwards by (h-l)/(n+1),
+/// and their standard errors are the standard deviations of the extreme order statistics, (h-l) sqrt(n/(n+2))/(n+1)
+pub fn fit(x: &[f64]) -> (Estimate, Estimate) {
+  assert!(x.len() > 1);
+  let l = x.iter().cloned().fold(f64::INFINITY, f64::min);
+  let h = x.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+  assert!(l < h);
+  let n = x.len() as f64;
+  let std_err = (h - l) * (n / (n + 2.0)).sqrt() / (n + 1.0);
+  (Estimate{value: l, std_err}, Estimate{value: h, std_err})
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod test {
   use super::*;
-  use crate::dist::compare::close_rel_eps;
+  use compare::assert_close;
+  use crate::gen::pseudo::*;
+  use crate::dist::Dist;
+  use crate::dist::continuous::Uniform;
 
   #[test]
   fn uniform() {
     for i in 1..10 {
       let x = i as f64 * 0.1;
-      assert!(close_rel_eps(inv_cdf(cdf(x, 0.0, 1.0), 0.0, 1.0), x, Some(1024.0 * std::f64::EPSILON)));
-      assert!(close_rel_eps(pdf(x, 0.0, 1.0), 1.0, Some(1024.0 * std::f64::EPSILON)));
+      assert_close!(inv_cdf(cdf(x, 0.0, 1.0), 0.0, 1.0), x, rel = 1024.0 * f64::EPSILON);
+      assert_close!(pdf(x, 0.0, 1.0), 1.0, rel = 1024.0 * f64::EPSILON);
     }
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn uniform_fit() {
+    let (l, h) = fit(&[0.5, 0.25, 1.5, 1.0]);
+    assert_eq!((l.value, h.value), (0.25, 1.5));
+    assert_close!(l.std_err, 1.25 * (2.0f64 / 3.0).sqrt() / 5.0);
+    let (l, h) = fit(&Uniform::new(-1.0, 3.0, LCG::new(Some(19937))).sample_n(10000));
+    assert!(l.value > -1.0 && -1.0 > l.value - 3.0 * l.std_err);
+    assert!(h.value < 3.0 && 3.0 < h.value + 3.0 * h.std_err);
+  }
+
+  #[test]
+  #[should_panic]
+  fn uniform_fit_constant() {
+    fit(&[1.0, 1.0]);
+  }
+}
@@ -1,4 +1,18 @@
 #include <random>
+#include <algorithm>
+
+// A seed sequence that passes a given state through unchanged, so that all 624 words can be set
+struct StateSeq
+{
+  typedef uint32_t result_type;
+  const uint32_t* state;
+
+  template<typename It>
+  void generate(It begin, It end) const
+  {
+    std::copy(state, state + (end - begin), begin);
+  }
+};
 
 extern "C" {
 
@@ -7,6 +21,14 @@ std::mt19937* mt19937_create(uint32_t seed)
   return new std::mt19937(seed);
 }
 
+std::mt19937* mt19937_create_from_state(const uint32_t* state)
+{
+  StateSeq seq{state};
+  std::mt19937* pimpl = new std::mt19937;
+  pimpl->seed(seq);
+  return pimpl;
+}
+
 void mt19937_destroy(std::mt19937* pimpl)
 {
   delete pimpl;
@@ -0,0 +1,317 @@
+//! gen::chacha: Bernstein's ChaCha stream cipher used as a cryptographically secure generator
+
+use crate::gen::*;
+use crate::gen::entropy::{self, Entropy, OsEntropy};
+#[cfg(feature = "alloc")]
+use crate::gen::seed::{FromSeedSequence, SeedSequence};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// ChaCha with 8, 12 or 20 rounds (20 being the standard cipher, 8 the fastest with no known practical attack), a
+/// 256-bit key, a 64-bit block counter and a 64-bit stream id. The output is the keystream as 32-bit words.
+///
+/// Generators created by `new` or `with_entropy` are keyed from an entropy source, and rekeyed from it after every
+/// `reseed_every` bytes of output (64KiB by default) and in a child process after a fork, so that parent and child
+/// don't produce the same values. Generators created by `from_key` are deterministic and are never reseeded.
+pub struct ChaCha<E = OsEntropy> {
+  rounds: usize,
+  key: [u32; 8],
+  counter: u64,
+  stream: u64,
+  block: [u32; 16],
+  /// the next unused word of the block
+  pos: usize,
+  entropy: Option<E>,
+  /// reseeding threshold and bytes produced since the last reseed
+  reseed_after: u64,
+  produced: u64,
+  /// fork count when last (re)seeded
+  forks: usize,
+}
+
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+const DEFAULT_RESEED: u64 = 64 * 1024;
+
+#[inline]
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+  x[a] = x[a].wrapping_add(x[b]); x[d] = (x[d] ^ x[a]).rotate_left(16);
+  x[c] = x[c].wrapping_add(x[d]); x[b] = (x[b] ^ x[c]).rotate_left(12);
+  x[a] = x[a].wrapping_add(x[b]); x[d] = (x[d] ^ x[a]).rotate_left(8);
+  x[c] = x[c].wrapping_add(x[d]); x[b] = (x[b] ^ x[c]).rotate_left(7);
+}
+
+/// The ChaCha block function
+fn block(key: &[u32; 8], counter: u64, stream: u64, rounds: usize) -> [u32; 16] {
+  let mut input = [0u32; 16];
+  input[..4].copy_from_slice(&CONSTANTS);
+  input[4..12].copy_from_slice(key);
+  input[12] = counter as u32;
+  input[13] = (counter >> 32) as u32;
+  input[14] = stream as u32;
+  input[15] = (stream >> 32) as u32;
+  let mut x = input;
+  for _ in 0..rounds / 2 {
+    // columns then diagonals
+    quarter_round(&mut x, 0, 4, 8, 12);
+    quarter_round(&mut x, 1, 5, 9, 13);
+    quarter_round(&mut x, 2, 6, 10, 14);
+    quarter_round(&mut x, 3, 7, 11, 15);
+    quarter_round(&mut x, 0, 5, 10, 15);
+    quarter_round(&mut x, 1, 6, 11, 12);
+    quarter_round(&mut x, 2, 7, 8, 13);
+    quarter_round(&mut x, 3, 4, 9, 14);
+  }
+  for (xi, ii) in x.iter_mut().zip(input.iter()) {
+    *xi = xi.wrapping_add(*ii);
+  }
+  x
+}
+
+fn check_rounds(rounds: usize) {
+  assert!(rounds == 8 || rounds == 12 || rounds == 20, "ChaCha rounds must be 8, 12 or 20");
+}
+
+impl ChaCha {
+  /// Keyed from the operating system's entropy
+  #[cfg(feature = "std")]
+  pub fn new(rounds: usize) -> ChaCha {
+    ChaCha::with_entropy(rounds, OsEntropy::new())
+  }
+
+  /// A deterministic generator from a 32-byte key and a stream id, starting at block 0
+  pub fn from_key(rounds: usize, key: [u8; 32], stream: u64) -> ChaCha {
+    check_rounds(rounds);
+    let mut k = [0u32; 8];
+    for (ki, b) in k.iter_mut().zip(key.chunks_exact(4)) {
+      *ki = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+    }
+    ChaCha { rounds, key: k, counter: 0, stream, block: [0; 16], pos: 16, entropy: None, reseed_after: u64::MAX,
+             produced: 0, forks: 0 }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl FromSeedSequence for ChaCha {
+  /// ChaCha20, deterministically keyed from the sequence
+  fn from_seed_sequence(seq: &SeedSequence) -> ChaCha {
+    let bytes = seq.generate_bytes(40);
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&bytes[..32]);
+    let mut stream = [0u8; 8];
+    stream.copy_from_slice(&bytes[32..]);
+    ChaCha::from_key(20, key, u64::from_le_bytes(stream))
+  }
+}
+
+impl<E: Entropy> ChaCha<E> {
+  /// Keyed from the given entropy source. Panics if it fails.
+  pub fn with_entropy(rounds: usize, entropy: E) -> ChaCha<E> {
+    check_rounds(rounds);
+    let mut rng = ChaCha { rounds, key: [0; 8], counter: 0, stream: 0, block: [0; 16], pos: 16, entropy: Some(entropy),
+                           reseed_after: DEFAULT_RESEED, produced: 0, forks: 0 };
+    rng.reseed();
+    rng
+  }
+
+  /// Rekey after every `bytes` of output (rounded up to whole 64-byte blocks). Only generators with an entropy source
+  /// can be reseeded.
+  pub fn reseed_every(&mut self, bytes: u64) -> &mut Self {
+    assert!(self.entropy.is_some(), "generator has no entropy source");
+    assert!(bytes > 0);
+    self.reseed_after = bytes;
+    self
+  }
+
+  /// Rekey from the entropy source now, discarding any buffered output. Panics if the source fails.
+  pub fn reseed(&mut self) -> &mut Self {
+    let entropy = self.entropy.as_mut().expect("generator has no entropy source");
+    let mut seed = [0u8; 40];
+    entropy.fill_bytes(&mut seed).expect("failed to read entropy");
+    for (ki, b) in self.key.iter_mut().zip(seed.chunks_exact(4)) {
+      *ki = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+    }
+    let mut stream = [0u8; 8];
+    stream.copy_from_slice(&seed[32..]);
+    self.stream = u64::from_le_bytes(stream);
+    self.counter = 0;
+    self.pos = 16;
+    self.produced = 0;
+    self.forks = entropy::fork_count();
+    self
+  }
+
+  /// The current position in the stream, in 32-bit words
+  pub fn position(&self) -> u64 {
+    self.counter * 16 - (16 - self.pos as u64)
+  }
+
+  // in a forked child, discard the state shared with the parent, including any buffered output
+  fn check_fork(&mut self) {
+    if self.entropy.is_some() && entropy::fork_count() != self.forks {
+      self.reseed();
+    }
+  }
+
+  fn refill(&mut self) {
+    if self.produced >= self.reseed_after {
+      self.reseed();
+    }
+    self.block = block(&self.key, self.counter, self.stream, self.rounds);
+    self.counter = self.counter.wrapping_add(1);
+    self.produced += 64;
+    self.pos = 0;
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: Entropy> RandomStream for ChaCha<E> {
+  fn next_n(&mut self, n: usize) -> Vec<u32> {
+    self.check_fork();
+    let mut result = Vec::with_capacity(n);
+    while result.len() < n {
+      if self.pos == 16 {
+        self.refill();
+      }
+      let take = (n - result.len()).min(16 - self.pos);
+      result.extend_from_slice(&self.block[self.pos..self.pos + take]);
+      self.pos += take;
+    }
+    result
+  }
+
+  fn uniforms01(&mut self, n: usize) -> Vec<f64> {
+    simd::uniforms01(&self.next_n(n))
+  }
+}
+
+impl<E: Entropy> Dimensionless for ChaCha<E> {
+  fn next_1(&mut self) -> u32 {
+    self.check_fork();
+    if self.pos == 16 {
+      self.refill();
+    }
+    self.pos += 1;
+    self.block[self.pos - 1]
+  }
+
+  fn uniform01(&mut self) -> f64 {
+    self.next_1() as f64 / 4294967296.0
+  }
+}
+
+impl<E: Entropy> Rejectable for ChaCha<E> { }
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+  use super::*;
+  use std::io;
+
+  // keystreams for the all-zero key and stream id (draft-strombergson-chacha-test-vectors TC1)
+  const ZERO_8: [u32; 16] = [0x2fef003e, 0xd6405f89, 0xe8b85b7f, 0xa1a5091f, 0xc30e842c, 0x3b7f9ace, 0x88e11b18,
+    0x1e1a71ef, 0x72e14c98, 0x416f21b9, 0x6753449f, 0x19566d45, 0xa3424a31, 0x01b086da, 0xb8fd7b38, 0x42fe0c0e];
+  const ZERO_12: [u32; 16] = [0x6a9af49b, 0x53f95507, 0x12ce1f81, 0xd583265f, 0xbbc32904, 0x1474e049, 0xa589007e,
+    0x5f15ae2e, 0x79f86405, 0xc0e37ad2, 0x3428e82c, 0x798cfaac, 0x2c9f623a, 0x1969dea0, 0x2fe80b61, 0xbe261341];
+  const ZERO_20: [u32; 16] = [0xade0b876, 0x903df1a0, 0xe56a5d40, 0x28bd8653, 0xb819d2bd, 0x1aed8da0, 0xccef36a8,
+    0xc70d778b, 0x7c5941da, 0x8d485751, 0x3fe02477, 0x374ad8b8, 0xf4b8436a, 0x1ca11815, 0x69b687c3, 0x8665eeb2];
+
+  #[test]
+  fn test_vectors() {
+    assert_eq!(ChaCha::from_key(8, [0; 32], 0).next_n(16), ZERO_8);
+    assert_eq!(ChaCha::from_key(12, [0; 32], 0).next_n(16), ZERO_12);
+    assert_eq!(ChaCha::from_key(20, [0; 32], 0).next_n(16), ZERO_20);
+    // RFC 7539 2.3.2, whose 32-bit counter and 96-bit nonce map onto the 64-bit counter and stream id
+    let mut key = [0u8; 32];
+    for (i, k) in key.iter_mut().enumerate() {
+      *k = i as u8;
+    }
+    let expected = [0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3, 0xc7f4d1c7, 0x0368c033, 0x9aaa2204, 0x4e6cd4c3,
+                    0x466482d2, 0x09aa9f07, 0x05d7c214, 0xa2028bd9, 0xd19c12b5, 0xb94e16de, 0xe883d0cb, 0x4e3c50a2];
+    assert_eq!(block(&ChaCha::from_key(20, key, 0).key, 1 | 0x0900_0000 << 32, 0x4a00_0000, 20), expected);
+  }
+
+  #[test]
+  fn test_stream() {
+    // next_1 and next_n agree across block boundaries
+    let mut a = ChaCha::from_key(20, [7; 32], 3);
+    let mut b = ChaCha::from_key(20, [7; 32], 3);
+    let x = a.next_n(37);
+    let y: Vec<u32> = (0..37).map(|_| b.next_1()).collect();
+    assert_eq!(x, y);
+    assert_eq!(a.position(), 37);
+    assert_eq!(a.next_n(100), b.next_n(100));
+    // seeded from bytes, deterministically
+    assert_eq!(ChaCha::from_seed_bytes(b"19937").next_n(16), ChaCha::from_seed_bytes(b"19937").next_n(16));
+    // different streams differ
+    assert_ne!(ChaCha::from_key(20, [7; 32], 4).next_n(16), ChaCha::from_key(20, [7; 32], 3).next_n(16));
+    let n = 100000;
+    let u = ChaCha::new(8).uniforms01(n);
+    assert!(u.iter().all(|&x| (0.0..1.0).contains(&x)));
+    assert!((u.iter().sum::<f64>() - n as f64 / 2.0).abs() < (n as f64).sqrt());
+  }
+
+  // counts its calls, and gives different bytes each time
+  struct Counting(u8);
+
+  impl Entropy for Counting {
+    type Error = io::Error;
+
+    fn fill_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+      self.0 += 1;
+      buf.iter_mut().for_each(|b| *b = self.0);
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_reseed() {
+    let mut rng = ChaCha::with_entropy(12, Counting(0));
+    rng.reseed_every(128);
+    // 2 blocks from the first key, then 2 from the second
+    let x = rng.next_n(64);
+    assert_eq!(rng.entropy.as_ref().unwrap().0, 2);
+    assert_eq!(x[..32], ChaCha::from_key(12, [1; 32], u64::from_le_bytes([1; 8])).next_n(32)[..]);
+    assert_eq!(x[32..], ChaCha::from_key(12, [2; 32], u64::from_le_bytes([2; 8])).next_n(32)[..]);
+    rng.reseed();
+    assert_eq!(rng.entropy.as_ref().unwrap().0, 3);
+    assert_eq!(rng.position(), 0);
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_deterministic_reseed() {
+    ChaCha::from_key(20, [0; 32], 0).reseed();
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_rounds() {
+    ChaCha::from_key(10, [0; 32], 0);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_fork() {
+    // the child's output differs from the parent's
+    let mut rng = ChaCha::new(20);
+    rng.next_1();
+    let mut fds = [0; 2];
+    unsafe { libc::pipe(fds.as_mut_ptr()) };
+    let pid = unsafe { libc::fork() };
+    if pid == 0 {
+      let x = rng.next_n(16);
+      unsafe {
+        libc::write(fds[1], x.as_ptr() as *const libc::c_void, 64);
+        libc::_exit(0);
+      }
+    }
+    let mut y = [0u32; 16];
+    unsafe {
+      libc::read(fds[0], y.as_mut_ptr() as *mut libc::c_void, 64);
+      libc::waitpid(pid, std::ptr::null_mut(), 0);
+      libc::close(fds[0]);
+      libc::close(fds[1]);
+    }
+    assert!(rng.next_n(16).iter().zip(y.iter()).all(|(x, y)| x != y));
+  }
+}
@@ -1,19 +1,141 @@
+//! gen::entropy: randomness from the operating system, used directly or to seed and reseed other generators
+
+#[cfg(feature = "std")]
 use crate::gen::*;
+use core::fmt::Debug;
+#[cfg(feature = "std")]
 use std::fs::File;
-use std::io::BufReader;
-extern crate byteorder;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::sync::Once;
+
+/// A source of (true) random bytes
+pub trait Entropy {
+  /// why bytes couldn't be read
+  type Error: Debug;
+  /// fill buf with random bytes, or report why it couldn't be
+  fn fill_bytes(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+/// The operating system's entropy pool. The getrandom syscall is preferred, as it needs no file descriptor and blocks
+/// until the pool is initialised; on kernels without it (or on other unixes) /dev/urandom is read instead.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct OsEntropy {
+  urandom: Option<File>,
+}
 
-pub struct EntropySource
-{
-  buf: BufReader<File>
+/// Without std there is no operating system to ask, so this type has no values, and only generators with a given
+/// key or seed can be created.
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub enum OsEntropy {}
+
+#[cfg(not(feature = "std"))]
+impl Entropy for OsEntropy {
+  type Error = core::convert::Infallible;
+
+  fn fill_bytes(&mut self, _buf: &mut [u8]) -> Result<(), Self::Error> {
+    match *self {}
+  }
 }
 
+#[cfg(feature = "std")]
+impl OsEntropy {
+  pub fn new() -> OsEntropy {
+    OsEntropy { urandom: None }
+  }
+
+  fn fill_urandom(&mut self, buf: &mut [u8]) -> io::Result<()> {
+    if self.urandom.is_none() {
+      self.urandom = Some(File::open("/dev/urandom")?);
+    }
+    self.urandom.as_mut().unwrap().read_exact(buf)
+  }
+}
+
+// Some(result) if the syscall is available, None if the fallback should be used
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+fn getrandom(buf: &mut [u8]) -> Option<io::Result<()>> {
+  let mut filled = 0;
+  while filled < buf.len() {
+    let rest = &mut buf[filled..];
+    let n = unsafe { libc::syscall(libc::SYS_getrandom, rest.as_mut_ptr(), rest.len(), 0) };
+    if n < 0 {
+      let err = io::Error::last_os_error();
+      match err.raw_os_error() {
+        Some(libc::EINTR) => continue,
+        Some(libc::ENOSYS) => return None,
+        _ => return Some(Err(err)),
+      }
+    }
+    filled += n as usize;
+  }
+  Some(Ok(()))
+}
+
+#[cfg(all(feature = "std", not(any(target_os = "linux", target_os = "android"))))]
+fn getrandom(_buf: &mut [u8]) -> Option<io::Result<()>> {
+  None
+}
+
+#[cfg(feature = "std")]
+impl Entropy for OsEntropy {
+  type Error = io::Error;
+
+  fn fill_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+    if self.urandom.is_none() {
+      if let Some(result) = getrandom(buf) {
+        return result;
+      }
+    }
+    self.fill_urandom(buf)
+  }
+}
+
+#[cfg(feature = "std")]
+static FORKS: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(all(feature = "std", unix))]
+extern "C" fn forked() {
+  FORKS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The number of times this process (or its ancestors) has forked since the count was first requested. Generators
+/// holding state derived from entropy compare this before producing output, so that a child process doesn't repeat
+/// its parent's stream.
+#[cfg(feature = "std")]
+pub(crate) fn fork_count() -> usize {
+  static REGISTER: Once = Once::new();
+  #[cfg(unix)]
+  REGISTER.call_once(|| unsafe {
+    libc::pthread_atfork(None, None, Some(forked));
+  });
+  FORKS.load(Ordering::Relaxed)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn fork_count() -> usize {
+  0
+}
+
+/// Random integers straight from the operating system. This is slow, but suitable for seeding and cryptographic use.
+#[cfg(feature = "std")]
+pub struct EntropySource {
+  os: OsEntropy,
+}
+
+#[cfg(feature = "std")]
 impl EntropySource {
   pub fn new() -> Self {
-    EntropySource{ buf: BufReader::new(File::open("/dev/urandom").unwrap()) }
+    EntropySource { os: OsEntropy::new() }
   }
 }
 
+#[cfg(feature = "std")]
 impl Default for EntropySource {
   fn default() -> Self {
     EntropySource::new()
@@ -21,22 +143,23 @@ impl Default for EntropySource {
 }
 
 /// General traits of random
+#[cfg(feature = "std")]
 impl RandomStream for EntropySource {
   fn next_n(&mut self, n: usize) -> Vec<u32> {
-    use byteorder::{ReadBytesExt, NativeEndian};
-    let mut res: Vec<u32> = Vec::with_capacity(n);
-    unsafe { res.set_len(n); }
-    self.buf.read_u32_into::<NativeEndian>(&mut res).unwrap();
-    res
+    let mut bytes = vec![0u8; 4 * n];
+    // the trait has no way to report failure, which in any case means the OS can't provide randomness
+    self.os.fill_bytes(&mut bytes).expect("failed to read entropy from the operating system");
+    bytes.chunks_exact(4).map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]])).collect()
   }
   fn uniforms01(&mut self, n: usize) -> Vec<f64> {
     self.next_n(n).iter().map(|&r| r as f64 / (2.0f64.powi(32))).collect()
   }
 }
 
+#[cfg(feature = "std")]
 impl Rejectable for EntropySource { }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test {
   use super::*;
 
@@ -46,4 +169,31 @@ mod test {
     let mut rng = EntropySource::new();
     assert!((rng.uniforms01(n).iter().sum::<f64>() - (n as f64/2.0)).abs() < (n as f64).sqrt());
   }
+
+  #[test]
+  fn test_os_entropy() {
+    // the fallback gives random bytes too
+    let mut os = OsEntropy::new();
+    let mut a = [0u8; 64];
+    let mut b = [0u8; 64];
+    os.fill_bytes(&mut a).unwrap();
+    os.fill_urandom(&mut b).unwrap();
+    assert_ne!(a, b);
+    assert!(os.fill_bytes(&mut []).is_ok());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_fork_count() {
+    let before = fork_count();
+    let pid = unsafe { libc::fork() };
+    if pid == 0 {
+      // the child reports whether it saw the fork, without running any more of the test harness
+      unsafe { libc::_exit(if fork_count() == before + 1 { 0 } else { 1 }) };
+    }
+    let mut status = 0;
+    unsafe { libc::waitpid(pid, &mut status, 0) };
+    assert!(libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0);
+    assert_eq!(fork_count(), before);
+  }
 }
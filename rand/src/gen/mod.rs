@@ -1,12 +1,23 @@
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 /// General traits of random 
+#[cfg(feature = "alloc")]
 pub trait RandomStream {
   /// return n integers (u32)
   fn next_n(&mut self, n: usize) -> Vec<u32>;
   /// return n doubles
   fn uniforms01(&mut self, n: usize) -> Vec<f64>;
+  /// return n floats, using the top 24 bits of each integer
+  fn uniforms01_f32(&mut self, n: usize) -> Vec<f32> {
+    self.next_n(n).iter().map(|&r| (r >> 8) as f32 * F32_SCALE).collect()
+  }
 }
 
+// 2^-24, the resolution of a float uniform on [0,1)
+const F32_SCALE: f32 = 1.0 / 16777216.0;
+
 pub trait Seeded {
   /// initialise from a given seed 
   fn seed(&self) -> u32;
@@ -19,6 +30,10 @@ pub trait Dimensionless {
   fn next_1(&mut self) -> u32;
   /// return 1 double
   fn uniform01(&mut self) -> f64;
+  /// return 1 float, using the top 24 bits of the integer
+  fn uniform01_f32(&mut self) -> f32 {
+    (self.next_1() >> 8) as f32 * F32_SCALE
+  }
 }
 
 pub trait Dimensioned {
@@ -31,6 +46,13 @@ pub trait Rejectable {
   fn rejectable() -> bool { true }    
 }
 
+/// Can be stepped backwards, so that integers drawn ahead in a block (e.g. for SIMD) and not used can be returned to
+/// the stream. The uniforms of such generators must be their integers scaled by 2^-32 (using the top 24 bits for f32).
+pub trait Rewindable: Dimensionless {
+  /// Step back n values
+  fn rewind(&mut self, n: usize) -> &mut Self;
+}
+
 pub trait Resettable {
   /// Reset the generator to its initial state
   fn reset(&mut self) -> &mut Self;
@@ -39,5 +61,12 @@ pub trait Resettable {
 }
 
 pub mod pseudo;
+#[cfg(feature = "alloc")]
 pub mod quasi;
+#[cfg(all(feature = "alloc", not(feature = "ffi-sobol")))]
+mod sobol_data;
 pub mod entropy;
+pub mod chacha;
+#[cfg(feature = "alloc")]
+pub mod seed;
+pub(crate) mod simd;
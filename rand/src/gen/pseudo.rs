@@ -3,6 +3,13 @@
 // mod rand is implicit from project name in Cargo.toml
 // mod gen  is implicit from this filename
 use crate::gen::*;
+#[cfg(feature = "alloc")]
+use crate::gen::seed::{FromSeedSequence, SeedSequence};
+use crate::gen::simd::xorshift;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+use std::sync::OnceLock;
 
 /// Linear congruential generator equivalent to the C++11 minstd_rand
 pub struct LCG {
@@ -14,29 +21,60 @@ pub struct LCG {
 
 pub struct Xorshift64 {
   s: u32,
+  /// The initial state
+  r0: u64,
   r: u64
 }
 
+/// Mersenne twister, equivalent to the C++11 std::mt19937 (which is linked instead with the `ffi-mt19937` feature)
+#[cfg(not(feature = "ffi-mt19937"))]
+pub struct MT19937 {
+  seed: u32,
+  /// The initial state, restored by reset
+  init: [u32; MT_N],
+  state: [u32; MT_N],
+  /// The next word of the state to output, MT_N when the state must be regenerated
+  pos: usize
+}
+
 /// untyped pointer to C++ object. rust doesnt need to know the type as it doesnt directly access the object
+#[cfg(feature = "ffi-mt19937")]
 type MT19937Impl = *const std::ffi::c_void;
+#[cfg(feature = "ffi-mt19937")]
 pub struct MT19937 {
   seed: u32,
+  /// The full initial state, when seeded from a sequence
+  state: Option<Vec<u32>>,
   pimpl: MT19937Impl
 }
 
-// get seed if specified otherwise use system clock
+// the parameters of std::mt19937
+const MT_N: usize = 624;
+#[cfg(not(feature = "ffi-mt19937"))]
+const MT_M: usize = 397;
+#[cfg(not(feature = "ffi-mt19937"))]
+const MT_MATRIX_A: u32 = 0x9908_b0df;
+#[cfg(not(feature = "ffi-mt19937"))]
+const MT_UPPER: u32 = 0x8000_0000;
+
+// get seed if specified otherwise use system clock, which needs std
 fn get_seed(seed: Option<u32>) -> u32 {
-  use std::time::{SystemTime, UNIX_EPOCH};
   match seed {
     Some(val) => val,
-    None => SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos()
+    #[cfg(feature = "std")]
+    None => {
+      use std::time::{SystemTime, UNIX_EPOCH};
+      SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos()
+    }
+    #[cfg(not(feature = "std"))]
+    None => panic!("a seed is required without std")
   }
 }
 
 // private
 impl LCG {
   const A: u64 = 48271;
-  const M: u64 = std::i32::MAX as u64;
+  const M: u64 = i32::MAX as u64;
 }
 
 // public
@@ -65,8 +103,14 @@ impl Dimensionless for LCG {
   fn uniform01(&mut self) -> f64 {
     self.next_1() as f64 / LCG::M as f64
   }
+
+  // only 31 bits are generated
+  fn uniform01_f32(&mut self) -> f32 {
+    (self.next_1() >> 7) as f32 * F32_SCALE
+  }
 }
 
+#[cfg(feature = "alloc")]
 impl RandomStream for LCG {
   fn next_n(&mut self, n: usize) -> Vec<u32> {
     (0..n).map(|_| self.next_1()).collect()
@@ -75,6 +119,19 @@ impl RandomStream for LCG {
   fn uniforms01(&mut self, n: usize) -> Vec<f64> {
     (0..n).map(|_| self.uniform01()).collect()
   }
+
+  fn uniforms01_f32(&mut self, n: usize) -> Vec<f32> {
+    (0..n).map(|_| self.uniform01_f32()).collect()
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl FromSeedSequence for LCG {
+  // the state is in [1, M)
+  fn from_seed_sequence(seq: &SeedSequence) -> LCG {
+    let seed = (seq.generate_state(1)[0] as u64 % (LCG::M - 1)) as u32 + 1;
+    LCG{s: seed, r: seed}
+  }
 }
 
 impl Resettable for LCG {
@@ -84,7 +141,9 @@ impl Resettable for LCG {
   }
 
   fn skip(&mut self, n: u32) -> &mut Self {
-    self.next_n(n as usize);
+    for _ in 0..n {
+      self.next_1();
+    }
     self
   }
 }
@@ -93,7 +152,17 @@ impl Xorshift64 {
   pub fn new(seed: Option<u32>) -> Xorshift64 {
     let seed = get_seed(seed);
     assert_ne!(seed, 0);
-    Xorshift64{s: seed, r: seed as u64 }
+    Xorshift64{s: seed, r0: seed as u64, r: seed as u64 }
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl FromSeedSequence for Xorshift64 {
+  // the state is 64 (nonzero) bits. The seed reported is its lower half.
+  fn from_seed_sequence(seq: &SeedSequence) -> Xorshift64 {
+    let w = seq.generate_state(2);
+    let r = (w[0] as u64 | (w[1] as u64) << 32).max(1);
+    Xorshift64{s: r as u32, r0: r, r}
   }
 }
 
@@ -105,105 +174,282 @@ impl Seeded for Xorshift64 {
 
 impl Rejectable for Xorshift64 { }
 
+// the inverse of x ^= x << k (or >> k) is x ^ x << k ^ x << 2k ^ ...
+fn unshift_left(x: u64, k: u32) -> u64 {
+  (1..=63 / k).fold(x, |y, j| y ^ x << (j * k))
+}
+
+fn unshift_right(x: u64, k: u32) -> u64 {
+  (1..=63 / k).fold(x, |y, j| y ^ x >> (j * k))
+}
+
+// Blocks are generated as LANES interleaved streams of LANE_LEN, each starting LANE_LEN steps after the previous
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+const LANES: usize = 8;
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+const LANE_LEN: usize = 128;
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+// The xorshift step is linear over GF(2), so jumping LANE_LEN steps is multiplication by a 64x64 bit matrix. This is
+// tabulated for each byte of the state: entry [b][v] is the image of v << 8b.
+fn jump(x: u64) -> u64 {
+  static TABLE: OnceLock<Vec<[u64; 256]>> = OnceLock::new();
+  let table = TABLE.get_or_init(|| {
+    (0..8).map(|b| {
+      let mut t = [0; 256];
+      for (v, t) in t.iter_mut().enumerate() {
+        *t = (0..LANE_LEN).fold((v as u64) << (8 * b), |x, _| xorshift(x));
+      }
+      t
+    }).collect()
+  });
+  table.iter().enumerate().fold(0, |y, (b, t)| y ^ t[(x >> (8 * b)) as usize & 0xff])
+}
+
 impl Dimensionless for Xorshift64 {
   fn next_1(&mut self) -> u32 {
-    let mut x = self.r;
-    x ^= x << 13;
-    x ^= x >> 7;
-    x ^= x << 17;
-    self.r = x;
+    self.r = xorshift(self.r);
     (self.r & 0x00000000FFFFFFFF) as u32
   }
 
   fn uniform01(&mut self) -> f64 {
-    self.next_1() as f64 / 2.0f64.powi(32)
+    self.next_1() as f64 / 4294967296.0
   }
 }
 
+#[cfg(feature = "alloc")]
 impl RandomStream for Xorshift64 {
+  // whole blocks are generated with SIMD where available, otherwise with the state held locally rather than one
+  // value per call
   fn next_n(&mut self, n: usize) -> Vec<u32> {
-    (0..n).map(|_| self.next_1()).collect()
+    let mut result = vec![0; n];
+    #[cfg(all(feature = "std", target_arch = "x86_64"))]
+    let done = if simd::avx2() {
+      const BLOCK: usize = LANES * LANE_LEN;
+      let mut states = [0; LANES];
+      for block in result.chunks_exact_mut(BLOCK) {
+        // states[l] precedes values l * LANE_LEN..
+        states[0] = self.r;
+        for l in 1..LANES {
+          states[l] = jump(states[l - 1]);
+        }
+        self.r = unsafe { simd::xorshift_x8(&states, block) }[LANES - 1];
+      }
+      n - n % BLOCK
+    } else {
+      0
+    };
+    #[cfg(not(all(feature = "std", target_arch = "x86_64")))]
+    let done = 0;
+    let mut x = self.r;
+    for r in &mut result[done..] {
+      x = xorshift(x);
+      *r = x as u32;
+    }
+    self.r = x;
+    result
   }
 
-
   fn uniforms01(&mut self, n: usize) -> Vec<f64> {
-    (0..n).map(|_| self.uniform01()).collect()
+    let raw = self.next_n(n);
+    simd::uniforms01(&raw)
+  }
+}
+
+impl Rewindable for Xorshift64 {
+  fn rewind(&mut self, n: usize) -> &mut Self {
+    for _ in 0..n {
+      self.r = unshift_left(unshift_right(unshift_left(self.r, 17), 7), 13);
+    }
+    self
   }
 }
 
 impl Resettable for Xorshift64 {
   fn reset(&mut self) -> &mut Self {
-    self.r = self.s as u64;
+    self.r = self.r0;
     self
   }
 
   fn skip(&mut self, n: u32) -> &mut Self {
-    self.next_n(n as usize);
+    for _ in 0..n {
+      self.next_1();
+    }
     self
   }
 }
 
 
-#[link(name = "mt19937", kind = "static")]
-extern {
+impl MT19937 {
+  /// The number of 32-bit words of state
+  pub const STATE_SIZE: usize = MT_N;
+}
+
+#[cfg(not(feature = "ffi-mt19937"))]
+impl MT19937 {
+  pub fn new(seed: Option<u32>) -> MT19937 {
+    let seed = get_seed(seed);
+    let mut init = [0; MT_N];
+    init[0] = seed;
+    for i in 1..MT_N {
+      init[i] = 1812433253u32.wrapping_mul(init[i - 1] ^ init[i - 1] >> 30).wrapping_add(i as u32);
+    }
+    MT19937{seed, init, state: init, pos: MT_N}
+  }
+
+  // regenerate the whole state
+  fn twist(&mut self) {
+    let s = &mut self.state;
+    for i in 0..MT_N {
+      let y = (s[i] & MT_UPPER) | (s[(i + 1) % MT_N] & !MT_UPPER);
+      s[i] = s[(i + MT_M) % MT_N] ^ y >> 1 ^ if y & 1 == 1 { MT_MATRIX_A } else { 0 };
+    }
+    self.pos = 0;
+  }
+
+  fn next(&mut self) -> u32 {
+    if self.pos == MT_N {
+      self.twist();
+    }
+    let mut y = self.state[self.pos];
+    self.pos += 1;
+    // tempering
+    y ^= y >> 11;
+    y ^= y << 7 & 0x9d2c_5680;
+    y ^= y << 15 & 0xefc6_0000;
+    y ^ y >> 18
+  }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "ffi-mt19937")))]
+impl FromSeedSequence for MT19937 {
+  // all 19937 bits of state are set. The seed reported is the first word.
+  fn from_seed_sequence(seq: &SeedSequence) -> MT19937 {
+    let mut init = [0; MT_N];
+    init.copy_from_slice(&seq.generate_state(MT_N));
+    let seed = init[0];
+    // as in std::mt19937::seed(seq), a state that is zero (in the bits used) is replaced
+    if init[0] & MT_UPPER == 0 && init[1..].iter().all(|&w| w == 0) {
+      init[0] = MT_UPPER;
+    }
+    MT19937{seed, init, state: init, pos: MT_N}
+  }
+}
+
+#[cfg(not(feature = "ffi-mt19937"))]
+impl Resettable for MT19937 {
+  fn reset(&mut self) -> &mut Self {
+    self.state = self.init;
+    self.pos = MT_N;
+    self
+  }
+
+  fn skip(&mut self, n: u32) -> &mut Self {
+    // only the state needs advancing, the tempering being applied to each output
+    let mut n = n as usize;
+    while n > 0 {
+      if self.pos == MT_N {
+        self.twist();
+      }
+      let k = n.min(MT_N - self.pos);
+      self.pos += k;
+      n -= k;
+    }
+    self
+  }
+}
+
+#[cfg(feature = "ffi-mt19937")]
+extern "C" {
   // std::mt19937* mt19937_create(uint32_t seed)
   fn mt19937_create(seed: u32) -> MT19937Impl;
+  // std::mt19937* mt19937_create_from_state(const uint32_t* state)
+  fn mt19937_create_from_state(state: *const u32) -> MT19937Impl;
   // uint32_t mt_19937_next(std::mt19937* pimpl)
   fn mt19937_next(pimpl: MT19937Impl) -> u32;
   // void mt19937_destroy(std::mt19937* pimpl)
   fn mt19937_destroy(pimpl: MT19937Impl);
 }
 
+#[cfg(feature = "ffi-mt19937")]
 impl Drop for MT19937 {
   fn drop(&mut self) {
     unsafe { mt19937_destroy(self.pimpl); }
   }
 }
 
+#[cfg(feature = "ffi-mt19937")]
 impl MT19937 {
   pub fn new(seed: Option<u32>) -> MT19937 {
     let seed = get_seed(seed);
-    unsafe { MT19937{seed, pimpl: mt19937_create(seed)} }
+    unsafe { MT19937{seed, state: None, pimpl: mt19937_create(seed)} }
   }
-}
 
-impl Rejectable for MT19937 { }
-
-impl Dimensionless for MT19937 {
-  fn next_1(&mut self) -> u32 {
+  fn next(&mut self) -> u32 {
     unsafe { mt19937_next(self.pimpl) }
   }
-  fn uniform01(&mut self) -> f64 {
-    self.next_1() as f64 / 2.0f64.powi(32)
-  }
 }
 
-impl RandomStream for MT19937 {
-  fn next_n(&mut self, n: usize) -> Vec<u32> {
-    (0..n).map(|_| self.next_1()).collect()
-  }
-
-  fn uniforms01(&mut self, n: usize) -> Vec<f64> {
-    (0..n).map(|_| self.uniform01()).collect()
+#[cfg(feature = "ffi-mt19937")]
+impl FromSeedSequence for MT19937 {
+  // all 19937 bits of state are set. The seed reported is the first word.
+  fn from_seed_sequence(seq: &SeedSequence) -> MT19937 {
+    let state = seq.generate_state(MT19937::STATE_SIZE);
+    let pimpl = unsafe { mt19937_create_from_state(state.as_ptr()) };
+    MT19937{seed: state[0], state: Some(state), pimpl}
   }
 }
 
+#[cfg(feature = "ffi-mt19937")]
 impl Resettable for MT19937 {
   fn reset(&mut self) -> &mut Self {
     unsafe {
       mt19937_destroy(self.pimpl);
-      self.pimpl = mt19937_create(self.seed);
+      self.pimpl = match &self.state {
+        Some(state) => mt19937_create_from_state(state.as_ptr()),
+        None => mt19937_create(self.seed)
+      };
     }
     self
   }
 
   fn skip(&mut self, n: u32) -> &mut Self {
-    self.next_n(n as usize);
+    for _ in 0..n {
+      self.next_1();
+    }
     self
   }
 }
 
-#[cfg(test)]
+impl Seeded for MT19937 {
+  fn seed(&self) -> u32 {
+    self.seed
+  }
+}
+
+impl Rejectable for MT19937 { }
+
+impl Dimensionless for MT19937 {
+  fn next_1(&mut self) -> u32 {
+    self.next()
+  }
+  fn uniform01(&mut self) -> f64 {
+    self.next_1() as f64 / 4294967296.0
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl RandomStream for MT19937 {
+  fn next_n(&mut self, n: usize) -> Vec<u32> {
+    (0..n).map(|_| self.next_1()).collect()
+  }
+
+  fn uniforms01(&mut self, n: usize) -> Vec<f64> {
+    (0..n).map(|_| self.uniform01()).collect()
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod test {
   use super::*;
 
@@ -232,12 +478,76 @@ mod test {
     assert!(mean > 0.49 && mean < 0.51);
   }
 
+  #[test]
+  fn test_xorshift64_batched() {
+    // blocks are identical to one-at-a-time generation
+    let mut a = Xorshift64::new(Some(19937));
+    let mut b = Xorshift64::new(Some(19937));
+    // whole SIMD blocks and a remainder, and a remainder only
+    for &n in &[5000, 1001] {
+      assert_eq!(a.next_n(n), (0..n).map(|_| b.next_1()).collect::<Vec<_>>());
+      assert_eq!(a.uniforms01(n), (0..n).map(|_| b.uniform01()).collect::<Vec<_>>());
+    }
+    // and rewinding returns values to the stream
+    let v = a.next_n(100);
+    assert_eq!(a.rewind(100).next_n(100), v);
+    a.rewind(1100);
+    b.rewind(1000);
+    assert_eq!(a.next_1(), b.next_1());
+  }
+
   #[test]
   #[should_panic]
   fn test_xorshift64_failures() {
     Xorshift64::new(Some(0));
   }
 
+  #[test]
+  fn test_seed_sequence() {
+    // the same bytes give the same stream, and reset returns to its start
+    let mut a = MT19937::from_seed_bytes(b"a long seed of more than thirty-two bits");
+    let mut b = MT19937::from_seed_bytes(b"a long seed of more than thirty-two bits");
+    let x = a.next_n(1000);
+    assert_eq!(x, b.next_n(1000));
+    assert_eq!(a.reset().next_n(1000), x);
+    assert_ne!(MT19937::from_seed_bytes(b"a long seed of more than thirty-two bits!").next_n(1000), x);
+    // which isn't the stream seeded with the first word of the state (std::mt19937 only uses seed_seq's output)
+    assert_ne!(MT19937::new(Some(a.seed())).next_n(1000), x);
+    assert_eq!(MT19937::new(Some(19937)).seed(), 19937);
+
+    let mut seq = SeedSequence::from_u64(19937);
+    let children = seq.spawn(2);
+    let mut x = Xorshift64::from_seed_sequence(&children[0]);
+    let mut y = Xorshift64::from_seed_sequence(&children[1]);
+    let v = x.next_n(100);
+    assert_ne!(v, y.next_n(100));
+    assert_eq!(x.reset().next_n(100), v);
+    assert_eq!(x.seed(), Xorshift64::from_seed_sequence(&children[0]).r as u32);
+
+    let mut lcg = LCG::from_seed_sequence(&seq);
+    let v = lcg.next_n(100);
+    assert!(lcg.seed() > 0 && (lcg.seed() as u64) < LCG::M);
+    assert_eq!(lcg.reset().next_n(100), v);
+  }
+
+  #[test]
+  fn test_mt19937() {
+    // the C++ standard requires the 10000th value from the default seed to be 4123659995
+    let mut rng = MT19937::new(Some(5489));
+    assert_eq!(rng.next_n(10000)[9999], 4123659995);
+    // values from std::mt19937, which both backends must reproduce
+    let mut rng = MT19937::new(Some(19937));
+    assert_eq!(rng.next_n(700)[695..], [3031469492, 1218944086, 2705023106, 3536272581, 3601519751]);
+    let mut rng = MT19937::from_seed_sequence(&SeedSequence::from_u64(19937));
+    let x = rng.next_n(1000);
+    assert_eq!(x[995..], [792517440, 671696655, 1988255077, 2531433629, 1069538512]);
+    // skip across regenerations of the state
+    assert_eq!(rng.reset().skip(900).next_n(100), x[900..]);
+    let mut rng = MT19937::new(Some(19937));
+    let x = rng.next_n(1300);
+    assert_eq!(rng.reset().skip(1).skip(623).skip(624).next_n(52), x[1248..]);
+  }
+
   // fn moved(rng: MT19937) {
   //   println!("moved rng {:?}", rng.next_n(10));
   // }
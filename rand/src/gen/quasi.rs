@@ -1,48 +1,136 @@
 //! gen::quasi: quasirandom Generator module
+//!
+//! Sobol sequences are generated in rust by default. With the `ffi-sobol` feature nlopt's C implementation (from which
+//! the rust one is ported) is linked instead; the two give identical sequences.
 
 // mod rand is implicit from project name in Cargo.toml
 // mod gen  is implicit from the path
 // mod quasi is implicit from the filename
 use crate::gen::*;
+#[cfg(not(feature = "ffi-sobol"))]
+use crate::gen::sobol_data::{SOBOL_A, SOBOL_MINIT};
+use alloc::{vec, vec::Vec};
+
+/// Sobol sequence in up to `Sobol::MAX_DIM` dimensions, using Joe and Kuo's direction numbers. Each point is generated
+/// from the previous one in Gray code order (Antonov and Saleev's method), so the first point is (1/2, 1/2, ...).
+#[cfg(not(feature = "ffi-sobol"))]
+pub struct Sobol {
+  dim: u32,
+  /// direction numbers, left-aligned so that bit j of dimension i is v[j * dim + i] / 2^32
+  v: Vec<u32>,
+  /// the next point
+  cache: Vec<u32>,
+  /// the index of the next point (the first being 1)
+  n: u32,
+}
 
 /// untyped pointer to C struct. rust doesnt need to know the type as it doesnt directly access the object
+#[cfg(feature = "ffi-sobol")]
 type SobolImpl = *const std::ffi::c_void;
+#[cfg(feature = "ffi-sobol")]
 pub struct Sobol {
   dim: u32,
   cache: Vec<u32>,
   pimpl: SobolImpl
 }
 
-#[link(name = "sobol", kind = "static")]
-extern {
+#[cfg(feature = "ffi-sobol")]
+extern "C" {
   // SobolData* nlopt_sobol_create(uint32_t sdim)
   fn nlopt_sobol_create(dim: u32) -> SobolImpl;
   // int nlopt_sobol_next(SobolData* s, uint32_t *x)
-  fn nlopt_sobol_next(pimpl: SobolImpl, dest: &u32) -> i32;
-  // void nlopt_sobol_skip(SobolData* s, uint32_t n, uint32_t *x)
-  fn nlopt_sobol_skip(pimpl: SobolImpl, skips: u32, dest: &u32);
+  fn nlopt_sobol_next(pimpl: SobolImpl, dest: *mut u32) -> i32;
   // void nlopt_sobol_destroy(SobolData* s)
   fn nlopt_sobol_destroy(pimpl: SobolImpl);
   // inline uint32_t sobol_maxdim() { return MAXDIM; }
+  #[cfg(test)]
   fn sobol_maxdim() -> u32;
 }
 
+#[cfg(feature = "ffi-sobol")]
 impl Drop for Sobol {
   fn drop(&mut self) {
     unsafe { nlopt_sobol_destroy(self.pimpl); }
   }
 }
 
+impl Sobol {
+  /// The highest dimension for which there are direction numbers
+  pub const MAX_DIM: u32 = 1111;
+}
+
+#[cfg(not(feature = "ffi-sobol"))]
 impl Sobol {
   pub fn new(dim: u32) -> Sobol {
-    assert!(dim > 0 && dim <= unsafe {sobol_maxdim()});
-    let this = Sobol{dim, cache: vec![0; dim as usize], pimpl: unsafe { nlopt_sobol_create(dim) } };
-    // initialise cache
-    unsafe { nlopt_sobol_next(this.pimpl, &this.cache[0]); }
+    assert!(dim > 0 && dim <= Sobol::MAX_DIM);
+    let d = dim as usize;
+    // m[j * d + i] is the j-th direction number of dimension i, an odd integer less than 2^(j+1)
+    let mut m = vec![0u32; 32 * d];
+    // the first dimension is the van der Corput sequence
+    for j in 0..32 {
+      m[j * d] = 1;
+    }
+    for i in 1..d {
+      let a = SOBOL_A[i - 1];
+      // the degree of the primitive polynomial
+      let deg = (31 - a.leading_zeros()) as usize;
+      for j in 0..deg {
+        m[j * d + i] = SOBOL_MINIT[j][i - 1];
+      }
+      // the remainder by the recurrence given by the polynomial's coefficients
+      for j in deg..32 {
+        m[j * d + i] = (0..deg).fold(m[(j - deg) * d + i],
+                                     |mj, k| mj ^ ((a >> k & 1) * m[(j - deg + k) * d + i]) << (deg - k));
+      }
+    }
+    let v = m.iter().enumerate().map(|(k, &m)| m << (31 - k / d)).collect();
+    let mut this = Sobol{dim, v, cache: vec![0; d], n: 0};
+    this.advance();
     this
   }
+
+  // step to the next point, flipping the direction numbers of the lowest zero bit of the index
+  fn advance(&mut self) {
+    // 2^32-1 points is the limit of the 32-bit index, in the C implementation too
+    assert!(self.n < u32::MAX, "Sobol sequence exhausted");
+    let c = (!self.n).trailing_zeros() as usize;
+    self.n += 1;
+    let d = self.cache.len();
+    for (x, v) in self.cache.iter_mut().zip(&self.v[c * d..(c + 1) * d]) {
+      *x ^= v;
+    }
+  }
+
+  // go straight to point n, whose bits are the direction numbers of the set bits of n's Gray code
+  fn jump(&mut self, n: u32) {
+    let d = self.cache.len();
+    let gray = n ^ n >> 1;
+    self.cache.iter_mut().for_each(|x| *x = 0);
+    for j in (0..32).filter(|j| gray >> j & 1 == 1) {
+      for (x, v) in self.cache.iter_mut().zip(&self.v[j * d..(j + 1) * d]) {
+        *x ^= v;
+      }
+    }
+    self.n = n;
+  }
 }
 
+#[cfg(feature = "ffi-sobol")]
+impl Sobol {
+  pub fn new(dim: u32) -> Sobol {
+    assert!(dim > 0 && dim <= Sobol::MAX_DIM);
+    let mut this = Sobol{dim, cache: vec![0; dim as usize], pimpl: unsafe { nlopt_sobol_create(dim) } };
+    // initialise cache
+    this.advance();
+    this
+  }
+
+  fn advance(&mut self) {
+    // fails when 2^32-1 points have been generated
+    let ok = unsafe { nlopt_sobol_next(self.pimpl, self.cache.as_mut_ptr()) };
+    assert!(ok != 0, "Sobol sequence exhausted");
+  }
+}
 
 impl Dimensioned for Sobol {
   fn dim(&self) -> u32 {
@@ -54,11 +142,10 @@ impl RandomStream for Sobol {
   fn next_n(&mut self, n: usize) -> Vec<u32> {
     // only a multiple of dim is allowed
     assert_eq!(n % self.cache.len(), 0);
-    // clone the cache
-    let mut result = Vec::new();
+    let mut result = Vec::with_capacity(n);
     for _ in 0..n/self.cache.len() {
-      result.append(&mut self.cache.clone());
-      unsafe { nlopt_sobol_next(self.pimpl, &self.cache[0]); }
+      result.extend_from_slice(&self.cache);
+      self.advance();
     }
     result
   }
@@ -70,21 +157,37 @@ impl RandomStream for Sobol {
 
 }
 
+#[cfg(not(feature = "ffi-sobol"))]
+impl Resettable for Sobol {
+  fn reset(&mut self) -> &mut Self {
+    self.jump(1);
+    self
+  }
+
+  fn skip(&mut self, n: u32) -> &mut Self {
+    let n = self.n.checked_add(n).filter(|&n| n < u32::MAX).expect("Sobol sequence exhausted");
+    self.jump(n);
+    self
+  }
+}
+
+#[cfg(feature = "ffi-sobol")]
 impl Resettable for Sobol {
   fn reset(&mut self) -> &mut Self {
     unsafe {
       nlopt_sobol_destroy(self.pimpl);
       self.pimpl = nlopt_sobol_create(self.dim);
-      // refresh cache
-      nlopt_sobol_next(self.pimpl, &self.cache[0]);
     }
+    // refresh cache
+    self.advance();
     self
   }
 
   fn skip(&mut self, n: u32) -> &mut Self {
-    //println!("{:?}", self.cache);
-    unsafe { nlopt_sobol_skip(self.pimpl, n * self.dim, &self.cache[0]); }
-    //println!("{:?}", self.cache);
+    // nlopt_sobol_skip skips the largest power of 2 below n, as Joe and Kuo recommend, so step through instead
+    for _ in 0..n {
+      self.advance();
+    }
     self
   }
 }
@@ -111,6 +214,37 @@ mod test {
     assert_eq!(gen.reset().uniforms01(1111), vec![0.5; 1111]);
   }
 
+  #[test]
+  fn test_backends() {
+    // values from nlopt's implementation, which both backends must reproduce
+    let mut gen = Sobol::new(5);
+    assert_eq!(gen.next_n(40)[25..], [2684354560, 536870912, 1610612736, 1610612736, 536870912, 536870912, 2684354560,
+                                     3758096384, 3758096384, 2684354560, 805306368, 1342177280, 1342177280, 2952790016,
+                                     2415919104]);
+    let mut gen = Sobol::new(1111);
+    let checksum = gen.next_n(1111 * 100).iter().enumerate()
+      .fold(0u64, |a, (i, &v)| a.wrapping_mul(31).wrapping_add(v as u64 ^ i as u64));
+    assert_eq!(checksum, 18269969469009487102);
+    // skip n points exactly, not just a power of 2
+    let mut gen = Sobol::new(7);
+    assert_eq!(gen.skip((1 << 20) + 3).next_n(7), [1610618880, 402683904, 123262976, 3980167168, 3622950912, 1098930176,
+                                                   1715013632]);
+    let x = gen.reset().next_n(7 * 10);
+    assert_eq!(gen.reset().skip(3).next_n(7 * 7), x[7 * 3..]);
+  }
+
+  #[cfg(feature = "ffi-sobol")]
+  #[test]
+  fn test_maxdim() {
+    assert_eq!(Sobol::MAX_DIM, unsafe { sobol_maxdim() });
+  }
+
+  #[cfg(not(feature = "ffi-sobol"))]
+  #[test]
+  fn test_maxdim() {
+    assert_eq!(Sobol::MAX_DIM as usize, crate::gen::sobol_data::MAXDIM);
+  }
+
   #[test]
   #[should_panic]
   fn test_sobol_failures() {
@@ -120,6 +254,6 @@ mod test {
   #[test]
   #[should_panic]
   fn test_sobol_failures2() {
-    Sobol::new(unsafe { sobol_maxdim() } + 1);
+    Sobol::new(Sobol::MAX_DIM + 1);
   }
 }
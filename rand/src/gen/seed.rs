@@ -0,0 +1,197 @@
+//! gen::seed: expansion of seeds of any length into the full state of a generator
+
+#[cfg(feature = "std")]
+use crate::gen::entropy::{Entropy, OsEntropy};
+use alloc::vec::Vec;
+
+/// Hashes entropy of arbitrary length into a pool, from which any number of well-mixed state words can be drawn,
+/// in the manner of C++11 `std::seed_seq` and NumPy's `SeedSequence` (whose mixing functions are used here). Similar
+/// seeds give unrelated states, and every bit of the seed affects every word of the state.
+///
+/// Independent child sequences, e.g. one per thread, are obtained with `spawn`. Each child is identified by its
+/// position in the tree of spawned sequences (its spawn key), which is hashed in with the parent's entropy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedSequence {
+  entropy: Vec<u32>,
+  spawn_key: Vec<u32>,
+  pool: [u32; POOL_SIZE],
+  children: u32,
+}
+
+const POOL_SIZE: usize = 4;
+const INIT_A: u32 = 0x43b0_d7e5;
+const MULT_A: u32 = 0x931e_8875;
+const INIT_B: u32 = 0x8b51_f9dd;
+const MULT_B: u32 = 0x58f3_8ded;
+const MIX_MULT_L: u32 = 0xca01_f9dd;
+const MIX_MULT_R: u32 = 0x4973_f715;
+const XSHIFT: u32 = 16;
+
+// the hash multiplier is updated on each call
+fn hashmix(value: u32, hash_const: &mut u32) -> u32 {
+  let mut value = value ^ *hash_const;
+  *hash_const = hash_const.wrapping_mul(MULT_A);
+  value = value.wrapping_mul(*hash_const);
+  value ^ value >> XSHIFT
+}
+
+fn mix(x: u32, y: u32) -> u32 {
+  let result = MIX_MULT_L.wrapping_mul(x).wrapping_sub(MIX_MULT_R.wrapping_mul(y));
+  result ^ result >> XSHIFT
+}
+
+impl SeedSequence {
+  /// From entropy as 32-bit words
+  pub fn new(entropy: &[u32]) -> SeedSequence {
+    SeedSequence::with_spawn_key(entropy.to_vec(), Vec::new())
+  }
+
+  /// From entropy as bytes, read as little-endian words (the last zero-padded)
+  pub fn from_bytes(bytes: &[u8]) -> SeedSequence {
+    let words = bytes.chunks(4).map(|c| {
+      let mut w = [0u8; 4];
+      w[..c.len()].copy_from_slice(c);
+      u32::from_le_bytes(w)
+    }).collect::<Vec<_>>();
+    SeedSequence::new(&words)
+  }
+
+  /// From a 64-bit seed
+  pub fn from_u64(seed: u64) -> SeedSequence {
+    SeedSequence::new(&[seed as u32, (seed >> 32) as u32])
+  }
+
+  /// From 128 bits of the operating system's entropy. Panics if it can't be read.
+  #[cfg(feature = "std")]
+  pub fn from_entropy() -> SeedSequence {
+    let mut bytes = [0u8; 16];
+    OsEntropy::new().fill_bytes(&mut bytes).expect("failed to read entropy");
+    SeedSequence::from_bytes(&bytes)
+  }
+
+  fn with_spawn_key(entropy: Vec<u32>, spawn_key: Vec<u32>) -> SeedSequence {
+    // the spawn key follows the entropy, padded to the pool size so that it can't be confused with entropy
+    let mut assembled = entropy.clone();
+    if !spawn_key.is_empty() && assembled.len() < POOL_SIZE {
+      assembled.resize(POOL_SIZE, 0);
+    }
+    assembled.extend_from_slice(&spawn_key);
+
+    let mut pool = [0u32; POOL_SIZE];
+    let mut hash_const = INIT_A;
+    for (i, p) in pool.iter_mut().enumerate() {
+      *p = hashmix(assembled.get(i).copied().unwrap_or(0), &mut hash_const);
+    }
+    for src in 0..POOL_SIZE {
+      for dst in 0..POOL_SIZE {
+        if src != dst {
+          pool[dst] = mix(pool[dst], hashmix(pool[src], &mut hash_const));
+        }
+      }
+    }
+    for &e in assembled.iter().skip(POOL_SIZE) {
+      for p in pool.iter_mut() {
+        *p = mix(*p, hashmix(e, &mut hash_const));
+      }
+    }
+    SeedSequence { entropy, spawn_key, pool, children: 0 }
+  }
+
+  /// n words of generator state
+  pub fn generate_state(&self, n: usize) -> Vec<u32> {
+    let mut hash_const = INIT_B;
+    self.pool.iter().cycle().take(n).map(|&p| {
+      let mut value = p ^ hash_const;
+      hash_const = hash_const.wrapping_mul(MULT_B);
+      value = value.wrapping_mul(hash_const);
+      value ^ value >> XSHIFT
+    }).collect()
+  }
+
+  /// n words of generator state, as bytes
+  pub fn generate_bytes(&self, n: usize) -> Vec<u8> {
+    self.generate_state(n.div_ceil(4)).iter().flat_map(|w| w.to_le_bytes()).take(n).collect()
+  }
+
+  /// n child sequences, independent of each other, of this sequence and of any children spawned previously
+  pub fn spawn(&mut self, n: u32) -> Vec<SeedSequence> {
+    let children = (self.children..self.children + n).map(|i| {
+      let mut key = self.spawn_key.clone();
+      key.push(i);
+      SeedSequence::with_spawn_key(self.entropy.clone(), key)
+    }).collect();
+    self.children += n;
+    children
+  }
+
+  /// The position of this sequence in the tree of spawned sequences, empty for the root
+  pub fn spawn_key(&self) -> &[u32] {
+    &self.spawn_key
+  }
+}
+
+/// Generators that can take their whole initial state from a seed sequence, rather than from a single 32-bit seed
+pub trait FromSeedSequence: Sized {
+  fn from_seed_sequence(seq: &SeedSequence) -> Self;
+
+  /// Seed from any number of bytes, which are hashed into the generator state
+  fn from_seed_bytes(bytes: &[u8]) -> Self {
+    Self::from_seed_sequence(&SeedSequence::from_bytes(bytes))
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_generate() {
+    let s = SeedSequence::from_u64(19937);
+    let x = s.generate_state(1000);
+    // deterministic, and a prefix of a longer state
+    assert_eq!(x, SeedSequence::from_u64(19937).generate_state(1000));
+    assert_eq!(x[..10], s.generate_state(10)[..]);
+    assert_eq!(s.generate_bytes(7), x[..2].iter().flat_map(|w| w.to_le_bytes()).take(7).collect::<Vec<_>>());
+    // roughly balanced bits
+    let ones: u32 = x.iter().map(|w| w.count_ones()).sum();
+    assert!((ones as f64 - 16000.0).abs() < 4.0 * 8000f64.sqrt());
+    // a one-bit change in the seed changes every word
+    let y = SeedSequence::from_u64(19937 ^ 1 << 40).generate_state(1000);
+    assert!(x.iter().zip(&y).all(|(a, b)| a != b));
+    // bytes are little-endian words, and trailing zeros are significant only as whole words
+    assert_eq!(SeedSequence::from_bytes(&19937u64.to_le_bytes()), s);
+    assert_eq!(SeedSequence::from_bytes(&[1, 2, 3]), SeedSequence::new(&[0x030201]));
+    // long seeds use all their entropy
+    let long: Vec<u32> = (0..100).collect();
+    let mut longer = long.clone();
+    longer[99] = 100;
+    assert_ne!(SeedSequence::new(&long).generate_state(4), SeedSequence::new(&longer).generate_state(4));
+  }
+
+  #[test]
+  fn test_spawn() {
+    let mut root = SeedSequence::new(&[19937]);
+    let children = root.spawn(3);
+    let more = root.spawn(2);
+    assert_eq!(more[0].spawn_key(), &[3]);
+    let grandchildren = children[0].clone().spawn(2);
+    assert_eq!(grandchildren[1].spawn_key(), &[0, 1]);
+    let mut states = vec![root.generate_state(8)];
+    states.extend(children.iter().chain(&more).chain(&grandchildren).map(|c| c.generate_state(8)));
+    for i in 0..states.len() {
+      for j in 0..i {
+        assert_ne!(states[i], states[j]);
+      }
+    }
+    // spawning is reproducible, and doesn't alter the parent's state
+    assert_eq!(SeedSequence::new(&[19937]).spawn(1)[0], children[0]);
+    assert_eq!(root.generate_state(8), SeedSequence::new(&[19937]).generate_state(8));
+    // a child isn't the parent with the key as entropy
+    assert_ne!(children[0].generate_state(8), SeedSequence::new(&[19937, 0]).generate_state(8));
+  }
+
+  #[test]
+  fn test_entropy() {
+    assert_ne!(SeedSequence::from_entropy(), SeedSequence::from_entropy());
+  }
+}
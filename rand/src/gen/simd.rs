@@ -0,0 +1,142 @@
+//! gen::simd: vectorised conversion of blocks of integers, using AVX2 when the CPU supports it (detected at run
+//! time) and a scalar loop otherwise. Both paths give bit-identical results.
+
+#[cfg(all(feature = "alloc", target_arch = "x86_64"))]
+use core::arch::x86_64::*;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+/// Whether the AVX2 code paths can be used. Without std the CPU can't be queried, so this is decided at compile time.
+#[cfg(feature = "alloc")]
+pub(crate) fn avx2() -> bool {
+  #[cfg(all(feature = "std", target_arch = "x86_64"))]
+  {
+    is_x86_feature_detected!("avx2")
+  }
+  #[cfg(not(all(feature = "std", target_arch = "x86_64")))]
+  {
+    cfg!(all(target_arch = "x86_64", target_feature = "avx2"))
+  }
+}
+
+/// Scale integers by 2^-32 to uniforms on [0,1)
+#[cfg(feature = "alloc")]
+pub(crate) fn uniforms01(raw: &[u32]) -> Vec<f64> {
+  #[cfg(target_arch = "x86_64")]
+  {
+    if avx2() {
+      return unsafe { uniforms01_avx2(raw) };
+    }
+  }
+  uniforms01_scalar(raw)
+}
+
+#[cfg(feature = "alloc")]
+fn uniforms01_scalar(raw: &[u32]) -> Vec<f64> {
+  raw.iter().map(|&r| r as f64 / 4294967296.0).collect()
+}
+
+/// Convert 4 unsigned integers to doubles scaled by 2^-shift. The conversion instruction is signed, so the integers
+/// are offset by 2^31 and the offset added back, which is exact.
+#[cfg(all(feature = "alloc", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn scale_epu32(r: __m128i, shift: u32) -> __m256d {
+  let signed = _mm_xor_si128(r, _mm_set1_epi32(i32::MIN));
+  let d = _mm256_add_pd(_mm256_cvtepi32_pd(signed), _mm256_set1_pd(2147483648.0));
+  _mm256_mul_pd(d, _mm256_set1_pd(f64::from_bits((1023 - shift as u64) << 52)))
+}
+
+#[cfg(all(feature = "alloc", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn uniforms01_avx2(raw: &[u32]) -> Vec<f64> {
+  let mut result = vec![0.0; raw.len()];
+  let blocks = raw.len() / 4;
+  for b in 0..blocks {
+    let r = _mm_loadu_si128(raw.as_ptr().add(4 * b) as *const __m128i);
+    _mm256_storeu_pd(result.as_mut_ptr().add(4 * b), scale_epu32(r, 32));
+  }
+  for i in 4 * blocks..raw.len() {
+    result[i] = raw[i] as f64 / 4294967296.0;
+  }
+  result
+}
+
+/// The xorshift (13, 7, 17) step
+pub(crate) fn xorshift(mut x: u64) -> u64 {
+  x ^= x << 13;
+  x ^= x >> 7;
+  x ^= x << 17;
+  x
+}
+
+/// Advance 8 xorshift streams in parallel, writing the low 32 bits of the next `out.len() / 8` states of each
+/// stream to consecutive blocks of `out`, and return the final states
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn xorshift_x8(states: &[u64; 8], out: &mut [u32]) -> [u64; 8] {
+  // one step of 4 streams
+  #[target_feature(enable = "avx2")]
+  #[inline]
+  unsafe fn step(mut x: __m256i) -> __m256i {
+    x = _mm256_xor_si256(x, _mm256_slli_epi64::<13>(x));
+    x = _mm256_xor_si256(x, _mm256_srli_epi64::<7>(x));
+    _mm256_xor_si256(x, _mm256_slli_epi64::<17>(x))
+  }
+  let len = out.len() / 8;
+  let mut lo = _mm256_loadu_si256(states.as_ptr() as *const __m256i);
+  let mut hi = _mm256_loadu_si256(states.as_ptr().add(4) as *const __m256i);
+  // the low halves of the 8 states
+  let pick = _mm256_setr_epi32(0, 2, 4, 6, 0, 2, 4, 6);
+  let mut low = [0u32; 8];
+  for j in 0..len {
+    lo = step(lo);
+    hi = step(hi);
+    let l = _mm256_permutevar8x32_epi32(lo, pick);
+    let h = _mm256_permutevar8x32_epi32(hi, pick);
+    _mm256_storeu_si256(low.as_mut_ptr() as *mut __m256i, _mm256_permute2x128_si256::<0x20>(l, h));
+    for (lane, &r) in low.iter().enumerate() {
+      *out.get_unchecked_mut(lane * len + j) = r;
+    }
+  }
+  let mut result = [0u64; 8];
+  _mm256_storeu_si256(result.as_mut_ptr() as *mut __m256i, lo);
+  _mm256_storeu_si256(result.as_mut_ptr().add(4) as *mut __m256i, hi);
+  result
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+  use super::*;
+
+  #[test]
+  fn identical() {
+    let raw: Vec<u32> = (0..1003u32).map(|i| i.wrapping_mul(2654435761)).chain(vec![0, 1, u32::MAX, 1 << 31]).collect();
+    let u = uniforms01(&raw);
+    assert_eq!(u, uniforms01_scalar(&raw));
+    assert!(u.iter().all(|&x| (0.0..1.0).contains(&x)));
+  }
+
+  #[test]
+  fn lanes() {
+    if !avx2() {
+      return;
+    }
+    // 8 streams of 5 from consecutive starting points
+    let mut states = [0u64; 8];
+    let mut x = 19937;
+    for s in states.iter_mut() {
+      x = xorshift(x);
+      *s = x;
+    }
+    let mut out = vec![0; 40];
+    let last = unsafe { xorshift_x8(&states, &mut out) };
+    for (lane, &s) in states.iter().enumerate() {
+      let mut x = s;
+      for j in 0..5 {
+        x = xorshift(x);
+        assert_eq!(out[lane * 5 + j], x as u32);
+      }
+      assert_eq!(last[lane], x);
+    }
+  }
+}
@@ -0,0 +1,182 @@
+//! golden: regression tests comparing the first values of every generator and distribution, from fixed seeds, with
+//! the files in `golden/`, so that any change to a stream is caught. Floats are written in Rust's shortest
+//! round-trip form and compared exactly. When a change is intended, regenerate the files with
+//!
+//! ```bash
+//! RAND_UPDATE_GOLDEN=1 cargo test golden
+//! ```
+//!
+//! and commit them with the change. The transcendental functions come from the platform (or libm), so the
+//! distribution files could differ in the last place on another platform.
+
+use crate::gen::*;
+use crate::gen::pseudo::*;
+use crate::gen::quasi::*;
+use crate::gen::chacha::ChaCha;
+use crate::dist::Dist;
+use crate::dist::continuous::*;
+use crate::dist::normal::{acklam::{InverseCumulative, Method}, polar::Polar, ziggurat::Ziggurat};
+use crate::dist::exponential::{self, ziggurat::Ziggurat as ExpZiggurat};
+use crate::dist::truncated::*;
+use crate::dist::discrete::*;
+use crate::dist::empirical::*;
+use crate::dist::domain::*;
+use vector::Vector;
+use std::fs;
+
+// values per file
+const N: usize = 64;
+const SEED: u32 = 19937;
+
+// the text of a single value
+trait Golden {
+  fn golden(&self) -> String;
+}
+
+macro_rules! golden_display {
+  ($($t:ty),*) => {
+    $(impl Golden for $t {
+      fn golden(&self) -> String {
+        self.to_string()
+      }
+    })*
+  };
+}
+golden_display!(u32, i32);
+
+impl Golden for f64 {
+  fn golden(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl Golden for f32 {
+  fn golden(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl<T: Golden> Golden for [T] {
+  fn golden(&self) -> String {
+    self.iter().map(|x| x.golden()).collect::<Vec<_>>().join(" ")
+  }
+}
+
+impl<T: Golden> Golden for Vec<T> {
+  fn golden(&self) -> String {
+    self[..].golden()
+  }
+}
+
+impl<T: Golden> Golden for [T; 2] {
+  fn golden(&self) -> String {
+    self[..].golden()
+  }
+}
+
+impl Golden for Vector {
+  fn golden(&self) -> String {
+    [self.i, self.j, self.k].golden()
+  }
+}
+
+// compare values with golden/<name>.txt (one per line), or rewrite it if RAND_UPDATE_GOLDEN is set
+fn check<T: Golden>(name: &str, values: &[T]) {
+  let path = format!("{}/golden/{}.txt", env!("CARGO_MANIFEST_DIR"), name);
+  let actual = values.iter().map(|x| x.golden() + "\n").collect::<String>();
+  if std::env::var_os("RAND_UPDATE_GOLDEN").is_some() {
+    fs::write(&path, &actual).unwrap();
+  }
+  let expected = fs::read_to_string(&path)
+    .unwrap_or_else(|_| panic!("{} is missing: rerun the tests with RAND_UPDATE_GOLDEN=1 to create it", path));
+  if let Some((i, (e, a))) = expected.lines().zip(actual.lines()).enumerate().find(|(_, (e, a))| e != a) {
+    panic!("{}: value {} changed from {} to {}", path, i, e, a);
+  }
+  assert_eq!(expected.lines().count(), values.len(), "{}: wrong number of values", path);
+}
+
+fn stream<R: RandomStream>(name: &str, mut rng: R, dim: usize) {
+  check(name, &rng.next_n(N * dim));
+  check(&format!("{}_uniforms01", name), &rng.uniforms01(N * dim));
+}
+
+#[test]
+fn generators() {
+  stream("lcg", LCG::new(Some(SEED)), 1);
+  stream("xorshift64", Xorshift64::new(Some(SEED)), 1);
+  stream("mt19937", MT19937::new(Some(SEED)), 1);
+  stream("sobol", Sobol::new(4), 4);
+  stream("chacha8", ChaCha::from_key(8, [7; 32], 1), 1);
+  stream("chacha20", ChaCha::from_key(20, [7; 32], 1), 1);
+  check("xorshift64_f32", &Xorshift64::new(Some(SEED)).uniforms01_f32(N));
+}
+
+#[test]
+fn continuous() {
+  check("uniform", &Uniform::new(-1.0, 2.0, MT19937::new(Some(SEED))).sample_n(N));
+  check("uniform_f32", &Uniform::new(-1.0f32, 2.0, MT19937::new(Some(SEED))).sample_n(N));
+  check("exponential", &Exponential::new(2.0, Xorshift64::new(Some(SEED))).sample_n(N));
+  check("exponential_inverse", &exponential::inversecumulative::InverseCumulative::new(LCG::new(Some(SEED))).get_n(N));
+  check("exponential_ziggurat", &ExpZiggurat::<_>::new(Xorshift64::new(Some(SEED))).sample_n(N));
+}
+
+#[test]
+fn normal() {
+  check("normal_acklam", &Normal::<InverseCumulative<MT19937>>::new(1.0, 4.0, MT19937::new(Some(SEED))).sample_n(N));
+  let mut wichura = InverseCumulative::<_>::with_method(MT19937::new(Some(SEED)), Method::Wichura, false);
+  check("normal_wichura", &wichura.get_n(N));
+  check("normal_sobol", &Normal::<InverseCumulative<Sobol>>::new(0.0, 1.0, Sobol::new(1)).sample_n(N));
+  check("normal_polar", &Normal::<Polar<MT19937>>::new(1.0, 4.0, MT19937::new(Some(SEED))).sample_n(N));
+  check("normal_ziggurat", &Normal::<Ziggurat<Xorshift64>>::new(1.0, 4.0, Xorshift64::new(Some(SEED))).sample_n(N));
+  check("normal_ziggurat_f32",
+        &Normal::<Ziggurat<Xorshift64, f32>, f32>::new(1.0, 4.0, Xorshift64::new(Some(SEED))).sample_n(N));
+  // batched generation gives the same values
+  let batched = Ziggurat::<_>::new(Xorshift64::new(Some(SEED))).get_n_batched(N);
+  check("normal_ziggurat", &batched.iter().map(|z| 1.0 + 2.0 * z).collect::<Vec<f64>>());
+}
+
+#[test]
+fn truncated() {
+  check("truncated_normal_inversion",
+        &TruncatedNormal::<Inversion<_>>::new(0.0, 1.0, 1.0, 3.0, MT19937::new(Some(SEED))).sample_n(N));
+  check("truncated_normal_rejection",
+        &TruncatedNormal::<Rejection<_>>::new(0.0, 1.0, 1.0, 3.0, MT19937::new(Some(SEED))).sample_n(N));
+  check("truncated_exponential", &TruncatedExponential::new(2.0, 0.5, 1.5, MT19937::new(Some(SEED))).sample_n(N));
+}
+
+#[test]
+fn discrete() {
+  check("discrete", &Discrete::new(&[1, 2, 3, 4, 5, 6], LCG::new(Some(SEED))).sample_n(N));
+  check("discrete_weighted",
+        &DiscreteWeighted::new(&[(1, 0.1), (2, 0.2), (3, 0.7)], MT19937::new(Some(SEED))).sample_n(N));
+  check("without_replacement",
+        &WithoutReplacement::new(&[(1, 20), (2, 30), (3, 50)], MT19937::new(Some(SEED))).sample_n(N));
+  let alias = AliasTable::new(&[0.1, 0.2, 0.3, 0.4]);
+  let u = MT19937::new(Some(SEED)).uniforms01(N);
+  check("alias", &u.iter().map(|&u| alias.sample_1(u) as u32).collect::<Vec<_>>());
+}
+
+#[test]
+fn empirical() {
+  let x = [3.0, 1.0, 5.0, 2.0, 4.0, 2.5, 7.0, 0.5];
+  check("bootstrap", &Empirical::<Bootstrap<_>>::new(&x, MT19937::new(Some(SEED))).sample_n(N));
+  let normals = Ziggurat::<_>::new(Xorshift64::new(Some(SEED)));
+  check("smoothed_bootstrap",
+        &Empirical::<Smoothed<_, _>>::new(&x, Bandwidth::Silverman, MT19937::new(Some(SEED)), normals).sample_n(N));
+  check("interpolated", &Empirical::<Interpolated<_>>::new(&x, Sobol::new(1)).sample_n(N));
+}
+
+#[test]
+fn domain() {
+  let normals = || Ziggurat::<_>::new(Xorshift64::new(Some(SEED)));
+  let exponentials = || ExpZiggurat::<_>::new(MT19937::new(Some(SEED)));
+  check("sphere", &Dist::<Vec<f64>>::sample_n(&mut Sphere::new(4, normals()), N));
+  check("sphere_vector", &Dist::<Vector>::sample_n(&mut Sphere::new(3, normals()), N));
+  check("ball", &Dist::<Vec<f64>>::sample_n(&mut Ball::new(4, normals(), exponentials()), N));
+  check("ball_vector", &Dist::<Vector>::sample_n(&mut Ball::new(3, normals(), exponentials()), N));
+  check("simplex", &Simplex::new(4, exponentials()).sample_n(N));
+  check("disc", &Disc::new(Sobol::new(2)).sample_n(N));
+  check("triangle", &Triangle::new([[0.0, 0.0], [2.0, 0.0], [0.0, 1.0]], MT19937::new(Some(SEED))).sample_n(N));
+  let l = [[0.0, 0.0], [0.0, 2.0], [1.0, 2.0], [1.0, 1.0], [2.0, 1.0], [2.0, 0.0]];
+  check("polygon", &Polygon::new(&l, MT19937::new(Some(SEED))).sample_n(N));
+}
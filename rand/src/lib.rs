@@ -1,4 +1,31 @@
 //! Import the generator module
 //! Import the distribution module
+//! Import the generator quality test module
+//! Import the Monte Carlo integration module
+//! Import the stochastic process module
+//! Import the Markov chain Monte Carlo module
+//! Import the special function module
+//!
+//! Without the `std` feature the crate is `no_std`: the generators that can be sampled one value at a time, the
+//! densities, CDFs and special functions are always available, the `alloc` feature adds the random streams and
+//! distributions (which return `Vec`s), and `std` adds OS entropy and the remaining modules.
+#![cfg_attr(not(feature = "std"), no_std)]
+// Without std the float methods come from num_traits::Float. Its imports allow being unused, since when std is linked
+// anyway (by the test harness, or criterion's num-traits/std in test builds) the inherent methods take precedence.
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod gen;
-pub mod dist;
\ No newline at end of file
+pub mod dist;
+#[cfg(feature = "std")]
+pub mod quality;
+#[cfg(feature = "std")]
+pub mod montecarlo;
+#[cfg(feature = "std")]
+pub mod process;
+#[cfg(feature = "std")]
+pub mod mcmc;
+pub mod special;
+#[cfg(all(test, feature = "std"))]
+mod golden;
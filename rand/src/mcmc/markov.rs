@@ -0,0 +1,82 @@
+//! mcmc::markov: discrete-time Markov chains on a finite state space
+
+use crate::gen::RandomStream;
+use crate::dist::discrete::AliasTable;
+
+/// A Markov chain with states 0..n, sampled using an alias table for each row of the transition matrix, so each
+/// transition consumes exactly one uniform variate
+#[derive(Debug)]
+pub struct MarkovChain<R> {
+  rows: Vec<AliasTable>,
+  state: usize,
+  rng: R,
+}
+
+impl<R: RandomStream> MarkovChain<R> {
+  /// `transitions[i][j]` is the probability of moving from state i to state j
+  pub fn new(transitions: &[Vec<f64>], initial: usize, rng: R) -> MarkovChain<R> {
+    let n = transitions.len();
+    assert!(initial < n);
+    assert!(transitions.iter().all(|row| row.len() == n));
+    MarkovChain { rows: transitions.iter().map(|row| AliasTable::new(row)).collect(), state: initial, rng }
+  }
+
+  pub fn state(&self) -> usize {
+    self.state
+  }
+
+  pub fn states(&self) -> usize {
+    self.rows.len()
+  }
+
+  /// Advance n steps, returning the states visited (excluding the current state)
+  pub fn path(&mut self, n: usize) -> Vec<usize> {
+    self.rng.uniforms01(n)
+            .iter()
+            .map(|&u| {
+              self.state = self.rows[self.state].sample_1(u);
+              self.state
+            })
+            .collect()
+  }
+
+  /// Advance one step
+  pub fn step(&mut self) -> usize {
+    self.path(1)[0]
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+
+  const N: usize = 100000;
+
+  #[test]
+  fn stationary() {
+    // stationary distribution of [[0.9, 0.1], [0.5, 0.5]] is (5/6, 1/6)
+    let mut chain = MarkovChain::new(&[vec![0.9, 0.1], vec![0.5, 0.5]], 1, MT19937::new(Some(19937)));
+    assert_eq!(chain.states(), 2);
+    let p = chain.path(N);
+    let f = p.iter().filter(|&&s| s == 0).count() as f64 / N as f64;
+    assert!((f - 5.0 / 6.0).abs() < 0.01);
+    assert_eq!(chain.state(), p[N - 1]);
+  }
+
+  #[test]
+  fn absorbing() {
+    let mut chain = MarkovChain::new(&[vec![0.0, 1.0, 0.0], vec![0.0, 0.5, 0.5], vec![0.0, 0.0, 1.0]],
+                                     0, Xorshift64::new(Some(19937)));
+    assert_eq!(chain.step(), 1);
+    let p = chain.path(100);
+    assert_eq!(chain.state(), 2);
+    assert!(p.windows(2).all(|w| w[1] >= w[0]));
+  }
+
+  #[test]
+  #[should_panic]
+  fn not_square() {
+    MarkovChain::new(&[vec![0.5, 0.5], vec![1.0]], 0, LCG::new(None));
+  }
+}
@@ -0,0 +1,111 @@
+//! mcmc::metropolis: random-walk Metropolis-Hastings
+
+use crate::gen::{RandomStream, Dimensionless, Rejectable};
+use crate::dist::Dist;
+use crate::mcmc::Sampler;
+
+/// Random-walk Metropolis-Hastings with isotropic normal proposals. `normals` supplies standard normal variates
+/// for the proposals and `rng` the uniform variates for the acceptance test, which is a rejection so requires a
+/// `Rejectable` generator.
+pub struct MetropolisHastings<F, N, R> {
+  log_density: F,
+  step_size: f64,
+  x: Vec<f64>,
+  log_p: f64,
+  normals: N,
+  rng: R,
+}
+
+impl<F, N, R> MetropolisHastings<F, N, R>
+  where F: Fn(&[f64]) -> f64,
+        N: Dist<f64>,
+        R: RandomStream + Dimensionless + Rejectable
+{
+  /// `log_density` need not be normalised; the initial state x0 must have finite log density
+  pub fn new(log_density: F, x0: &[f64], step_size: f64, normals: N, rng: R) -> MetropolisHastings<F, N, R> {
+    assert!(!x0.is_empty() && step_size > 0.0);
+    let log_p = log_density(x0);
+    assert!(log_p.is_finite());
+    MetropolisHastings { log_density, step_size, x: x0.to_vec(), log_p, normals, rng }
+  }
+}
+
+impl<F, N, R> Sampler for MetropolisHastings<F, N, R>
+  where F: Fn(&[f64]) -> f64,
+        N: Dist<f64>,
+        R: RandomStream + Dimensionless + Rejectable
+{
+  fn step(&mut self) -> bool {
+    let z = self.normals.sample_n(self.x.len());
+    let y = self.x.iter().zip(z).map(|(x, z)| x + self.step_size * z).collect::<Vec<_>>();
+    let log_p = (self.log_density)(&y);
+    // symmetric proposal: accept with probability min(1, p(y)/p(x))
+    if (1.0 - self.rng.uniform01()).ln() < log_p - self.log_p {
+      self.x = y;
+      self.log_p = log_p;
+      true
+    } else {
+      false
+    }
+  }
+
+  fn state(&self) -> &[f64] {
+    &self.x
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::dist::continuous::Normal;
+  use crate::dist::normal::polar::Polar;
+  use crate::mcmc::run;
+
+  #[test]
+  fn normal_1d() {
+    let normals = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937)));
+    let mut mh = MetropolisHastings::new(|x| -0.5 * (x[0] - 2.0).powi(2), &[0.0], 2.4, normals,
+                                         Xorshift64::new(Some(19937)));
+    let s = run(&mut mh, 20000, 1000, 2);
+    let x = s.column(0);
+    let mean = s.mean()[0];
+    let var = x.iter().map(|xi| (xi - mean).powi(2)).sum::<f64>() / x.len() as f64;
+    let ess = s.ess()[0];
+    assert!(s.acceptance_rate > 0.3 && s.acceptance_rate < 0.6);
+    assert!(ess < 20000.0 && ess > 1000.0);
+    assert!((mean - 2.0).abs() < 4.0 / ess.sqrt());
+    assert!((var - 1.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn correlated_2d() {
+    // bivariate normal with unit variances and correlation 0.8
+    let rho: f64 = 0.8;
+    let log_p = move |x: &[f64]| -0.5 * (x[0] * x[0] - 2.0 * rho * x[0] * x[1] + x[1] * x[1]) / (1.0 - rho * rho);
+    let normals = Normal::<Polar<Xorshift64>>::new(0.0, 1.0, Xorshift64::new(Some(1)));
+    let mut mh = MetropolisHastings::new(log_p, &[3.0, -3.0], 1.0, normals, MT19937::new(Some(19937)));
+    let s = run(&mut mh, 20000, 2000, 5);
+    let c = s.values.iter().map(|x| x[0] * x[1]).sum::<f64>() / s.values.len() as f64;
+    assert!((c - rho).abs() < 0.1);
+    assert!(s.mean().iter().all(|m| m.abs() < 0.1));
+  }
+
+  #[test]
+  fn tiny_steps() {
+    // very small steps are almost always accepted but mix slowly
+    let normals = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937)));
+    let mut mh = MetropolisHastings::new(|x| -0.5 * x[0] * x[0], &[0.0], 0.01, normals, LCG::new(Some(19937)));
+    let s = run(&mut mh, 2000, 0, 1);
+    assert!(s.acceptance_rate > 0.99);
+    assert!(s.ess()[0] < 100.0);
+  }
+
+  #[test]
+  #[should_panic]
+  fn no_samples() {
+    let normals = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937)));
+    let mut mh = MetropolisHastings::new(|x| -0.5 * x[0] * x[0], &[0.0], 1.0, normals, LCG::new(Some(19937)));
+    run(&mut mh, 0, 10, 1);
+  }
+}
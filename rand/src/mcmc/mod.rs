@@ -0,0 +1,133 @@
+//! mcmc: Markov chains and Markov chain Monte Carlo samplers
+//!
+//! `markov` simulates discrete-time chains on a finite state space. `metropolis` and `slice` sample from a
+//! user-supplied (unnormalised) log density, and implement `Sampler` so they can be run with burn-in and thinning.
+
+pub mod markov;
+pub mod metropolis;
+pub mod slice;
+
+/// An MCMC sampler over R^d
+pub trait Sampler {
+  /// Advance the chain by one iteration, returning whether the state moved
+  fn step(&mut self) -> bool;
+  /// The current state of the chain
+  fn state(&self) -> &[f64];
+}
+
+/// The retained states of a chain
+#[derive(Debug, Clone)]
+pub struct Samples {
+  pub values: Vec<Vec<f64>>,
+  /// Proportion of iterations after burn-in in which the state moved
+  pub acceptance_rate: f64,
+}
+
+/// Run the sampler, discarding the first `burn_in` iterations and then keeping every `thin`th state until `n`
+/// states have been collected
+pub fn run<S: Sampler>(sampler: &mut S, n: usize, burn_in: usize, thin: usize) -> Samples {
+  assert!(n > 0 && thin > 0);
+  for _ in 0..burn_in {
+    sampler.step();
+  }
+  let mut accepted = 0;
+  let mut values = Vec::with_capacity(n);
+  for _ in 0..n {
+    for _ in 0..thin {
+      if sampler.step() { accepted += 1; }
+    }
+    values.push(sampler.state().to_vec());
+  }
+  Samples { values, acceptance_rate: accepted as f64 / (n * thin) as f64 }
+}
+
+impl Samples {
+  /// The samples of the ith coordinate
+  pub fn column(&self, i: usize) -> Vec<f64> {
+    self.values.iter().map(|x| x[i]).collect()
+  }
+
+  pub fn mean(&self) -> Vec<f64> {
+    let d = self.values.first().map_or(0, |x| x.len());
+    (0..d).map(|i| self.column(i).iter().sum::<f64>() / self.values.len() as f64).collect()
+  }
+
+  /// Effective sample size of each coordinate
+  pub fn ess(&self) -> Vec<f64> {
+    let d = self.values.first().map_or(0, |x| x.len());
+    (0..d).map(|i| effective_sample_size(&self.column(i))).collect()
+  }
+}
+
+/// Sample autocorrelation at lags 0..=max_lag
+pub fn autocorrelation(x: &[f64], max_lag: usize) -> Vec<f64> {
+  assert!(max_lag < x.len());
+  let (mean, c0) = centre(x);
+  (0..=max_lag).map(|k| autocovariance(x, mean, k) / c0).collect()
+}
+
+// mean and (unnormalised) variance
+fn centre(x: &[f64]) -> (f64, f64) {
+  let mean = x.iter().sum::<f64>() / x.len() as f64;
+  (mean, x.iter().map(|xi| (xi - mean).powi(2)).sum::<f64>())
+}
+
+fn autocovariance(x: &[f64], mean: f64, k: usize) -> f64 {
+  x.iter().zip(&x[k..]).map(|(a, b)| (a - mean) * (b - mean)).sum::<f64>()
+}
+
+/// Effective sample size n / tau, where the integrated autocorrelation time tau is estimated using Geyer's initial
+/// positive sequence (sums of adjacent pairs of autocorrelations are truncated at the first that is not positive)
+pub fn effective_sample_size(x: &[f64]) -> f64 {
+  let n = x.len();
+  assert!(n > 3);
+  let (mean, c0) = centre(x);
+  if c0 == 0.0 {
+    // constant chain
+    return 1.0;
+  }
+  let mut tau = -1.0;
+  // autocorrelations are computed lazily as the sum is usually truncated long before the maximum lag
+  for k in (0..n / 2).step_by(2) {
+    let g = (autocovariance(x, mean, k) + autocovariance(x, mean, k + 1)) / c0;
+    if g <= 0.0 { break; }
+    tau += 2.0 * g;
+  }
+  n as f64 / tau
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::dist::Dist;
+  use crate::dist::continuous::Normal;
+  use crate::dist::normal::polar::Polar;
+
+  const N: usize = 20000;
+
+  #[test]
+  fn ess_iid() {
+    let x = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937))).sample_n(N);
+    let ess = effective_sample_size(&x);
+    assert!((ess / N as f64 - 1.0).abs() < 0.1);
+    let rho = autocorrelation(&x, 2);
+    assert_eq!(rho[0], 1.0);
+    assert!(rho[1].abs() < 4.0 / (N as f64).sqrt());
+  }
+
+  #[test]
+  fn ess_ar1() {
+    // AR(1) with coefficient phi has tau = (1 + phi) / (1 - phi)
+    let phi = 0.9;
+    let z = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937))).sample_n(N);
+    let x = z.iter().scan(0.0, |s, zi| { *s = phi * *s + zi; Some(*s) }).collect::<Vec<_>>();
+    let expected = N as f64 * (1.0 - phi) / (1.0 + phi);
+    assert!((effective_sample_size(&x) / expected - 1.0).abs() < 0.25);
+  }
+
+  #[test]
+  fn constant() {
+    assert_eq!(effective_sample_size(&[1.0; 10]), 1.0);
+  }
+}
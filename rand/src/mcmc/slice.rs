@@ -0,0 +1,115 @@
+//! mcmc::slice: Neal's slice sampler, with stepping out and shrinkage, updating one coordinate at a time
+
+use crate::gen::{RandomStream, Dimensionless, Rejectable};
+use crate::mcmc::Sampler;
+
+pub struct SliceSampler<F, R> {
+  log_density: F,
+  width: f64,
+  max_steps: usize,
+  x: Vec<f64>,
+  log_p: f64,
+  rng: R,
+}
+
+impl<F, R> SliceSampler<F, R>
+  where F: Fn(&[f64]) -> f64,
+        R: RandomStream + Dimensionless + Rejectable
+{
+  /// `width` is the initial size of the interval around the current point and `max_steps` limits how far it may
+  /// be stepped out; the initial state x0 must have finite log density
+  pub fn new(log_density: F, x0: &[f64], width: f64, max_steps: usize, rng: R) -> SliceSampler<F, R> {
+    assert!(!x0.is_empty() && width > 0.0 && max_steps > 0);
+    let log_p = log_density(x0);
+    assert!(log_p.is_finite());
+    SliceSampler { log_density, width, max_steps, x: x0.to_vec(), log_p, rng }
+  }
+
+  // log density with the ith coordinate replaced
+  fn log_p_at(&self, i: usize, xi: f64) -> f64 {
+    let mut y = self.x.clone();
+    y[i] = xi;
+    (self.log_density)(&y)
+  }
+
+  fn update(&mut self, i: usize) {
+    // the slice is {x: log p(x) > log_y}
+    let log_y = self.log_p + (1.0 - self.rng.uniform01()).ln();
+    let x0 = self.x[i];
+    let mut l = x0 - self.width * self.rng.uniform01();
+    let mut r = l + self.width;
+    // step out, sharing the step budget randomly between the two sides
+    let mut j = (self.max_steps as f64 * self.rng.uniform01()) as usize;
+    let mut k = self.max_steps - 1 - j;
+    while j > 0 && self.log_p_at(i, l) > log_y {
+      l -= self.width;
+      j -= 1;
+    }
+    while k > 0 && self.log_p_at(i, r) > log_y {
+      r += self.width;
+      k -= 1;
+    }
+    // shrink until a point in the slice is found
+    loop {
+      let x1 = l + (r - l) * self.rng.uniform01();
+      let log_p = self.log_p_at(i, x1);
+      if log_p > log_y {
+        self.x[i] = x1;
+        self.log_p = log_p;
+        return;
+      }
+      if x1 < x0 { l = x1; } else { r = x1; }
+    }
+  }
+}
+
+impl<F, R> Sampler for SliceSampler<F, R>
+  where F: Fn(&[f64]) -> f64,
+        R: RandomStream + Dimensionless + Rejectable
+{
+  /// Update every coordinate in turn. The slice sampler always moves.
+  fn step(&mut self) -> bool {
+    for i in 0..self.x.len() {
+      self.update(i);
+    }
+    true
+  }
+
+  fn state(&self) -> &[f64] {
+    &self.x
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::mcmc::run;
+
+  #[test]
+  fn exponential() {
+    // unit exponential: mean 1, variance 1
+    let log_p = |x: &[f64]| if x[0] < 0.0 { f64::NEG_INFINITY } else { -x[0] };
+    let mut s = SliceSampler::new(log_p, &[1.0], 1.0, 10, MT19937::new(Some(19937)));
+    let samples = run(&mut s, 20000, 100, 1);
+    assert_eq!(samples.acceptance_rate, 1.0);
+    let x = samples.column(0);
+    let mean = samples.mean()[0];
+    let var = x.iter().map(|xi| (xi - mean).powi(2)).sum::<f64>() / x.len() as f64;
+    assert!(x.iter().all(|&xi| xi >= 0.0));
+    assert!((mean - 1.0).abs() < 4.0 / samples.ess()[0].sqrt());
+    assert!((var - 1.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn normal_2d() {
+    // independent normals with standard deviations 1 and 10: stepping out copes with the wider scale
+    let log_p = |x: &[f64]| -0.5 * (x[0] * x[0] + x[1] * x[1] / 100.0);
+    let mut s = SliceSampler::new(log_p, &[0.0, 0.0], 1.0, 50, Xorshift64::new(Some(19937)));
+    let samples = run(&mut s, 10000, 100, 1);
+    let sd = (0..2).map(|i| (samples.column(i).iter().map(|x| x * x).sum::<f64>() / 10000.0).sqrt())
+                   .collect::<Vec<_>>();
+    assert!((sd[0] - 1.0).abs() < 0.05);
+    assert!((sd[1] - 10.0).abs() < 0.5);
+  }
+}
@@ -0,0 +1,266 @@
+//! montecarlo: integration of functions over the unit hypercube
+//!
+//! The integrator draws points from any `RandomStream` in batches and stops once the estimated standard error
+//! reaches a target, or the evaluation budget is exhausted. Quasirandom streams (i.e. Sobol) are integrated over
+//! their own dimension, using `Integrator::quasi`, and the standard error (which assumes independent samples) is
+//! conservative.
+
+use crate::gen::{RandomStream, Dimensionless, Dimensioned};
+
+pub mod parallel;
+
+/// The result of an integration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+  pub value: f64,
+  pub std_error: f64,
+  /// number of integrand evaluations
+  pub samples: usize,
+}
+
+pub struct Integrator<R> {
+  dim: usize,
+  batch: usize,
+  max_samples: usize,
+  target_error: Option<f64>,
+  antithetic: bool,
+  rng: R,
+}
+
+// running means and (co)variances of observations and control variates (Welford's algorithm)
+#[derive(Debug, Default)]
+struct Accumulator {
+  n: usize,
+  mean_y: f64,
+  mean_c: f64,
+  ss_y: f64,
+  ss_c: f64,
+  ss_yc: f64,
+}
+
+impl Accumulator {
+  fn add(&mut self, y: f64, c: f64) {
+    self.n += 1;
+    let n = self.n as f64;
+    let dy = y - self.mean_y;
+    let dc = c - self.mean_c;
+    self.mean_y += dy / n;
+    self.mean_c += dc / n;
+    self.ss_y += dy * (y - self.mean_y);
+    self.ss_c += dc * (c - self.mean_c);
+    self.ss_yc += dy * (c - self.mean_c);
+  }
+
+  // mean and its standard error, adjusted by the control variate with the given mean, if present
+  fn estimate(&self, control_mean: Option<f64>) -> (f64, f64) {
+    let n = self.n as f64;
+    match control_mean {
+      Some(mu) if self.ss_c > 0.0 => {
+        let beta = self.ss_yc / self.ss_c;
+        let var = (self.ss_y - beta * self.ss_yc).max(0.0) / (n - 2.0).max(1.0);
+        (self.mean_y - beta * (self.mean_c - mu), (var / n).sqrt())
+      }
+      _ => (self.mean_y, (self.ss_y / (n - 1.0).max(1.0) / n).sqrt()),
+    }
+  }
+}
+
+impl<R: RandomStream + Dimensionless> Integrator<R> {
+  /// Integrates over [0,1)^dim, by default using up to a million evaluations in batches of 1000 points
+  pub fn new(dim: usize, rng: R) -> Integrator<R> {
+    Integrator::new_with(dim, rng)
+  }
+}
+
+impl<R: RandomStream + Dimensioned> Integrator<R> {
+  /// As `new`, for a generator with an inherent dimension, which must equal `dim`
+  pub fn quasi(dim: usize, rng: R) -> Integrator<R> {
+    assert_eq!(rng.dim() as usize, dim, "dimension doesn't match the generator's");
+    Integrator::new_with(dim, rng)
+  }
+}
+
+impl<R: RandomStream> Integrator<R> {
+  fn new_with(dim: usize, rng: R) -> Integrator<R> {
+    assert!(dim > 0);
+    Integrator { dim, batch: 1000, max_samples: 1_000_000, target_error: None, antithetic: false, rng }
+  }
+
+  /// Set the number of points drawn from the stream at a time
+  pub fn batch(&mut self, n: usize) -> &mut Self {
+    assert!(n > 0);
+    self.batch = n;
+    self
+  }
+
+  /// Set the maximum number of integrand evaluations. Antithetic pairs aren't split, so with antithetic variates an
+  /// odd budget leaves one evaluation unused.
+  pub fn max_samples(&mut self, n: usize) -> &mut Self {
+    assert!(n > 0);
+    self.max_samples = n;
+    self
+  }
+
+  /// Stop once the standard error falls to this level
+  pub fn target_error(&mut self, e: f64) -> &mut Self {
+    assert!(e > 0.0);
+    self.target_error = Some(e);
+    self
+  }
+
+  /// Evaluate at each point u and its reflection 1-u
+  pub fn antithetic(&mut self, on: bool) -> &mut Self {
+    self.antithetic = on;
+    self
+  }
+
+  /// Plain Monte Carlo estimate of the integral of f
+  pub fn integrate<F: Fn(&[f64]) -> f64>(&mut self, f: F) -> Estimate {
+    self.run(|u| (f(u), 0.0), None)
+  }
+
+  /// Estimate the integral of f using g, whose integral is known to be `g_mean`, as a control variate
+  pub fn integrate_control<F, G>(&mut self, f: F, g: G, g_mean: f64) -> Estimate
+    where F: Fn(&[f64]) -> f64,
+          G: Fn(&[f64]) -> f64
+  {
+    self.run(|u| (f(u), g(u)), Some(g_mean))
+  }
+
+  /// Estimate the integral of f over [0,1)^dim by importance sampling: `sample` maps a uniform point to one
+  /// drawn from the proposal distribution (e.g. by inverse CDF) and `density` is the proposal density
+  pub fn integrate_importance<F, S, Q>(&mut self, f: F, sample: S, density: Q) -> Estimate
+    where F: Fn(&[f64]) -> f64,
+          S: Fn(&[f64]) -> Vec<f64>,
+          Q: Fn(&[f64]) -> f64
+  {
+    self.run(|u| { let x = sample(u); (f(&x) / density(&x), 0.0) }, None)
+  }
+
+  fn run<H: Fn(&[f64]) -> (f64, f64)>(&mut self, h: H, control_mean: Option<f64>) -> Estimate {
+    let evals_per_point = if self.antithetic { 2 } else { 1 };
+    let max_points = self.max_samples / evals_per_point;
+    assert!(max_points > 0, "antithetic variates need a budget of at least 2 evaluations");
+    let mut acc = Accumulator::default();
+    let mut reflected = vec![0.0; self.dim];
+    loop {
+      // draw no more points than are evaluated, so the stream can be used again (or skipped) exactly
+      let u = self.rng.uniforms01(self.batch.min(max_points - acc.n) * self.dim);
+      for p in u.chunks(self.dim) {
+        let (y, c) = if self.antithetic {
+          for (r, &x) in reflected.iter_mut().zip(p) {
+            *r = 1.0 - x;
+          }
+          let (y0, c0) = h(p);
+          let (y1, c1) = h(&reflected);
+          (0.5 * (y0 + y1), 0.5 * (c0 + c1))
+        } else {
+          h(p)
+        };
+        acc.add(y, c);
+      }
+      let (value, std_error) = acc.estimate(control_mean);
+      let converged = acc.n > 1 && self.target_error.is_some_and(|e| std_error <= e);
+      if converged || acc.n == max_points {
+        return Estimate { value, std_error, samples: acc.n * evals_per_point };
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::gen::quasi::*;
+
+  const N: usize = 100_000;
+
+  fn check(e: &Estimate, expected: f64) {
+    assert!((e.value - expected).abs() < 4.0 * e.std_error);
+  }
+
+  #[test]
+  fn plain() {
+    let e = Integrator::new(1, MT19937::new(Some(19937))).max_samples(N).integrate(|x| x[0] * x[0]);
+    assert_eq!(e.samples, N);
+    check(&e, 1.0 / 3.0);
+    // standard deviation of x^2 is 2/sqrt(45)
+    assert!((e.std_error * (N as f64).sqrt() - 2.0 / 45.0f64.sqrt()).abs() < 0.01);
+  }
+
+  #[test]
+  fn antithetic() {
+    let plain = Integrator::new(1, MT19937::new(Some(19937))).max_samples(N).integrate(|x| x[0].exp());
+    let anti = Integrator::new(1, MT19937::new(Some(19937))).max_samples(N).antithetic(true).integrate(|x| x[0].exp());
+    assert_eq!(anti.samples, N);
+    check(&anti, 1.0f64.exp() - 1.0);
+    // exp is monotone, so antithetic variates are negatively correlated
+    assert!(anti.std_error < 0.2 * plain.std_error);
+  }
+
+  #[test]
+  fn control() {
+    let plain = Integrator::new(1, Xorshift64::new(Some(19937))).max_samples(N).integrate(|x| x[0].exp());
+    let cv = Integrator::new(1, Xorshift64::new(Some(19937))).max_samples(N)
+                                                              .integrate_control(|x| x[0].exp(), |x| x[0], 0.5);
+    check(&cv, 1.0f64.exp() - 1.0);
+    assert!(cv.std_error < 0.2 * plain.std_error);
+  }
+
+  #[test]
+  fn importance() {
+    // proposal density 5x^4, sampled by inverting its CDF x^5
+    let plain = Integrator::new(1, MT19937::new(Some(19937))).max_samples(N).integrate(|x| x[0].powi(5));
+    let is = Integrator::new(1, MT19937::new(Some(19937))).max_samples(N)
+                                                          .integrate_importance(|x| x[0].powi(5),
+                                                                                |u| vec![u[0].powf(0.2)],
+                                                                                |x| 5.0 * x[0].powi(4));
+    check(&is, 1.0 / 6.0);
+    assert!(is.std_error < 0.2 * plain.std_error);
+  }
+
+  #[test]
+  fn target_error() {
+    let e = Integrator::new(2, LCG::new(Some(19937))).target_error(1e-3).integrate(|x| x[0] * x[1]);
+    check(&e, 0.25);
+    assert!(e.std_error <= 1e-3);
+    assert!(e.samples < 1_000_000);
+    assert_eq!(e.samples % 1000, 0);
+  }
+
+  #[test]
+  fn quasi() {
+    const DIM: usize = 4;
+    // product of 2x over each dimension integrates to 1
+    let f = |x: &[f64]| x.iter().map(|&xi| 2.0 * xi).product::<f64>();
+    let q = Integrator::quasi(DIM, Sobol::new(DIM as u32)).max_samples(65536).batch(1024).integrate(f);
+    let p = Integrator::new(DIM, MT19937::new(Some(19937))).max_samples(65536).integrate(f);
+    check(&p, 1.0);
+    assert!((q.value - 1.0).abs() < 0.1 * (p.value - 1.0).abs());
+  }
+
+  #[test]
+  fn budget() {
+    // an odd budget with antithetic variates stops a pair short
+    let e = Integrator::new(1, MT19937::new(Some(19937))).max_samples(1001).antithetic(true).integrate(|x| x[0]);
+    assert_eq!(e.samples, 1000);
+    // a budget that isn't a multiple of the batch draws only the points evaluated, so a second run continues from
+    // the next point of the sequence
+    let f = |x: &[f64]| x[0] * x[1];
+    let mut q = Integrator::quasi(2, Sobol::new(2));
+    q.max_samples(1000).batch(300);
+    q.integrate(f);
+    let e = q.integrate(f);
+    let u = Sobol::new(2).uniforms01(4000);
+    let expected = u[2000..].chunks(2).map(f).sum::<f64>() / 1000.0;
+    assert_eq!(e.samples, 1000);
+    assert!((e.value - expected).abs() < 1e-15);
+  }
+
+  #[test]
+  #[should_panic(expected = "dimension doesn't match")]
+  fn quasi_dim() {
+    Integrator::quasi(3, Sobol::new(2));
+  }
+}
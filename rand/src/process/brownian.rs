@@ -0,0 +1,260 @@
+//! process::brownian: Brownian motion and processes that are exact functions of it
+
+use crate::dist::Dist;
+use crate::process::{Paths, grid};
+
+/// Brownian bridge construction on a time grid: the first variate determines the terminal value, the next the
+/// value at the midpoint, and so on. With quasirandom variates this concentrates the variance of the path in the
+/// first (best distributed) dimensions.
+#[derive(Debug, Clone)]
+pub struct BrownianBridge {
+  bridge: Vec<usize>,
+  left: Vec<usize>,
+  right: Vec<usize>,
+  left_weight: Vec<f64>,
+  right_weight: Vec<f64>,
+  std_dev: Vec<f64>,
+}
+
+impl BrownianBridge {
+  /// `times` are strictly increasing and positive (the path starts at zero at time zero)
+  pub fn new(times: &[f64]) -> BrownianBridge {
+    let t = grid(times);
+    let n = times.len();
+    // indices below refer to t, i.e. 1..=n, with 0 the (known) start of the path
+    let mut populated = vec![false; n + 1];
+    populated[0] = true;
+    populated[n] = true;
+    let mut b = BrownianBridge { bridge: vec![n; n], left: vec![0; n], right: vec![0; n], left_weight: vec![0.0; n],
+                                 right_weight: vec![0.0; n], std_dev: vec![t[n].sqrt(); n] };
+    let mut j = 0;
+    for i in 1..n {
+      // find the next unpopulated interval (j, k)
+      while populated[j + 1] { j += 1; if j == n { j = 0; } }
+      let mut k = j + 1;
+      while !populated[k] { k += 1; }
+      let l = (j + k) / 2;
+      populated[l] = true;
+      b.bridge[i] = l;
+      b.left[i] = j;
+      b.right[i] = k;
+      b.left_weight[i] = (t[k] - t[l]) / (t[k] - t[j]);
+      b.right_weight[i] = (t[l] - t[j]) / (t[k] - t[j]);
+      b.std_dev[i] = ((t[l] - t[j]) * (t[k] - t[l]) / (t[k] - t[j])).sqrt();
+      j = if k >= n { 0 } else { k };
+    }
+    b
+  }
+
+  /// Number of time steps (and variates per path)
+  pub fn len(&self) -> usize {
+    self.bridge.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.bridge.is_empty()
+  }
+
+  /// Construct a path (including the initial zero) from standard normal variates
+  pub fn path(&self, z: &[f64]) -> Vec<f64> {
+    assert_eq!(z.len(), self.len());
+    let mut w = vec![0.0; self.len() + 1];
+    for (i, zi) in z.iter().enumerate() {
+      w[self.bridge[i]] = self.left_weight[i] * w[self.left[i]] + self.right_weight[i] * w[self.right[i]]
+                          + self.std_dev[i] * zi;
+    }
+    w
+  }
+}
+
+/// Standard Brownian motion, constructed incrementally or via a Brownian bridge
+#[derive(Debug)]
+pub struct Brownian<D> {
+  times: Vec<f64>,
+  bridge: Option<BrownianBridge>,
+  normals: D,
+}
+
+impl<D: Dist<f64>> Brownian<D> {
+  /// Incremental construction from standard normal variates
+  pub fn new(times: &[f64], normals: D) -> Brownian<D> {
+    Brownian { times: grid(times), bridge: None, normals }
+  }
+
+  /// Brownian bridge construction. To use with Sobol the generator dimension should equal the number of times.
+  pub fn with_bridge(times: &[f64], normals: D) -> Brownian<D> {
+    Brownian { times: grid(times), bridge: Some(BrownianBridge::new(times)), normals }
+  }
+
+  pub fn times(&self) -> &[f64] {
+    &self.times
+  }
+
+  /// Sample n paths
+  pub fn paths(&mut self, n: usize) -> Paths {
+    let steps = self.times.len() - 1;
+    let mut values = Vec::with_capacity(n * (steps + 1));
+    for _ in 0..n {
+      let z = self.normals.sample_n(steps);
+      match &self.bridge {
+        Some(b) => values.extend(b.path(&z)),
+        None => {
+          let mut w = 0.0;
+          values.push(w);
+          for (i, zi) in z.iter().enumerate() {
+            w += (self.times[i + 1] - self.times[i]).sqrt() * zi;
+            values.push(w);
+          }
+        }
+      }
+    }
+    Paths::new(self.times.clone(), values)
+  }
+}
+
+/// Geometric Brownian motion dS = mu S dt + sigma S dW, sampled exactly
+#[derive(Debug)]
+pub struct GeometricBrownian<D> {
+  s0: f64,
+  mu: f64,
+  sigma: f64,
+  brownian: Brownian<D>,
+}
+
+impl<D: Dist<f64>> GeometricBrownian<D> {
+  pub fn new(s0: f64, mu: f64, sigma: f64, brownian: Brownian<D>) -> GeometricBrownian<D> {
+    assert!(s0 > 0.0 && sigma >= 0.0);
+    GeometricBrownian { s0, mu, sigma, brownian }
+  }
+
+  pub fn paths(&mut self, n: usize) -> Paths {
+    let (s0, drift, sigma) = (self.s0, self.mu - 0.5 * self.sigma * self.sigma, self.sigma);
+    self.brownian.paths(n).map(|t, w| s0 * (drift * t + sigma * w).exp())
+  }
+}
+
+/// Ornstein-Uhlenbeck process dX = theta (mu - X) dt + sigma dW, sampled exactly
+#[derive(Debug)]
+pub struct OrnsteinUhlenbeck<D> {
+  x0: f64,
+  theta: f64,
+  mu: f64,
+  sigma: f64,
+  brownian: Brownian<D>,
+}
+
+impl<D: Dist<f64>> OrnsteinUhlenbeck<D> {
+  pub fn new(x0: f64, theta: f64, mu: f64, sigma: f64, brownian: Brownian<D>) -> OrnsteinUhlenbeck<D> {
+    assert!(theta > 0.0 && sigma >= 0.0);
+    OrnsteinUhlenbeck { x0, theta, mu, sigma, brownian }
+  }
+
+  pub fn paths(&mut self, n: usize) -> Paths {
+    let w = self.brownian.paths(n);
+    let t = w.times();
+    let mut values = Vec::with_capacity(w.len() * t.len());
+    for p in w.iter() {
+      let mut x = self.x0;
+      values.push(x);
+      for i in 1..t.len() {
+        let dt = t[i] - t[i - 1];
+        // the normalised Brownian increment is a standard normal
+        let z = (p[i] - p[i - 1]) / dt.sqrt();
+        let decay = (-self.theta * dt).exp();
+        x = self.mu + (x - self.mu) * decay + self.sigma * ((1.0 - decay * decay) / (2.0 * self.theta)).sqrt() * z;
+        values.push(x);
+      }
+    }
+    Paths::new(t.to_vec(), values)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::gen::quasi::*;
+  use crate::dist::continuous::Normal;
+  use crate::dist::normal::polar::Polar;
+  use crate::dist::normal::acklam::InverseCumulative;
+  use crate::dist::moments::OnlineMoments;
+
+  const N: usize = 20000;
+
+  fn times(steps: usize, t: f64) -> Vec<f64> {
+    (1..=steps).map(|i| t * i as f64 / steps as f64).collect()
+  }
+
+  fn moments(v: &[f64]) -> OnlineMoments {
+    v.iter().fold(OnlineMoments::new(), |mut m, &x| { m.add(x); m })
+  }
+
+  #[test]
+  fn bridge_order() {
+    let b = BrownianBridge::new(&times(8, 1.0));
+    assert_eq!(b.bridge, vec![8, 4, 2, 6, 1, 3, 5, 7]);
+    // with only the first variate nonzero the path is a straight line
+    let mut z = vec![0.0; 8];
+    z[0] = 1.0;
+    let w = b.path(&z);
+    for (i, wi) in w.iter().enumerate() {
+      assert!((wi - i as f64 / 8.0).abs() < 1e-15);
+    }
+    // uneven grids and lengths not a power of 2
+    let b = BrownianBridge::new(&[0.1, 0.3, 0.35, 1.0, 2.5]);
+    let mut bridged = b.bridge.clone();
+    bridged.sort();
+    assert_eq!(bridged, vec![1, 2, 3, 4, 5]);
+  }
+
+  #[test]
+  fn covariance() {
+    let t = times(5, 2.0);
+    for bridge in &[false, true] {
+      let normals = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937)));
+      let mut bm = if *bridge { Brownian::with_bridge(&t, normals) } else { Brownian::new(&t, normals) };
+      let p = bm.paths(N);
+      assert_eq!(p.len(), N);
+      assert_eq!(p.at(0), vec![0.0; N]);
+      // Cov(W_s, W_t) = min(s, t)
+      for (i, j) in &[(1, 1), (2, 4), (5, 5), (3, 5)] {
+        let c = p.iter().map(|w| w[*i] * w[*j]).sum::<f64>() / N as f64;
+        assert!((c - t[i - 1]).abs() < 4.0 * t[j - 1] * (2.0 / N as f64).sqrt());
+      }
+    }
+  }
+
+  #[test]
+  fn sobol_bridge() {
+    const STEPS: usize = 16;
+    let t = times(STEPS, 1.0);
+    let normals = Normal::<InverseCumulative<Sobol>>::new(0.0, 1.0, Sobol::new(STEPS as u32));
+    let p = Brownian::with_bridge(&t, normals).paths(4096);
+    let m = moments(&p.at(STEPS).iter().map(|w| w * w).collect::<Vec<_>>());
+    // terminal variance comes from the first dimension alone so is very accurate
+    assert!((m.mean() - 1.0).abs() < 1e-2);
+  }
+
+  #[test]
+  fn geometric() {
+    let t = times(4, 1.0);
+    let normals = Normal::<Polar<Xorshift64>>::new(0.0, 1.0, Xorshift64::new(Some(19937)));
+    let p = GeometricBrownian::new(100.0, 0.05, 0.2, Brownian::new(&t, normals)).paths(N);
+    let m = moments(&p.at(4));
+    assert!((m.mean() - 100.0 * 0.05f64.exp()).abs() < 4.0 * (m.variance() / N as f64).sqrt());
+    assert!(p.iter().all(|s| s.iter().all(|&x| x > 0.0)));
+  }
+
+  #[test]
+  fn ornstein_uhlenbeck() {
+    let (x0, theta, mu, sigma) = (1.0, 2.0, -1.0, 0.5);
+    let t = times(10, 1.0);
+    let normals = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937)));
+    let p = OrnsteinUhlenbeck::new(x0, theta, mu, sigma, Brownian::new(&t, normals)).paths(N);
+    let m = moments(&p.at(10));
+    let mean = mu + (x0 - mu) * (-theta).exp();
+    let var = sigma * sigma / (2.0 * theta) * (1.0 - (-2.0 * theta).exp());
+    assert!((m.mean() - mean).abs() < 4.0 * (var / N as f64).sqrt());
+    assert!((m.variance() / var - 1.0).abs() < 0.05);
+  }
+}
@@ -0,0 +1,92 @@
+//! process: simulation of stochastic processes
+//!
+//! Continuous processes are driven by Brownian paths built from standard normal variates supplied by any
+//! `Dist<f64>` (e.g. `Normal::<InverseCumulative<Sobol>>::new(0.0, 1.0, ...)`), and are returned as `Paths`.
+//! Point processes are driven by unit-rate exponential variates.
+
+pub mod brownian;
+pub mod sde;
+pub mod poisson;
+
+/// A set of paths sampled on a common time grid. The first time is always zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paths {
+  times: Vec<f64>,
+  // row-major: one row per path
+  values: Vec<f64>,
+}
+
+impl Paths {
+  /// Create from row-major values, one row of length times.len() per path
+  pub fn new(times: Vec<f64>, values: Vec<f64>) -> Paths {
+    assert!(!times.is_empty() && times[0] == 0.0);
+    assert_eq!(values.len() % times.len(), 0);
+    Paths { times, values }
+  }
+
+  pub fn times(&self) -> &[f64] {
+    &self.times
+  }
+
+  /// Number of paths
+  pub fn len(&self) -> usize {
+    self.values.len() / self.times.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// The ith path, indexed by time
+  pub fn path(&self, i: usize) -> &[f64] {
+    let n = self.times.len();
+    &self.values[i * n..(i + 1) * n]
+  }
+
+  pub fn iter(&self) -> std::slice::Chunks<'_, f64> {
+    self.values.chunks(self.times.len())
+  }
+
+  /// Values of every path at the jth time
+  pub fn at(&self, j: usize) -> Vec<f64> {
+    self.iter().map(|p| p[j]).collect()
+  }
+
+  /// Apply a function pointwise to every value, given the time
+  pub fn map<F: Fn(f64, f64) -> f64>(&self, f: F) -> Paths {
+    let n = self.times.len();
+    let values = self.values.iter().enumerate().map(|(i, &x)| f(self.times[i % n], x)).collect();
+    Paths { times: self.times.clone(), values }
+  }
+}
+
+// prepend zero to a strictly increasing grid of positive times
+fn grid(times: &[f64]) -> Vec<f64> {
+  assert!(!times.is_empty() && times[0] > 0.0);
+  assert!(times.windows(2).all(|w| w[1] > w[0]));
+  let mut t = Vec::with_capacity(times.len() + 1);
+  t.push(0.0);
+  t.extend_from_slice(times);
+  t
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn paths() {
+    let p = Paths::new(grid(&[0.5, 1.0]), vec![0.0, 1.0, 2.0, 0.0, -1.0, -2.0]);
+    assert_eq!(p.len(), 2);
+    assert_eq!(p.times(), &[0.0, 0.5, 1.0]);
+    assert_eq!(p.path(1), &[0.0, -1.0, -2.0]);
+    assert_eq!(p.at(2), vec![2.0, -2.0]);
+    assert_eq!(p.map(|t, x| t * x).path(0), &[0.0, 0.5, 2.0]);
+  }
+
+  #[test]
+  #[should_panic]
+  fn bad_grid() {
+    grid(&[0.5, 0.5]);
+  }
+}
@@ -0,0 +1,193 @@
+//! process::poisson: Poisson point processes
+//!
+//! All the processes here are driven by unit-rate exponential variates from any `Dist<f64>`, e.g.
+//! `exponential::ziggurat::Ziggurat` or `continuous::Exponential::new(1.0, ...)`. Variates are drawn in blocks and
+//! any left over at the horizon are discarded, so quasirandom streams are not appropriate.
+
+use crate::dist::Dist;
+use crate::process::{Paths, grid};
+
+/// Homogeneous Poisson process with constant rate
+#[derive(Debug)]
+pub struct Poisson<D> {
+  rate: f64,
+  exponentials: D,
+}
+
+/// Non-homogeneous Poisson process with intensity bounded above by `max_rate`, sampled by thinning
+#[derive(Debug)]
+pub struct NonHomogeneousPoisson<D, F> {
+  intensity: F,
+  process: Poisson<D>,
+}
+
+/// Compound Poisson process: the sum of i.i.d. jumps occurring at the events of a Poisson process
+#[derive(Debug)]
+pub struct CompoundPoisson<D, J> {
+  process: Poisson<D>,
+  jumps: J,
+}
+
+impl<D: Dist<f64>> Poisson<D> {
+  pub fn new(rate: f64, exponentials: D) -> Poisson<D> {
+    assert!(rate > 0.0);
+    Poisson { rate, exponentials }
+  }
+
+  /// Event times in (0, horizon]
+  pub fn events(&mut self, horizon: f64) -> Vec<f64> {
+    assert!(horizon > 0.0);
+    // draw enough for the expected number of events plus a few standard deviations
+    let mean = self.rate * horizon;
+    let block = (mean + 4.0 * mean.sqrt()).ceil() as usize + 1;
+    let mut events = Vec::with_capacity(block);
+    let mut t = 0.0;
+    loop {
+      for e in self.exponentials.sample_n(block) {
+        t += e / self.rate;
+        if t > horizon {
+          return events;
+        }
+        events.push(t);
+      }
+    }
+  }
+
+  /// Sample n paths of the counting process N(t) at the given times
+  pub fn paths(&mut self, times: &[f64], n: usize) -> Paths {
+    let t = grid(times);
+    let horizon = *t.last().unwrap();
+    let mut values = Vec::with_capacity(n * t.len());
+    for _ in 0..n {
+      let events = self.events(horizon);
+      values.extend(t.iter().map(|&s| events.iter().take_while(|&&e| e <= s).count() as f64));
+    }
+    Paths::new(t, values)
+  }
+}
+
+impl<D: Dist<f64>, F: Fn(f64) -> f64> NonHomogeneousPoisson<D, F> {
+  /// `intensity(t)` must lie in [0, max_rate] for all t
+  pub fn new(intensity: F, max_rate: f64, exponentials: D) -> NonHomogeneousPoisson<D, F> {
+    NonHomogeneousPoisson { intensity, process: Poisson::new(max_rate, exponentials) }
+  }
+
+  /// Event times in (0, horizon]
+  pub fn events(&mut self, horizon: f64) -> Vec<f64> {
+    let candidates = self.process.events(horizon);
+    // if E is unit exponential then exp(-E) is uniform on (0,1], so the same source provides the acceptance variates
+    let u = self.process.exponentials.sample_n(candidates.len());
+    let max_rate = self.process.rate;
+    candidates.into_iter()
+              .zip(u)
+              .filter(|&(t, e)| {
+                let lambda = (self.intensity)(t);
+                assert!((0.0..=max_rate).contains(&lambda), "intensity {} at t={} outside [0, max_rate]", lambda, t);
+                (-e).exp() * max_rate <= lambda
+              })
+              .map(|(t, _)| t)
+              .collect()
+  }
+}
+
+impl<D: Dist<f64>, J: Dist<f64>> CompoundPoisson<D, J> {
+  pub fn new(rate: f64, exponentials: D, jumps: J) -> CompoundPoisson<D, J> {
+    CompoundPoisson { process: Poisson::new(rate, exponentials), jumps }
+  }
+
+  /// Event times in (0, horizon] and the corresponding jump sizes
+  pub fn events(&mut self, horizon: f64) -> Vec<(f64, f64)> {
+    let t = self.process.events(horizon);
+    let j = self.jumps.sample_n(t.len());
+    t.into_iter().zip(j).collect()
+  }
+
+  /// The sum of the jumps up to the horizon
+  pub fn total(&mut self, horizon: f64) -> f64 {
+    self.events(horizon).iter().map(|&(_, j)| j).sum()
+  }
+
+  /// Sample n paths of the cumulative process at the given times
+  pub fn paths(&mut self, times: &[f64], n: usize) -> Paths {
+    let t = grid(times);
+    let horizon = *t.last().unwrap();
+    let mut values = Vec::with_capacity(n * t.len());
+    for _ in 0..n {
+      let events = self.events(horizon);
+      values.extend(t.iter().map(|&s| events.iter().take_while(|&&(e, _)| e <= s).map(|&(_, j)| j).sum::<f64>()));
+    }
+    Paths::new(t, values)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::dist::continuous::{Exponential, Uniform};
+  use crate::dist::exponential::ziggurat::Ziggurat;
+  use crate::dist::moments::OnlineMoments;
+
+  const N: usize = 10000;
+
+  fn moments<I: Iterator<Item = f64>>(v: I) -> OnlineMoments {
+    v.fold(OnlineMoments::new(), |mut m, x| { m.add(x); m })
+  }
+
+  #[test]
+  fn homogeneous() {
+    let (rate, horizon) = (3.0, 2.0);
+    let mut p = Poisson::new(rate, Ziggurat::new(MT19937::new(Some(19937))));
+    let m = moments((0..N).map(|_| p.events(horizon).len() as f64));
+    // count is Poisson(rate * horizon)
+    assert!((m.mean() - rate * horizon).abs() < 4.0 * (rate * horizon / N as f64).sqrt());
+    assert!((m.variance() / (rate * horizon) - 1.0).abs() < 0.05);
+    let e = p.events(horizon);
+    assert!(e.windows(2).all(|w| w[1] > w[0]));
+    assert!(e.iter().all(|&t| t > 0.0 && t <= horizon));
+  }
+
+  #[test]
+  fn counting_paths() {
+    let mut p = Poisson::new(10.0, Exponential::new(1.0, Xorshift64::new(Some(19937))));
+    let paths = p.paths(&[0.5, 1.0], 1000);
+    assert!(paths.iter().all(|c| c[0] == 0.0 && c[1] <= c[2]));
+    let m = moments(paths.at(1).into_iter());
+    assert!((m.mean() - 5.0).abs() < 4.0 * (5.0 / 1000.0f64).sqrt());
+  }
+
+  #[test]
+  fn non_homogeneous() {
+    // intensity 2t on [0,3] has cumulative intensity t^2, so 9 events expected with mean time 2
+    let mut p = NonHomogeneousPoisson::new(|t| 2.0 * t, 6.0, Ziggurat::new(MT19937::new(Some(19937))));
+    let mut times = OnlineMoments::new();
+    let counts = moments((0..N).map(|_| {
+                           let e = p.events(3.0);
+                           e.iter().for_each(|&t| { times.add(t); });
+                           e.len() as f64
+                         }));
+    assert!((counts.mean() - 9.0).abs() < 4.0 * (9.0 / N as f64).sqrt());
+    assert!((counts.variance() / 9.0 - 1.0).abs() < 0.05);
+    assert!((times.mean() - 2.0).abs() < 0.02);
+  }
+
+  #[test]
+  #[should_panic]
+  fn bad_intensity() {
+    let mut p = NonHomogeneousPoisson::new(|t| 2.0 * t, 1.0, Ziggurat::new(MT19937::new(Some(19937))));
+    p.events(10.0);
+  }
+
+  #[test]
+  fn compound() {
+    // jumps uniform on [1,3]: E[S] = rate * T * 2, Var[S] = rate * T * E[J^2] = rate * T * 13/3
+    let (rate, horizon) = (2.0, 1.5);
+    let mut p = CompoundPoisson::new(rate, Ziggurat::new(Xorshift64::new(Some(19937))),
+                                     Uniform::new(1.0, 3.0, MT19937::new(Some(19937))));
+    let m = moments((0..N).map(|_| p.total(horizon)));
+    assert!((m.mean() - rate * horizon * 2.0).abs() < 4.0 * (m.variance() / N as f64).sqrt());
+    assert!((m.variance() / (rate * horizon * 13.0 / 3.0) - 1.0).abs() < 0.05);
+    let paths = p.paths(&[0.5, 1.0, 1.5], 100);
+    assert!(paths.iter().all(|s| s.windows(2).all(|w| w[1] >= w[0])));
+  }
+}
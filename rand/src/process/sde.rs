@@ -0,0 +1,93 @@
+//! process::sde: numerical solution of scalar SDEs dX = a(t,X) dt + b(t,X) dW
+
+use crate::dist::Dist;
+use crate::process::Paths;
+use crate::process::brownian::Brownian;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheme {
+  /// strong order 1/2
+  EulerMaruyama,
+  /// strong order 1, requires the derivative of the diffusion coefficient
+  Milstein,
+}
+
+/// A scalar SDE given its drift a(t,x), diffusion b(t,x), and the derivative of the diffusion with respect to x
+/// (only used by the Milstein scheme)
+pub struct Sde<A, B, C> {
+  drift: A,
+  diffusion: B,
+  diffusion_dx: C,
+}
+
+impl<A, B, C> Sde<A, B, C>
+  where A: Fn(f64, f64) -> f64,
+        B: Fn(f64, f64) -> f64,
+        C: Fn(f64, f64) -> f64
+{
+  pub fn new(drift: A, diffusion: B, diffusion_dx: C) -> Sde<A, B, C> {
+    Sde { drift, diffusion, diffusion_dx }
+  }
+
+  /// Solve along existing Brownian paths
+  pub fn integrate(&self, x0: f64, w: &Paths, scheme: Scheme) -> Paths {
+    let t = w.times();
+    let mut values = Vec::with_capacity(w.len() * t.len());
+    for p in w.iter() {
+      let mut x = x0;
+      values.push(x);
+      for i in 1..t.len() {
+        let (s, dt, dw) = (t[i - 1], t[i] - t[i - 1], p[i] - p[i - 1]);
+        let b = (self.diffusion)(s, x);
+        let mut dx = (self.drift)(s, x) * dt + b * dw;
+        if scheme == Scheme::Milstein {
+          dx += 0.5 * b * (self.diffusion_dx)(s, x) * (dw * dw - dt);
+        }
+        x += dx;
+        values.push(x);
+      }
+    }
+    Paths::new(t.to_vec(), values)
+  }
+
+  /// Sample n paths
+  pub fn paths<D: Dist<f64>>(&self, x0: f64, brownian: &mut Brownian<D>, n: usize, scheme: Scheme) -> Paths {
+    self.integrate(x0, &brownian.paths(n), scheme)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::dist::continuous::Normal;
+  use crate::dist::normal::polar::Polar;
+
+  #[test]
+  fn gbm_strong_convergence() {
+    // geometric Brownian motion has the exact solution S0 exp((mu - sigma^2/2)t + sigma W)
+    let (s0, mu, sigma) = (1.0, 0.1, 0.5);
+    let sde = Sde::new(|_, x| mu * x, |_, x| sigma * x, |_, _| sigma);
+    let t = (1..=16).map(|i| i as f64 / 16.0).collect::<Vec<_>>();
+    let normals = Normal::<Polar<MT19937>>::new(0.0, 1.0, MT19937::new(Some(19937)));
+    let w = Brownian::new(&t, normals).paths(5000);
+    let exact = w.map(|t, w| s0 * ((mu - 0.5 * sigma * sigma) * t + sigma * w).exp());
+    let error = |p: &Paths| p.at(16).iter().zip(exact.at(16)).map(|(x, y)| (x - y).abs()).sum::<f64>() / 5000.0;
+    let euler = error(&sde.integrate(s0, &w, Scheme::EulerMaruyama));
+    let milstein = error(&sde.integrate(s0, &w, Scheme::Milstein));
+    assert!(milstein < 0.25 * euler);
+    assert!(euler < 0.1);
+  }
+
+  #[test]
+  fn additive_noise() {
+    // with constant diffusion the schemes coincide, and dX = dW reproduces the Brownian path
+    let sde = Sde::new(|_, _| 0.0, |_, _| 1.0, |_, _| 0.0);
+    let normals = Normal::<Polar<Xorshift64>>::new(0.0, 1.0, Xorshift64::new(Some(19937)));
+    let mut bm = Brownian::new(&[0.5, 1.0, 1.5], normals);
+    let w = bm.paths(10);
+    assert_eq!(sde.integrate(0.0, &w, Scheme::EulerMaruyama), w);
+    assert_eq!(sde.integrate(0.0, &w, Scheme::Milstein), w);
+    assert_eq!(sde.paths(0.0, &mut bm, 10, Scheme::Milstein).len(), 10);
+  }
+}
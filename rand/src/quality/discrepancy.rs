@@ -0,0 +1,163 @@
+//! quality::discrepancy: measures of the uniformity of point sets in the unit hypercube
+//!
+//! Points are rows of `dim` coordinates in [0,1). The L2 measures are computed in O(N^2 d) time via closed-form
+//! sums (Warnock's formula for the star discrepancy, Hickernell's for the centred and wrap-around variants).
+//! The star (L-infinity) discrepancy is computed exactly by enumerating all critical boxes, which is O(N^(d+1) d)
+//! and only practical in low dimensions.
+
+use crate::gen::{RandomStream, Dimensioned};
+
+/// Draw `n` points of dimension `dim` from any stream
+pub fn points<R: RandomStream>(rng: &mut R, n: usize, dim: usize) -> Vec<Vec<f64>> {
+  assert!(dim > 0);
+  rng.uniforms01(n * dim).chunks(dim).map(|p| p.to_vec()).collect()
+}
+
+/// Draw `n` points from a generator with inherent dimension (i.e. Sobol)
+pub fn points_dimensioned<R: RandomStream + Dimensioned>(rng: &mut R, n: usize) -> Vec<Vec<f64>> {
+  let dim = rng.dim() as usize;
+  points(rng, n, dim)
+}
+
+fn dim(points: &[Vec<f64>]) -> usize {
+  assert!(!points.is_empty());
+  let d = points[0].len();
+  assert!(d > 0 && points.iter().all(|p| p.len() == d));
+  d
+}
+
+/// Exact star discrepancy: the supremum over anchored boxes [0,y) of |volume - proportion of points inside|
+pub fn star(points: &[Vec<f64>]) -> f64 {
+  let d = dim(points);
+  let n = points.len() as f64;
+  if d == 1 {
+    // closed form for one dimension
+    let mut x = points.iter().map(|p| p[0]).collect::<Vec<_>>();
+    x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    return 0.5 / n + x.iter().enumerate().map(|(i, &xi)| (xi - (2 * i + 1) as f64 * 0.5 / n).abs()).fold(0.0, f64::max);
+  }
+  star_enumerate(points, d)
+}
+
+// the supremum is attained at a box whose corner lies on the grid of point coordinates (and 1)
+fn star_enumerate(points: &[Vec<f64>], d: usize) -> f64 {
+  let n = points.len() as f64;
+  let grid = (0..d).map(|k| {
+                     let mut g = points.iter().map(|p| p[k]).collect::<Vec<_>>();
+                     g.push(1.0);
+                     g.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                     g.dedup();
+                     g
+                   })
+                   .collect::<Vec<_>>();
+  let mut index = vec![0; d];
+  let mut corner = vec![0.0; d];
+  let mut sup = 0.0f64;
+  loop {
+    for k in 0..d {
+      corner[k] = grid[k][index[k]];
+    }
+    let volume = corner.iter().product::<f64>();
+    let open = points.iter().filter(|p| p.iter().zip(&corner).all(|(x, y)| x < y)).count() as f64;
+    let closed = points.iter().filter(|p| p.iter().zip(&corner).all(|(x, y)| x <= y)).count() as f64;
+    sup = sup.max(volume - open / n).max(closed / n - volume);
+    // advance the multi-index
+    let mut k = 0;
+    while k < d {
+      index[k] += 1;
+      if index[k] < grid[k].len() { break; }
+      index[k] = 0;
+      k += 1;
+    }
+    if k == d { break; }
+  }
+  sup
+}
+
+/// L2 star discrepancy, by Warnock's formula
+pub fn l2_star(points: &[Vec<f64>]) -> f64 {
+  let d = dim(points) as i32;
+  let n = points.len() as f64;
+  let single = points.iter().map(|p| p.iter().map(|x| 1.0 - x * x).product::<f64>()).sum::<f64>();
+  let pairs = pair_sum(points, |x, y| 1.0 - x.max(y));
+  (3.0f64.powi(-d) - 2.0f64.powi(1 - d) / n * single + pairs / (n * n)).max(0.0).sqrt()
+}
+
+/// Centred L2 discrepancy (Hickernell), invariant under reflections about the centre of the cube
+pub fn centred_l2(points: &[Vec<f64>]) -> f64 {
+  let d = dim(points) as i32;
+  let n = points.len() as f64;
+  let single = points.iter()
+                     .map(|p| p.iter().map(|x| { let c = (x - 0.5).abs(); 1.0 + 0.5 * c - 0.5 * c * c }).product::<f64>())
+                     .sum::<f64>();
+  let pairs = pair_sum(points, |x, y| 1.0 + 0.5 * (x - 0.5).abs() + 0.5 * (y - 0.5).abs() - 0.5 * (x - y).abs());
+  ((13.0f64 / 12.0).powi(d) - 2.0 / n * single + pairs / (n * n)).max(0.0).sqrt()
+}
+
+/// Wrap-around L2 discrepancy (Hickernell), invariant under shifts modulo 1
+pub fn wrap_around_l2(points: &[Vec<f64>]) -> f64 {
+  let d = dim(points) as i32;
+  let n = points.len() as f64;
+  let pairs = pair_sum(points, |x, y| { let a = (x - y).abs(); 1.5 - a * (1.0 - a) });
+  (-(4.0f64 / 3.0).powi(d) + pairs / (n * n)).max(0.0).sqrt()
+}
+
+// sum over all ordered pairs of points of the product over dimensions of f
+fn pair_sum<F: Fn(f64, f64) -> f64>(points: &[Vec<f64>], f: F) -> f64 {
+  points.iter()
+        .map(|p| points.iter().map(|q| p.iter().zip(q).map(|(&x, &y)| f(x, y)).product::<f64>()).sum::<f64>())
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use crate::gen::quasi::*;
+  use compare::assert_close;
+
+  #[test]
+  fn single_point() {
+    let p = vec![vec![0.5]];
+    assert_close!(star(&p), 0.5);
+    // L2 star and centred discrepancy of the midpoint are both 1/sqrt(12)
+    assert_close!(l2_star(&p), (1.0f64 / 12.0).sqrt(), rel = 1e-15);
+    assert_close!(centred_l2(&p), (1.0f64 / 12.0).sqrt(), rel = 1e-15);
+    // a single point is equally (un)representative wherever it is when wrapped
+    assert_close!(wrap_around_l2(&p), wrap_around_l2(&[vec![0.1]]), rel = 1e-15);
+  }
+
+  #[test]
+  fn exact_star() {
+    // the 1d closed form agrees with enumeration of boxes
+    let x = points(&mut MT19937::new(Some(19937)), 50, 1);
+    assert_close!(star(&x), star_enumerate(&x, 1), rel = 1e-14);
+    // regular grid in 2d
+    let g = (0..4).flat_map(|i| (0..4).map(move |j| vec![(i as f64 + 0.5) / 4.0, (j as f64 + 0.5) / 4.0]))
+                  .collect::<Vec<_>>();
+    assert!(star(&g) < 0.25);
+  }
+
+  #[test]
+  fn sobol_vs_mt19937() {
+    const N: usize = 128;
+    let q = points_dimensioned(&mut Sobol::new(2), N);
+    let p = points(&mut MT19937::new(Some(19937)), N, 2);
+    assert!(star(&q) < star(&p));
+    // Sobol star discrepancy is O(log(N)^d/N)
+    assert!(star(&q) < 2.0 * (N as f64).ln().powi(2) / N as f64);
+
+    // higher dimensions, L2 measures only
+    let q = points_dimensioned(&mut Sobol::new(8), 512);
+    let p = points(&mut MT19937::new(Some(19937)), 512, 8);
+    assert!(l2_star(&q) < l2_star(&p));
+    assert!(centred_l2(&q) < centred_l2(&p));
+    assert!(wrap_around_l2(&q) < wrap_around_l2(&p));
+  }
+
+  #[test]
+  #[should_panic]
+  fn empty() {
+    l2_star(&[]);
+  }
+}
@@ -0,0 +1,375 @@
+//! quality: empirical tests of generator output (a miniature TestU01/PractRand)
+//!
+//! Every test consumes values from a `RandomStream` and reports a statistic together with its right-tail p-value
+//! (the probability of a result at least as extreme under the hypothesis that the stream is uniform and independent).
+//! As in TestU01, p-values very close to either 0 or 1 are flagged. The counts of the birthday spacings and collision
+//! tests are discrete, so their p-values are mid-p values, otherwise a count of zero would have a p-value of exactly 1.
+
+use std::fmt;
+
+use crate::gen::RandomStream;
+use crate::dist::{normal, poisson};
+use crate::special::{gamma_p, gamma_q};
+
+pub mod discrepancy;
+
+/// The configurable empirical tests
+#[derive(Debug, Clone, PartialEq)]
+pub enum Test {
+  /// Marsaglia's birthday spacings: `birthdays` birthdays in a year of 2^`days_bits` days, repeated `samples` times
+  BirthdaySpacings { days_bits: u32, birthdays: usize, samples: usize },
+  /// Knuth's gap test: lengths of gaps between visits to [`lo`, `hi`), lumping gaps >= `max_gap`
+  Gap { lo: f64, hi: f64, max_gap: usize, gaps: usize },
+  /// Runs up and down in `n` uniforms
+  Runs { n: usize },
+  /// Serial correlation at `lag` over `n` uniforms
+  SerialCorrelation { n: usize, lag: usize },
+  /// Rank over GF(2) of `matrices` random `size` x `size` binary matrices
+  MatrixRank { size: usize, matrices: usize },
+  /// Knuth's collision test: `balls` balls into 2^`cells_bits` cells, repeated `samples` times
+  Collision { cells_bits: u32, balls: usize, samples: usize },
+  /// Proportion of ones in the bits of `n` 32-bit integers
+  Monobit { n: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+  Pass,
+  Suspicious,
+  Fail,
+}
+
+#[derive(Debug, Clone)]
+pub struct TestResult {
+  pub name: &'static str,
+  pub statistic: f64,
+  pub p_value: f64,
+  pub verdict: Verdict,
+}
+
+/// A collection of tests and the thresholds used to judge their p-values
+#[derive(Debug, Clone)]
+pub struct Battery {
+  pub tests: Vec<Test>,
+  /// p-values outside [suspicious, 1-suspicious] are suspicious
+  pub suspicious: f64,
+  /// p-values outside [fail, 1-fail] are failures
+  pub fail: f64,
+}
+
+impl Test {
+  /// The full set of tests with their default parameters
+  pub fn defaults() -> Vec<Test> {
+    vec![Test::BirthdaySpacings { days_bits: 24, birthdays: 512, samples: 200 },
+         Test::Gap { lo: 0.0, hi: 0.25, max_gap: 16, gaps: 20000 },
+         Test::Runs { n: 100000 },
+         Test::SerialCorrelation { n: 100000, lag: 1 },
+         Test::MatrixRank { size: 32, matrices: 1000 },
+         Test::Collision { cells_bits: 24, balls: 8192, samples: 20 },
+         Test::Monobit { n: 100000 }]
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Test::BirthdaySpacings { .. } => "birthday spacings",
+      Test::Gap { .. } => "gap",
+      Test::Runs { .. } => "runs",
+      Test::SerialCorrelation { .. } => "serial correlation",
+      Test::MatrixRank { .. } => "matrix rank",
+      Test::Collision { .. } => "collision",
+      Test::Monobit { .. } => "monobit",
+    }
+  }
+
+  /// Run the test, returning the statistic and its (right-tail) p-value
+  pub fn run<R: RandomStream>(&self, rng: &mut R) -> (f64, f64) {
+    match *self {
+      Test::BirthdaySpacings { days_bits, birthdays, samples } => birthday_spacings(rng, days_bits, birthdays, samples),
+      Test::Gap { lo, hi, max_gap, gaps } => gap(rng, lo, hi, max_gap, gaps),
+      Test::Runs { n } => runs(rng, n),
+      Test::SerialCorrelation { n, lag } => serial_correlation(rng, n, lag),
+      Test::MatrixRank { size, matrices } => matrix_rank(rng, size, matrices),
+      Test::Collision { cells_bits, balls, samples } => collision(rng, cells_bits, balls, samples),
+      Test::Monobit { n } => monobit(rng, n),
+    }
+  }
+}
+
+impl Battery {
+  pub fn new(tests: Vec<Test>) -> Battery {
+    Battery { tests, suspicious: 1e-3, fail: 1e-10 }
+  }
+
+  pub fn verdict(&self, p: f64) -> Verdict {
+    match p {
+      p if p.is_nan() || p < self.fail || p > 1.0 - self.fail => Verdict::Fail,
+      p if p < self.suspicious || p > 1.0 - self.suspicious => Verdict::Suspicious,
+      _ => Verdict::Pass,
+    }
+  }
+
+  /// Run every test in turn on the same stream
+  pub fn run<R: RandomStream>(&self, rng: &mut R) -> Vec<TestResult> {
+    self.tests
+        .iter()
+        .map(|t| {
+          let (statistic, p_value) = t.run(rng);
+          TestResult { name: t.name(), statistic, p_value, verdict: self.verdict(p_value) }
+        })
+        .collect()
+  }
+}
+
+impl Default for Battery {
+  fn default() -> Self {
+    Battery::new(Test::defaults())
+  }
+}
+
+impl fmt::Display for Verdict {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let s = match self {
+      Verdict::Pass => "pass",
+      Verdict::Suspicious => "suspicious",
+      Verdict::Fail => "FAIL",
+    };
+    f.pad(s)
+  }
+}
+
+impl fmt::Display for TestResult {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:<20}{:>14.4}{:>14.4e}  {}", self.name, self.statistic, self.p_value, self.verdict)
+  }
+}
+
+// right-tail p-value of a standard normal statistic
+fn normal_p(z: f64) -> f64 {
+  normal::cdf(-z, 0.0, 1.0)
+}
+
+// right-tail p-value of a chi-squared statistic
+fn chi_squared_p(x: f64, df: usize) -> f64 {
+  gamma_q(df as f64 / 2.0, x / 2.0)
+}
+
+// the mid-p value P(X > k) + P(X = k)/2 for X ~ Poisson(lambda), which (unlike P(X >= k)) is only close to 1 when
+// k = 0 is unlikely
+fn poisson_mid_p(k: usize, lambda: f64) -> f64 {
+  let pmf = poisson::pmf(k as u32, lambda);
+  if k == 0 { 1.0 - 0.5 * pmf } else { gamma_p(k as f64, lambda) - 0.5 * pmf }
+}
+
+fn chi_squared(observed: &[usize], expected: &[f64]) -> f64 {
+  observed.iter().zip(expected).map(|(&o, &e)| (o as f64 - e).powi(2) / e).sum()
+}
+
+fn birthday_spacings<R: RandomStream>(rng: &mut R, days_bits: u32, birthdays: usize, samples: usize) -> (f64, f64) {
+  assert!(days_bits > 0 && days_bits <= 32 && birthdays > 1 && samples > 0);
+  let days = 2.0f64.powi(days_bits as i32);
+  let mut collisions = 0;
+  for _ in 0..samples {
+    let mut b = rng.uniforms01(birthdays).iter().map(|&u| (u * days) as u64).collect::<Vec<_>>();
+    b.sort_unstable();
+    let mut s = b.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
+    s.sort_unstable();
+    collisions += s.windows(2).filter(|w| w[0] == w[1]).count();
+  }
+  // number of repeated spacings is asymptotically Poisson with mean m^3/4n
+  let lambda = (birthdays as f64).powi(3) / (4.0 * days) * samples as f64;
+  (collisions as f64, poisson_mid_p(collisions, lambda))
+}
+
+fn gap<R: RandomStream>(rng: &mut R, lo: f64, hi: f64, max_gap: usize, gaps: usize) -> (f64, f64) {
+  assert!(lo >= 0.0 && hi <= 1.0 && hi > lo && max_gap > 0 && gaps > 0);
+  const BLOCK: usize = 1024;
+  let mut counts = vec![0; max_gap + 1];
+  let mut n = 0;
+  let mut len = 0;
+  while n < gaps {
+    for u in rng.uniforms01(BLOCK) {
+      if u >= lo && u < hi {
+        counts[len.min(max_gap)] += 1;
+        n += 1;
+        len = 0;
+        if n == gaps { break; }
+      } else {
+        len += 1;
+      }
+    }
+  }
+  let p = hi - lo;
+  let mut expected = (0..max_gap).map(|k| gaps as f64 * p * (1.0 - p).powi(k as i32)).collect::<Vec<_>>();
+  expected.push(gaps as f64 * (1.0 - p).powi(max_gap as i32));
+  let x = chi_squared(&counts, &expected);
+  (x, chi_squared_p(x, max_gap))
+}
+
+fn runs<R: RandomStream>(rng: &mut R, n: usize) -> (f64, f64) {
+  assert!(n > 2);
+  let u = rng.uniforms01(n);
+  let d = u.windows(2).map(|w| w[1] > w[0]).collect::<Vec<_>>();
+  let r = 1 + d.windows(2).filter(|w| w[0] != w[1]).count();
+  let n = n as f64;
+  let z = (r as f64 - (2.0 * n - 1.0) / 3.0) / ((16.0 * n - 29.0) / 90.0).sqrt();
+  (z, normal_p(z))
+}
+
+fn serial_correlation<R: RandomStream>(rng: &mut R, n: usize, lag: usize) -> (f64, f64) {
+  assert!(lag > 0 && n > lag);
+  let u = rng.uniforms01(n);
+  let mean = u.iter().sum::<f64>() / n as f64;
+  let var = u.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+  let cov = u.iter().zip(&u[lag..]).map(|(x, y)| (x - mean) * (y - mean)).sum::<f64>();
+  // under independence the correlation is approximately N(-1/n, 1/n)
+  let n = n as f64;
+  let z = (cov / var + 1.0 / n) * n.sqrt();
+  (z, normal_p(z))
+}
+
+// rank over GF(2) of a matrix stored as one row per integer
+fn gf2_rank(mut rows: Vec<u32>) -> usize {
+  let mut rank = 0;
+  for bit in 0..32 {
+    let mask = 1u32 << bit;
+    if let Some(i) = (rank..rows.len()).find(|&i| rows[i] & mask != 0) {
+      rows.swap(rank, i);
+      let pivot = rows[rank];
+      for (j, row) in rows.iter_mut().enumerate() {
+        if j != rank && *row & mask != 0 {
+          *row ^= pivot;
+        }
+      }
+      rank += 1;
+    }
+  }
+  rank
+}
+
+// probability that a random m x m binary matrix has rank r
+fn rank_probability(m: usize, r: usize) -> f64 {
+  let (m, r) = (m as i32, r as i32);
+  (0..r).fold(2.0f64.powi(r * (2 * m - r) - m * m), |acc, i| {
+    acc * (1.0 - 2.0f64.powi(i - m)).powi(2) / (1.0 - 2.0f64.powi(i - r))
+  })
+}
+
+fn matrix_rank<R: RandomStream>(rng: &mut R, size: usize, matrices: usize) -> (f64, f64) {
+  assert!((4..=32).contains(&size) && matrices > 0);
+  let mask = if size == 32 { !0 } else { (1u32 << size) - 1 };
+  // categories are full rank, full rank - 1, full rank - 2, and anything lower
+  let mut counts = [0; 4];
+  for _ in 0..matrices {
+    let rank = gf2_rank(rng.next_n(size).iter().map(|&r| r & mask).collect());
+    counts[(size - rank).min(3)] += 1;
+  }
+  let mut expected = (0..3).map(|k| matrices as f64 * rank_probability(size, size - k)).collect::<Vec<_>>();
+  expected.push(matrices as f64 - expected.iter().sum::<f64>());
+  let x = chi_squared(&counts, &expected);
+  (x, chi_squared_p(x, 3))
+}
+
+fn collision<R: RandomStream>(rng: &mut R, cells_bits: u32, balls: usize, samples: usize) -> (f64, f64) {
+  assert!(cells_bits > 0 && cells_bits <= 32 && balls > 1 && samples > 0);
+  let cells = 2.0f64.powi(cells_bits as i32);
+  let mut collisions = 0;
+  for _ in 0..samples {
+    let mut c = rng.uniforms01(balls).iter().map(|&u| (u * cells) as u64).collect::<Vec<_>>();
+    c.sort_unstable();
+    c.dedup();
+    collisions += balls - c.len();
+  }
+  // in the sparse case collisions are approximately Poisson, with the exact mean
+  let lambda = (balls as f64 - cells * (1.0 - (1.0 - 1.0 / cells).powi(balls as i32))) * samples as f64;
+  (collisions as f64, poisson_mid_p(collisions, lambda))
+}
+
+fn monobit<R: RandomStream>(rng: &mut R, n: usize) -> (f64, f64) {
+  assert!(n > 0);
+  let ones = rng.next_n(n).iter().map(|r| r.count_ones() as f64).sum::<f64>();
+  let bits = 32.0 * n as f64;
+  let z = (2.0 * ones - bits) / bits.sqrt();
+  (z, normal_p(z))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::gen::pseudo::*;
+  use compare::assert_close;
+
+  // a deliberately poor generator: a Weyl sequence
+  struct Counter {
+    r: u32,
+  }
+
+  impl RandomStream for Counter {
+    fn next_n(&mut self, n: usize) -> Vec<u32> {
+      (0..n).map(|_| { self.r = self.r.wrapping_add(0x9E3779B9); self.r }).collect()
+    }
+
+    fn uniforms01(&mut self, n: usize) -> Vec<f64> {
+      self.next_n(n).iter().map(|&r| r as f64 / 2.0f64.powi(32)).collect()
+    }
+  }
+
+  #[test]
+  fn special_functions() {
+    // chi-squared with 2 df has survival function exp(-x/2)
+    assert_close!(chi_squared_p(3.0, 2), (-1.5f64).exp(), rel = 1e-12);
+    assert_close!(chi_squared_p(30.0, 2), (-15.0f64).exp(), rel = 1e-12);
+    // P(X >= 1) - P(X = 1)/2 for Poisson is 1 - (1 + lambda/2) exp(-lambda)
+    assert_close!(poisson_mid_p(1, 2.0), 1.0 - 2.0 * (-2.0f64).exp(), rel = 1e-12);
+    assert_close!(poisson_mid_p(0, 2.0), 1.0 - 0.5 * (-2.0f64).exp(), rel = 1e-12);
+    // no collisions when few are expected is normal
+    let battery = Battery::default();
+    assert_eq!(battery.verdict(poisson_mid_p(0, 1e-3)), Verdict::Pass);
+    assert_eq!(battery.verdict(poisson_mid_p(0, 30.0)), Verdict::Fail);
+  }
+
+  #[test]
+  fn rank() {
+    assert_eq!(gf2_rank(vec![1, 2, 4, 8]), 4);
+    assert_eq!(gf2_rank(vec![1, 2, 3, 8]), 3);
+    assert_eq!(gf2_rank(vec![0, 0, 0, 0]), 0);
+    // known values for 32x32 matrices
+    assert!((rank_probability(32, 32) - 0.2888).abs() < 1e-4);
+    assert!((rank_probability(32, 31) - 0.5776).abs() < 1e-4);
+    assert!((rank_probability(32, 30) - 0.1284).abs() < 1e-4);
+    let total = (0..=6).map(|r| rank_probability(6, r)).sum::<f64>();
+    assert!((total - 1.0).abs() < 1e-12);
+  }
+
+  #[test]
+  fn verdicts() {
+    let battery = Battery::default();
+    assert_eq!(battery.verdict(0.5), Verdict::Pass);
+    assert_eq!(battery.verdict(1e-4), Verdict::Suspicious);
+    assert_eq!(battery.verdict(1.0 - 1e-4), Verdict::Suspicious);
+    assert_eq!(battery.verdict(1e-12), Verdict::Fail);
+    assert_eq!(battery.verdict(f64::NAN), Verdict::Fail);
+  }
+
+  #[test]
+  fn mt19937() {
+    let battery = Battery::default();
+    for result in battery.run(&mut MT19937::new(Some(19937))) {
+      assert_ne!(result.verdict, Verdict::Fail);
+    }
+  }
+
+  #[test]
+  fn xorshift64() {
+    let battery = Battery::default();
+    for result in battery.run(&mut Xorshift64::new(Some(19937))) {
+      assert_ne!(result.verdict, Verdict::Fail);
+    }
+  }
+
+  #[test]
+  fn counter() {
+    let battery = Battery::default();
+    let results = battery.run(&mut Counter { r: 0 });
+    assert!(results.iter().any(|r| r.verdict == Verdict::Fail));
+  }
+}
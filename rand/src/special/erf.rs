@@ -0,0 +1,234 @@
+//! special::erf: error functions, ported from fdlibm's s_erf.c (accurate to under 1 ULP), with the scaled
+//! complementary error function and the inverses
+#![allow(clippy::excessive_precision)]
+
+use crate::special::ndtri;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+/*
+ * ====================================================
+ * Copyright (C) 1993 by Sun Microsystems, Inc. All rights reserved.
+ *
+ * Developed at SunPro, a Sun Microsystems, Inc. business.
+ * Permission to use, copy, modify, and distribute this
+ * software is freely granted, provided that this notice
+ * is preserved.
+ * ====================================================
+ */
+
+const ERX: f64 = 8.45062911510467529297e-01;
+// erf on [0,0.84375]
+const EFX8: f64 = 1.02703333676410069053e+00;
+const PP: [f64; 5] = [1.28379167095512558561e-01, -3.25042107247001499370e-01, -2.84817495755985104766e-02,
+                      -5.77027029648944159157e-03, -2.37630166566501626084e-05];
+const QQ: [f64; 5] = [3.97917223959155352819e-01, 6.50222499887672944485e-02, 5.08130628187576562776e-03,
+                      1.32494738004321644526e-04, -3.96022827877536812320e-06];
+// erf on [0.84375,1.25]
+const PA: [f64; 7] = [-2.36211856075265944077e-03, 4.14856118683748331666e-01, -3.72207876035701323847e-01,
+                      3.18346619901161753674e-01, -1.10894694282396677476e-01, 3.54783043256182359371e-02,
+                      -2.16637559486879084300e-03];
+const QA: [f64; 6] = [1.06420880400844228286e-01, 5.40397917702171048937e-01, 7.18286544141962662868e-02,
+                      1.26171219808761642112e-01, 1.36370839120290507362e-02, 1.19844998467991074170e-02];
+// erfc on [1.25,1/0.35]
+const RA: [f64; 8] = [-9.86494403484714822705e-03, -6.93858572707181764372e-01, -1.05586262253232909814e+01,
+                      -6.23753324503260060396e+01, -1.62396669462573470355e+02, -1.84605092906711035994e+02,
+                      -8.12874355063065934246e+01, -9.81432934416914548592e+00];
+const SA: [f64; 8] = [1.96512716674392571292e+01, 1.37657754143519042600e+02, 4.34565877475229228821e+02,
+                      6.45387271733267880336e+02, 4.29008140027567833386e+02, 1.08635005541779435134e+02,
+                      6.57024977031928170135e+00, -6.04244152148580987438e-02];
+// erfc on [1/0.35,28]
+const RB: [f64; 7] = [-9.86494292470009928597e-03, -7.99283237680523006574e-01, -1.77579549177547519889e+01,
+                      -1.60636384855821916062e+02, -6.37566443368389627722e+02, -1.02509513161107724954e+03,
+                      -4.83519191608651397019e+02];
+const SB: [f64; 7] = [3.03380607434824582924e+01, 3.25792512996573918826e+02, 1.53672958608443695994e+03,
+                      3.19985821950859553908e+03, 2.55305040643316442583e+03, 4.74528541206955367215e+02,
+                      -2.24409524465858183362e+01];
+
+const TINY: f64 = 2.2250738585072014e-308;
+
+// a0 + a1 x + a2 x^2 + ...
+fn poly(a: &[f64], x: f64) -> f64 {
+  a.iter().rev().fold(0.0, |acc, &c| acc * x + c)
+}
+
+// 1 + a0 x + a1 x^2 + ...
+fn poly1(a: &[f64], x: f64) -> f64 {
+  1.0 + x * poly(a, x)
+}
+
+// (erf(x) - x) / x for |x| < 0.84375
+fn small(x: f64) -> f64 {
+  let z = x * x;
+  poly(&PP, z) / poly1(&QQ, z)
+}
+
+// erf(|x|) - erx for |x| in [0.84375,1.25]
+fn near_one(x: f64) -> f64 {
+  let s = x.abs() - 1.0;
+  poly(&PA, s) / poly1(&QA, s)
+}
+
+// log(erfc(x) x) + x^2 + 0.5625 for x in [1.25,28]
+fn tail(x: f64) -> f64 {
+  let s = 1.0 / (x * x);
+  if x < 1.0 / 0.35 { poly(&RA, s) / poly1(&SA, s) } else { poly(&RB, s) / poly1(&SB, s) }
+}
+
+// erfc(|x|) for |x| in [0.84375,28]
+fn erfc_mid(x: f64) -> f64 {
+  let x = x.abs();
+  if x < 1.25 {
+    return 1.0 - ERX - near_one(x);
+  }
+  // split x^2 so that exp(-x^2) is computed without cancellation
+  let z = f64::from_bits(x.to_bits() & 0xffff_ffff_0000_0000);
+  (-z * z - 0.5625).exp() * ((z - x) * (z + x) + tail(x)).exp() / x
+}
+
+/// The error function 2/sqrt(pi) int_0^x exp(-t^2) dt
+pub fn erf(x: f64) -> f64 {
+  if x.is_nan() {
+    return x;
+  }
+  let a = x.abs();
+  if a < 0.84375 {
+    if a < 2.0f64.powi(-28) {
+      // avoid underflow
+      return 0.125 * (8.0 * x + EFX8 * x);
+    }
+    return x + x * small(x);
+  }
+  let y = if a < 6.0 { 1.0 - erfc_mid(a) } else { 1.0 - TINY };
+  y.copysign(x)
+}
+
+/// The complementary error function 1 - erf(x), computed without cancellation for large x
+pub fn erfc(x: f64) -> f64 {
+  if x.is_nan() {
+    return x;
+  }
+  let a = x.abs();
+  if a < 0.84375 {
+    if a < 2.0f64.powi(-56) {
+      return 1.0 - x;
+    }
+    let y = small(x);
+    return if x < 0.25 { 1.0 - (x + x * y) } else { 0.5 - (x - 0.5 + x * y) };
+  }
+  if a < 28.0 {
+    return if x < 0.0 { 2.0 - erfc_mid(a) } else { erfc_mid(a) };
+  }
+  if x < 0.0 { 2.0 - TINY } else { 0.0 }
+}
+
+/// The scaled complementary error function exp(x^2) erfc(x), which does not underflow for large x
+pub fn erfcx(x: f64) -> f64 {
+  if x.is_nan() {
+    return x;
+  }
+  if x < 0.84375 {
+    // exp(x^2) overflows for x < -26.6
+    return if x < -26.7 { f64::INFINITY } else { (x * x).exp() * erfc(x) };
+  }
+  if x < 1.25 {
+    return (x * x).exp() * erfc_mid(x);
+  }
+  if x < 28.0 {
+    // the exp(-x^2) factor of erfc cancels exactly
+    return (tail(x) - 0.5625).exp() / x;
+  }
+  // asymptotic expansion 1/(x sqrt(pi)) sum_k (-1)^k (2k-1)!! / (2x^2)^k
+  let s = 0.5 / (x * x);
+  let mut term = 1.0;
+  let mut sum = 1.0;
+  for k in 1..12 {
+    term *= -((2 * k - 1) as f64) * s;
+    sum += term;
+  }
+  sum * core::f64::consts::FRAC_2_SQRT_PI / (2.0 * x)
+}
+
+// one Halley step for erfc(x) = q, or erf(x) = y when complement is false
+fn halley(x: f64, target: f64, complement: bool) -> f64 {
+  // f'(x) = -/+ 2/sqrt(pi) exp(-x^2), f''(x) = -2x f'(x)
+  let u = if complement {
+    -(erfc(x) - target) / (core::f64::consts::FRAC_2_SQRT_PI * (-x * x).exp())
+  } else {
+    (erf(x) - target) / (core::f64::consts::FRAC_2_SQRT_PI * (-x * x).exp())
+  };
+  x - u / (1.0 + x * u)
+}
+
+/// The inverse of erfc on [0, 2]
+pub fn erfc_inv(q: f64) -> f64 {
+  assert!((0.0..=2.0).contains(&q));
+  if q == 0.0 { return f64::INFINITY; }
+  if q == 2.0 { return f64::NEG_INFINITY; }
+  // erfc(x) = 2 Phi(-x sqrt(2))
+  let x = -ndtri(0.5 * q) * core::f64::consts::FRAC_1_SQRT_2;
+  halley(x, q, true)
+}
+
+/// The inverse of erf on [-1, 1]
+pub fn erf_inv(y: f64) -> f64 {
+  assert!((-1.0..=1.0).contains(&y));
+  if y.abs() >= 0.5 {
+    // 1 - |y| is exact
+    return erfc_inv(1.0 - y.abs()).copysign(y);
+  }
+  // leading terms of the Maclaurin series, then refine
+  let x = 0.5 * core::f64::consts::PI.sqrt() * (y + core::f64::consts::PI / 12.0 * y * y * y);
+  halley(halley(x, y, false), y, false)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use compare::assert_close;
+
+  #[test]
+  fn values() {
+    // reference values computed to 30 digits
+    assert_eq!(erf(0.0), 0.0);
+    assert_close!(erf(0.5), 0.520499877813046537682746653892, rel = 2e-16);
+    assert_close!(erf(1.0), 0.842700792949714869341220635083, rel = 2e-16);
+    assert_close!(erf(-2.0), -0.995322265018952734162069256367, rel = 2e-16);
+    assert_close!(erfc(0.5), 0.479500122186953462317253346108, rel = 2e-16);
+    assert_close!(erfc(2.0), 4.67773498104726583793074363275e-3, rel = 4e-16);
+    assert_close!(erfc(10.0), 2.08848758376254475700078629495e-45, rel = 4e-16);
+    assert_close!(erfc(26.0), 5.663192408856142846475727e-296, rel = 4e-16);
+    assert_close!(erfc(-1.0), 1.84270079294971486934122063508, rel = 2e-16);
+    assert_eq!(erfc(30.0), 0.0);
+    assert_eq!(erf(f64::INFINITY), 1.0);
+    assert_eq!(erfc(f64::NEG_INFINITY), 2.0);
+    assert!(erf(f64::NAN).is_nan());
+  }
+
+  #[test]
+  fn scaled() {
+    assert_close!(erfcx(0.0), 1.0);
+    assert_close!(erfcx(1.0), 0.427583576155807004410750344490, rel = 4e-16);
+    assert_close!(erfcx(5.0), 0.110704637733068626370212086492, rel = 4e-16);
+    assert_close!(erfcx(100.0), 5.64161378298943290355645700695e-3, rel = 4e-16);
+    assert_close!(erfcx(-1.0), 5.00898008076228346630982459821, rel = 4e-16);
+    // continuity across the branches
+    for &x in &[0.84375, 1.25, 28.0] {
+      assert_close!(erfcx(x - 1e-12), erfcx(x), rel = 1e-11);
+    }
+  }
+
+  #[test]
+  fn inverse() {
+    for &x in &[-1.5, -0.3, 1e-10, 0.1, 0.7, 2.0] {
+      assert_close!(erf_inv(erf(x)), x, rel = 1e-15);
+    }
+    for &x in &[-0.3, 0.1, 0.7, 5.5, 20.0] {
+      assert_close!(erfc_inv(erfc(x)), x, rel = 1e-15);
+    }
+    assert_close!(erfc_inv(1e-300), 26.209469960516124, rel = 1e-15);
+    assert_eq!(erf_inv(0.0), 0.0);
+    assert_eq!(erfc_inv(0.0), f64::INFINITY);
+  }
+}
@@ -0,0 +1,18 @@
+//! special: special functions needed for the CDFs and quantiles of common distributions
+//!
+//! Everything is implemented in Rust rather than linked from the platform's C maths library. The error functions
+//! and log gamma are ports of fdlibm and are accurate to about 1 ULP; the incomplete gamma and beta functions are
+//! accurate to a few ULP for moderate parameters, with relative error growing slowly with the size of the
+//! parameters. The inverse error functions are refined with Halley's method, and the inverse incomplete gamma and
+//! beta functions found by Newton's method safeguarded by bisection, to close to the accuracy of the forward functions.
+//! The inverse normal CDF `ndtri` is a rational approximation accurate to about 1e-16.
+
+mod erf;
+mod gamma;
+mod beta;
+mod ndtri;
+
+pub use erf::{erf, erfc, erfcx, erf_inv, erfc_inv};
+pub use gamma::{ln_gamma, gamma_p, gamma_q, gamma_p_inv, gamma_q_inv, digamma, trigamma};
+pub use beta::{ln_beta, beta_inc, beta_inc_inv};
+pub use ndtri::ndtri;
@@ -0,0 +1,63 @@
+//! special::ndtri: the inverse of the standard normal CDF, by Wichura's algorithm AS241 (PPND16), Applied
+//! Statistics 37(3), 1988, with relative error around 1e-16
+#![allow(clippy::excessive_precision)]
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float;
+
+const A: [f64; 8] = [3.387132872796366608, 133.14166789178437745, 1971.5909503065514427, 13731.693765509461125,
+                     45921.953931549871457, 67265.770927008700853, 33430.575583588128105, 2509.0809287301226727];
+const B: [f64; 8] = [1.0, 42.313330701600911252, 687.1870074920579083, 5394.1960214247511077,
+                     21213.794301586595867, 39307.89580009271061, 28729.085735721942674, 5226.495278852545925];
+const C: [f64; 8] = [1.42343711074968357734, 4.6303378461565452959, 5.7694972214606914055, 3.64784832476320460504,
+                     1.27045825245236838258, 0.24178072517745061177, 0.0227238449892691845833,
+                     7.7454501427834140764e-4];
+const D: [f64; 8] = [1.0, 2.05319162663775882187, 1.6763848301838038494, 0.68976733498510000455,
+                     0.14810397642748007459, 0.0151986665636164571966, 5.475938084995344946e-4,
+                     1.05075007164441684324e-9];
+const E: [f64; 8] = [6.6579046435011037772, 5.4637849111641143699, 1.7848265399172913358, 0.29656057182850489123,
+                     0.026532189526576123093, 0.0012426609473880784386, 2.71155556874348757815e-5,
+                     2.01033439929228813265e-7];
+const F: [f64; 8] = [1.0, 0.59983220655588793769, 0.13692988092273580531, 0.0148753612908506148525,
+                     7.868691311456132591e-4, 1.8463183175100546818e-5, 1.4215117583164458887e-7,
+                     2.04426310338993978564e-15];
+
+fn rational(num: &[f64; 8], den: &[f64; 8], r: f64) -> f64 {
+  num.iter().rev().fold(0.0, |acc, &c| acc * r + c) / den.iter().rev().fold(0.0, |acc, &c| acc * r + c)
+}
+
+/// The inverse of the standard normal CDF on [0, 1]
+pub fn ndtri(p: f64) -> f64 {
+  assert!((0.0..=1.0).contains(&p));
+  if p == 0.0 { return f64::NEG_INFINITY; }
+  if p == 1.0 { return f64::INFINITY; }
+
+  let q = p - 0.5;
+  if q.abs() <= 0.425 {
+    /* Central region: rational approximation in q^2 */
+    return q * rational(&A, &B, 0.180625 - q * q);
+  }
+  /* Tails: rational approximations in sqrt(-log(min(p, 1-p))) */
+  let r = (-(if q < 0.0 { p } else { 1.0 - p }).ln()).sqrt();
+  let u = if r <= 5.0 { rational(&C, &D, r - 1.6) } else { rational(&E, &F, r - 5.0) };
+  if q < 0.0 { -u } else { u }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use compare::assert_close;
+
+  #[test]
+  fn values() {
+    assert_eq!(ndtri(0.5), 0.0);
+    assert_eq!(ndtri(0.0), f64::NEG_INFINITY);
+    assert_eq!(ndtri(1.0), f64::INFINITY);
+    assert_close!(ndtri(0.975), 1.959963984540054, rel = 4.0 * f64::EPSILON);
+    assert_close!(ndtri(1e-300), -37.04709629936119923655, rel = 4.0 * f64::EPSILON);
+    for &p in &[1e-10, 0.01, 0.2, 0.4] {
+      assert_close!(ndtri(p), -ndtri(1.0 - p), rel = 1e-8);
+    }
+  }
+}
@@ -1,5 +1,6 @@
 //mod threadpool;
 use std::thread;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -17,34 +18,48 @@ impl<F: FnOnce()> FnBox for F {
 enum Message { NewJob(Job), Terminate, }
 
 struct Worker {
-  id: usize,
   thread: Option<thread::JoinHandle<()>>,
 }
 
 impl Worker {
   fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Message>>>) -> Worker {
-    //let thread = thread::spawn(|| { receiver; });
-    let thread = thread::spawn(move || {
+    let thread = thread::Builder::new().name(format!("threadpool-{}", id)).spawn(move || {
       loop {
-        let message = receiver.lock().unwrap().recv().unwrap();
+        // the lock is released before the job runs
+        let message = receiver.lock().unwrap().recv();
         match message {
-          Message::NewJob(job) => {
-            println!("Worker {} got a job; executing.", id);
-            job.call_box();
-          },
-          Message::Terminate => {
-            println!("Worker {} was told to terminate.", id);
-            break;
+          // a panicking job mustn't take the worker down with it (submit passes the panic on to the handle)
+          Ok(Message::NewJob(job)) => {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| job.call_box()));
           },
+          Ok(Message::Terminate) | Err(_) => break,
         }
       }
-    });
-    Worker { id, thread: Some(thread), }
+    }).unwrap();
+    Worker { thread: Some(thread), }
   }
 }
 
 type Job = Box<dyn FnBox + Send + 'static>;
 
+/// Handle to the result of a job submitted to the pool
+pub struct JobHandle<T> {
+  receiver: mpsc::Receiver<thread::Result<T>>,
+}
+
+impl<T> JobHandle<T> {
+  /// Block until the job has completed and return its result.
+  ///
+  /// # Panics if
+  /// the job panicked, with the job's panic payload
+  pub fn join(self) -> T {
+    match self.receiver.recv().expect("job terminated without returning a result") {
+      Ok(result) => result,
+      Err(payload) => panic::resume_unwind(payload),
+    }
+  }
+}
+
 pub struct ThreadPool {
   workers: Vec<Worker>,
   sender: mpsc::Sender<Message>,
@@ -75,19 +90,28 @@ impl ThreadPool {
     let job = Box::new(f);
     self.sender.send(Message::NewJob(job)).unwrap();
   }
+
+  /// Execute a job that returns a value, which can be retrieved from the returned handle.
+  pub fn submit<F, T>(&self, f: F) -> JobHandle<T>
+    where F: FnOnce() -> T + Send + 'static,
+          T: Send + 'static
+  {
+    let (sender, receiver) = mpsc::channel();
+    // the handle may have been dropped, in which case the result is discarded
+    self.execute(move || { let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f))); });
+    JobHandle { receiver }
+  }
 }
 
 impl Drop for ThreadPool {
+  // nothing here panics, as the pool may be dropped while unwinding
   fn drop(&mut self) {
-    println!("Sending terminate message to all workers.");
     for _ in &mut self.workers {
-      self.sender.send(Message::Terminate).unwrap();
+      let _ = self.sender.send(Message::Terminate);
     }
-    println!("Shutting down all workers.");
     for worker in &mut self.workers {
-      println!("Shutting down worker {}", worker.id);
       if let Some(thread) = worker.thread.take() {
-        thread.join().unwrap();
+        let _ = thread.join();
       }
     }
   }
@@ -95,8 +119,33 @@ impl Drop for ThreadPool {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn submit() {
+        let pool = ThreadPool::new(3);
+        let handles = (0..10).map(|i| pool.submit(move || i * i)).collect::<Vec<_>>();
+        let results = handles.into_iter().map(|h| h.join()).collect::<Vec<_>>();
+        assert_eq!(results, (0..10).map(|i| i * i).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn panicking_job() {
+        let pool = ThreadPool::new(2);
+        pool.execute(|| panic!("execute"));
+        let handles = (0..4).map(|i| pool.submit(move || if i == 1 { panic!("submit") } else { i }))
+                            .collect::<Vec<_>>();
+        // join passes the job's panic on
+        let join = |h: JobHandle<i32>| panic::catch_unwind(AssertUnwindSafe(|| h.join()))
+                                              .map_err(|e| *e.downcast::<&str>().unwrap());
+        let results = handles.into_iter().map(join).collect::<Vec<_>>();
+        assert_eq!(results, [Ok(0), Err("submit"), Ok(2), Ok(3)]);
+        // the workers survive
+        assert_eq!((0..4).map(|i| pool.submit(move || i)).map(|h| h.join()).sum::<i32>(), 6);
+    }
 }
@@ -1,4 +1,6 @@
 
+use compare::Tolerance;
+
 #[derive(Copy, Clone, Debug)]
 pub struct Vector {
   pub i: f64,
@@ -8,6 +10,10 @@ pub struct Vector {
 
 const EPS: f64 = 1.0 / (1u32 << 20) as f64; // ~6 dp
 
+fn tolerance() -> Tolerance {
+  Tolerance::exact().abs(EPS)
+}
+
 impl Vector {
 
   pub fn new(i: f64, j: f64, k: f64) -> Vector {
@@ -27,7 +33,7 @@ impl Vector {
   }
 
   pub fn is_normalized(self: &Vector) -> bool {
-    (self.modulus() - 1.0).abs() < EPS
+    tolerance().close(self.modulus(), 1.0)
   }
 
   pub fn zero() -> Vector {
@@ -62,13 +68,13 @@ pub fn cross(lhs: &Vector, rhs: &Vector) -> Vector {
 pub fn parallel(lhs: &Vector, rhs: &Vector) -> bool {
   lhs.modulus() > 0.0 &&
   rhs.modulus() > 0.0 &&
-  cross(lhs, rhs).modulus() < EPS
+  tolerance().close(cross(lhs, rhs).modulus(), 0.0)
 }
 
 pub fn perpendicular(lhs: &Vector, rhs: &Vector) -> bool {
   lhs.modulus() > 0.0 &&
   rhs.modulus() > 0.0 &&
-  dot(lhs, rhs).abs() < EPS
+  tolerance().close(dot(lhs, rhs), 0.0)
 }
 
 
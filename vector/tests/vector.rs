@@ -1,10 +1,7 @@
 #[cfg(test)]
 mod tests {
   use vector::{Vector, dot, cross, parallel, perpendicular};
-
-  fn are_equal(a: f64, b: f64) -> bool {
-    (a - b).abs() < 0.000001
-  }
+  use compare::assert_close;
 
   #[test]
   fn constructor_test() {
@@ -14,18 +11,18 @@ mod tests {
     assert_eq!(3.0, v.k, "Value of third argument passed into struct constructor should be assigned to \"k\"");
 
     let v = Vector::new(-4.0 / 3.0, 40.0 / 27.0, 68.0 / 69.0);
-    assert!(are_equal(v.i, -4.0 / 3.0), "\"i\" of Vector should equal -4 / 3");
-    assert!(are_equal(v.j, 40.0 / 27.0), "\"j\" of Vector should equal 40 / 27");
-    assert!(are_equal(v.k, 68.0 / 69.0), "\"k\" of Vector should equal 68 / 69");
+    assert_close!(v.i, -4.0 / 3.0, abs = 1e-6);
+    assert_close!(v.j, 40.0 / 27.0, abs = 1e-6);
+    assert_close!(v.k, 68.0 / 69.0, abs = 1e-6);
   }
 
   #[test]
   fn modulus_test() {
     let v = Vector::new(6.0, 10.0, -3.0);
-    assert!(are_equal(v.modulus(), (145 as f64).sqrt()));
+    assert_close!(v.modulus(), 145f64.sqrt(), abs = 1e-6);
     // note operator precedence here
-    assert!(are_equal(-v.modulus(), -(145 as f64).sqrt()));
-    assert!(are_equal((-v).modulus(), (145 as f64).sqrt()));
+    assert_close!(-v.modulus(), -145f64.sqrt(), abs = 1e-6);
+    assert_close!((-v).modulus(), 145f64.sqrt(), abs = 1e-6);
   }
 
   #[test]
@@ -44,33 +41,33 @@ mod tests {
   fn addsub_test() {
     let v = Vector::new(3.0, 7.0 / 2.0, -3.0 / 2.0);
     let s: Vector = v + &Vector::new(-27.0, 3.0, 4.0);
-    assert!(are_equal(s.i, -24.0));
-    assert!(are_equal(s.j, 13.0 / 2.0));
-    assert!(are_equal(s.k, 5.0 / 2.0));
+    assert_close!(s.i, -24.0, abs = 1e-6);
+    assert_close!(s.j, 13.0 / 2.0, abs = 1e-6);
+    assert_close!(s.k, 5.0 / 2.0, abs = 1e-6);
     let s: Vector = s - &Vector::new(-27.0, 3.0, 4.0);
-    assert!(are_equal(s.i, 3.0));
-    assert!(are_equal(s.j, 7.0 / 2.0));
-    assert!(are_equal(s.k, -3.0 / 2.0));
+    assert_close!(s.i, 3.0, abs = 1e-6);
+    assert_close!(s.j, 7.0 / 2.0, abs = 1e-6);
+    assert_close!(s.k, -3.0 / 2.0, abs = 1e-6);
   }
 
   #[test]
   fn muldiv_test() {
     let v = Vector::new(1.0 / 3.0, 177.0 / 27.0, -99.0);
     let e = v * (-3.0 / 7.0);
-    assert!(are_equal(e.i, -1.0 / 7.0));
-    assert!(are_equal(e.j, -59.0 / 21.0));
-    assert!(are_equal(e.k, 297.0 / 7.0));
+    assert_close!(e.i, -1.0 / 7.0, abs = 1e-6);
+    assert_close!(e.j, -59.0 / 21.0, abs = 1e-6);
+    assert_close!(e.k, 297.0 / 7.0, abs = 1e-6);
     let e = e / (-3.0 / 7.0);
-    assert!(are_equal(e.i, 1.0 / 3.0));
-    assert!(are_equal(e.j, 177.0 / 27.0));
-    assert!(are_equal(e.k, -99.0));
+    assert_close!(e.i, 1.0 / 3.0, abs = 1e-6);
+    assert_close!(e.j, 177.0 / 27.0, abs = 1e-6);
+    assert_close!(e.k, -99.0, abs = 1e-6);
   }
 
   #[test]
   fn dot_test() {
     let v = Vector::new(-99.0 / 71.0, 22.0 / 23.0, 45.0);
-    assert!(are_equal(dot(&v, &Vector::new(-5.0, 4.0, 7.0)), 325.7979179));
-    assert!(are_equal(dot(&v, &Vector::new(5.0, -4.0, -7.0)), -325.7979179));
+    assert_close!(dot(&v, &Vector::new(-5.0, 4.0, 7.0)), 325.7979179, abs = 1e-6);
+    assert_close!(dot(&v, &Vector::new(5.0, -4.0, -7.0)), -325.7979179, abs = 1e-6);
   }
 
   #[test]
@@ -79,12 +76,12 @@ mod tests {
     let b = Vector::new(4.0, 6.0, 5.0);
     let a_cross_b = cross(&a, &b);
     let b_cross_a = cross(&b, &a);
-    assert!(are_equal(a_cross_b.i, -13.0));
-    assert!(are_equal(a_cross_b.j, 2.0));
-    assert!(are_equal(a_cross_b.k, 8.0));
-    assert!(are_equal(b_cross_a.i, 13.0));
-    assert!(are_equal(b_cross_a.j, -2.0));
-    assert!(are_equal(b_cross_a.k, -8.0));
+    assert_close!(a_cross_b.i, -13.0, abs = 1e-6);
+    assert_close!(a_cross_b.j, 2.0, abs = 1e-6);
+    assert_close!(a_cross_b.k, 8.0, abs = 1e-6);
+    assert_close!(b_cross_a.i, 13.0, abs = 1e-6);
+    assert_close!(b_cross_a.j, -2.0, abs = 1e-6);
+    assert_close!(b_cross_a.k, -8.0, abs = 1e-6);
   }
 
   #[test]
@@ -116,15 +113,15 @@ mod tests {
   fn normalize_test() {
     let v = Vector::new(-1.0, -1.0, 1.0);
     let u = v.normalize();
-    assert!(are_equal(u.modulus(), 1.0));
-    assert!(are_equal(u.i, -1.0 / (3.0 as f64).sqrt()));
-    assert!(are_equal(u.j, -1.0 / (3.0 as f64).sqrt()));
-    assert!(are_equal(u.k, 1.0 / (3.0 as f64).sqrt()));
+    assert_close!(u.modulus(), 1.0, abs = 1e-6);
+    assert_close!(u.i, -1.0 / 3.0f64.sqrt(), abs = 1e-6);
+    assert_close!(u.j, -1.0 / 3.0f64.sqrt(), abs = 1e-6);
+    assert_close!(u.k, 1.0 / 3.0f64.sqrt(), abs = 1e-6);
   }
 
   #[test]
   fn is_normalized_test() {
-    let a = Vector::new(-1.0 / (2.0 as f64).sqrt(), 0.0, 1.0 / (2.0 as f64).sqrt());
+    let a = Vector::new(-1.0 / 2.0f64.sqrt(), 0.0, 1.0 / 2.0f64.sqrt());
     let b = Vector::new(1.0, 1.0, 1.0);
     assert!(a.is_normalized());
     assert!(!b.is_normalized());